@@ -0,0 +1,61 @@
+use super::math::Vec4;
+
+// Conversions between linear values and piecewise sRGB encoding used by formats with limited range.
+// Implementation based on: https://www.w3.org/Graphics/Color/srgb
+fn srgb_channel_to_linear(value: f32) -> f32
+{
+    if value <= 0.04045
+    {
+        value / 12.92
+    }
+    else
+    {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_channel_to_srgb(value: f32) -> f32
+{
+    if value <= 0.003_130_8
+    {
+        value * 12.92
+    }
+    else
+    {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+pub fn srgb_to_linear(color: Vec4) -> Vec4
+{
+    // Alpha is always stored as linear coverage.
+    Vec4::new(srgb_channel_to_linear(color.get_r()), srgb_channel_to_linear(color.get_g()), srgb_channel_to_linear(color.get_b()), color.get_a())
+}
+
+pub fn linear_to_srgb(color: Vec4) -> Vec4
+{
+    Vec4::new(linear_channel_to_srgb(color.get_r()), linear_channel_to_srgb(color.get_g()), linear_channel_to_srgb(color.get_b()), color.get_a())
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn srgb()
+    {
+        assert_eq!(srgb_to_linear(Vec4::black()), Vec4::black());
+        assert!((srgb_to_linear(Vec4::white()).get_r() - 1.0).abs() < 0.0001);
+        assert!((srgb_to_linear(Vec4::new(0.5, 0.02, 0.5, 0.5)).get_r() - 0.214).abs() < 0.001);
+        assert!((srgb_to_linear(Vec4::new(0.5, 0.02, 0.5, 0.5)).get_g() - 0.02 / 12.92).abs() < 0.0001);
+        assert_eq!(srgb_to_linear(Vec4::new(0.5, 0.02, 0.5, 0.5)).get_a(), 0.5);
+
+        for value in &[0.001, 0.02, 0.2, 0.5, 0.9]
+        {
+            let color = Vec4::new(*value, *value, *value, 1.0);
+            assert!((srgb_to_linear(linear_to_srgb(color)).get_r() - value).abs() < 0.0001);
+        }
+    }
+}
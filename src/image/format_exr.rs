@@ -0,0 +1,835 @@
+use std::io::{ Read, Write, BufRead, BufReader, BufWriter, Error, ErrorKind };
+use std::convert::TryFrom;
+use std::fs::OpenOptions;
+use std::path::Path;
+
+use super::math::Vec4;
+use super::surface::Surface;
+use super::writer::Format;
+use super::writer;
+use super::reader;
+
+// Implementation based on: https://www.openexr.com/documentation/openexrfilelayout.pdf
+// Image is stored as single part scanline file, with values written as they are, without gamma or clamping.
+const MAGIC: [u8; 4] = [0x76, 0x2f, 0x31, 0x01];
+const VERSION: u32 = 2;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PixelType
+{
+    Half,
+    Float
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Compression
+{
+    None,
+    Zip
+}
+
+pub struct FormatEXR
+{
+    pixel_type: PixelType,
+    compression: Compression
+}
+
+impl Default for FormatEXR
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            pixel_type: PixelType::Half,
+            compression: Compression::Zip
+        }
+    }
+}
+
+impl PixelType
+{
+    fn id(self) -> u32
+    {
+        match self
+        {
+            Self::Half => 1,
+            Self::Float => 2
+        }
+    }
+
+    fn size(self) -> usize
+    {
+        match self
+        {
+            Self::Half => 2,
+            Self::Float => 4
+        }
+    }
+}
+
+impl Compression
+{
+    fn id(self) -> u8
+    {
+        match self
+        {
+            Self::None => 0,
+            Self::Zip => 3
+        }
+    }
+
+    fn lines_per_block(self) -> usize
+    {
+        match self
+        {
+            Self::None => 1,
+            Self::Zip => 16
+        }
+    }
+}
+
+fn invalid_input(message: &str) -> Error
+{
+    Error::new(ErrorKind::InvalidInput, message)
+}
+
+fn invalid_data(message: &str) -> Error
+{
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+fn half_to_f32(value: u16) -> f32
+{
+    let sign = u32::from(value & 0x8000) << 16;
+    let exponent = u32::from((value >> 10) & 0x1f);
+    let mantissa = u32::from(value & 0x03ff);
+
+    match exponent
+    {
+        // Subnormal values are scaled directly, as they have no implicit leading bit.
+        0 => f32::from_bits((f32::from(value & 0x03ff) * 2.0_f32.powi(-24)).to_bits() | sign),
+        0x1f => f32::from_bits(sign | 0x7f80_0000 | mantissa << 13),
+        _ => f32::from_bits(sign | (exponent + 127 - 15) << 23 | mantissa << 13)
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_sign_loss)]
+fn f32_to_half(value: f32) -> u16
+{
+    // Converts to IEEE 754 half precision, rounding to nearest even.
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = i32::from((bits >> 23) as u8);
+    let mantissa = bits & 0x007f_ffff;
+
+    if exponent == 0xff
+    {
+        // Infinity stays infinite, while NaN keeps nonzero mantissa.
+        return sign | 0x7c00 | if mantissa == 0 { 0 } else { 0x0200 };
+    }
+
+    let half_exponent = exponent - 127 + 15;
+
+    if half_exponent >= 0x1f
+    {
+        return sign | 0x7c00;
+    }
+
+    let round = |value: u32, shift: u32| -> u32
+    {
+        let remainder = value & ((1 << shift) - 1);
+        let halfway = 1 << (shift - 1);
+        let truncated = value >> shift;
+
+        if remainder > halfway || (remainder == halfway && truncated & 1 == 1) { truncated + 1 } else { truncated }
+    };
+
+    if half_exponent <= 0
+    {
+        // Values below normal range become subnormal, or zero if they are too small even for that.
+        if half_exponent < -10
+        {
+            return sign;
+        }
+
+        return sign | round(mantissa | 0x0080_0000, (14 - half_exponent) as u32) as u16;
+    }
+
+    // Rounding can carry into exponent, which still gives correctly rounded value.
+    sign | round((half_exponent as u32) << 23 | mantissa, 13) as u16
+}
+
+fn zip_compress(data: &[u8]) -> Vec<u8>
+{
+    // Bytes are split into two interleaved halves and delta encoded before deflating, which makes them compress better.
+    let reordered = data.iter().step_by(2).chain(data.iter().skip(1).step_by(2));
+    let mut previous = None;
+
+    let predicted: Vec<u8> = reordered.map(|value|
+    {
+        let delta = previous.map_or(*value, |previous: u8| value.wrapping_sub(previous).wrapping_add(128));
+        previous = Some(*value);
+        delta
+    }).collect();
+
+    deflate::deflate_bytes_zlib(&predicted)
+}
+
+fn zip_decompress(data: &[u8]) -> std::io::Result<Vec<u8>>
+{
+    let predicted = inflate::inflate_bytes_zlib(data).map_err(|error| invalid_data(&error))?;
+    Ok(reorder_decompressed(predicted))
+}
+
+fn rle_decompress(data: &[u8]) -> std::io::Result<Vec<u8>>
+{
+    // Negative count is followed by literal bytes, while positive one repeats single byte one more time than it says.
+    let mut predicted = Vec::new();
+    let mut remaining = data;
+
+    while let [count, rest @ ..] = remaining
+    {
+        #[allow(clippy::cast_possible_wrap)]
+        let count = *count as i8;
+
+        if count < 0
+        {
+            let length = usize::from(count.unsigned_abs());
+            let literal = rest.get(..length).ok_or_else(|| invalid_data("Unexpected end of run length data!"))?;
+
+            predicted.extend(literal);
+            remaining = &rest[length..];
+        }
+        else
+        {
+            let value = rest.first().ok_or_else(|| invalid_data("Unexpected end of run length data!"))?;
+
+            predicted.extend(std::iter::repeat_n(*value, count.unsigned_abs() as usize + 1));
+            remaining = &rest[1..];
+        }
+    }
+
+    Ok(reorder_decompressed(predicted))
+}
+
+fn reorder_decompressed(mut predicted: Vec<u8>) -> Vec<u8>
+{
+    // Reverses delta encoding and interleaving applied before compression.
+    for index in 1..predicted.len()
+    {
+        predicted[index] = predicted[index - 1].wrapping_add(predicted[index]).wrapping_sub(128);
+    }
+
+    let (first_half, second_half) = predicted.split_at(predicted.len().div_ceil(2));
+    let mut data = Vec::with_capacity(predicted.len());
+
+    for (index, value) in first_half.iter().enumerate()
+    {
+        data.push(*value);
+        data.extend(second_half.get(index));
+    }
+
+    data
+}
+
+fn read_u32(reader: &mut &[u8]) -> std::io::Result<u32>
+{
+    let mut bytes = [0_u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_i32(reader: &mut &[u8]) -> std::io::Result<i32>
+{
+    let mut bytes = [0_u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(i32::from_le_bytes(bytes))
+}
+
+fn read_string<'a>(reader: &mut &'a [u8]) -> std::io::Result<&'a str>
+{
+    let length = reader.iter().position(|byte| *byte == 0).ok_or_else(|| invalid_data("Unterminated string!"))?;
+    let value = std::str::from_utf8(&reader[..length]).map_err(|_| invalid_data("Invalid string!"))?;
+
+    *reader = &reader[length + 1..];
+    Ok(value)
+}
+
+struct Header
+{
+    channels: Vec<(String, PixelType)>,
+    compression: u8,
+    min_y: i32,
+    width: usize,
+    height: usize
+}
+
+impl Header
+{
+    fn read(reader: &mut &[u8]) -> std::io::Result<Self>
+    {
+        let mut magic = [0_u8; 4];
+        reader.read_exact(&mut magic)?;
+
+        if magic != MAGIC
+        {
+            return Err(invalid_data("Missing OpenEXR header!"));
+        }
+
+        // Only version two is known, and flags for tiled, deep or multi part files are not supported.
+        let version = read_u32(reader)?;
+
+        if version & 0xff != VERSION || version & 0x1a00 != 0
+        {
+            return Err(invalid_data("Unsupported OpenEXR file type!"));
+        }
+
+        let mut channels = Vec::new();
+        let mut compression = None;
+        let mut data_window = None;
+
+        loop
+        {
+            let name = read_string(reader)?;
+
+            if name.is_empty()
+            {
+                break;
+            }
+
+            let _attribute_type = read_string(reader)?;
+            let size = usize::try_from(read_u32(reader)?).or(Err(invalid_data("Invalid attribute size!")))?;
+            let mut value = reader.get(..size).ok_or_else(|| invalid_data("Unexpected end of header!"))?;
+            *reader = &reader[size..];
+
+            match name
+            {
+                "channels" => while !value.starts_with(&[0])
+                {
+                    // Linear flag, padding and sampling rates following pixel type are not needed.
+                    let channel_name = read_string(&mut value)?.to_string();
+
+                    let pixel_type = match read_u32(&mut value)?
+                    {
+                        1 => PixelType::Half,
+                        2 => PixelType::Float,
+                        _ => return Err(invalid_data("Unsupported pixel type!"))
+                    };
+
+                    value = value.get(12..).ok_or_else(|| invalid_data("Invalid channel list!"))?;
+                    channels.push((channel_name, pixel_type));
+                },
+                "compression" => compression = value.first().copied(),
+                "dataWindow" => data_window = Some([read_i32(&mut value)?, read_i32(&mut value)?, read_i32(&mut value)?, read_i32(&mut value)?]),
+                _ => {}
+            }
+        }
+
+        let [min_x, min_y, max_x, max_y] = data_window.ok_or_else(|| invalid_data("Missing data window!"))?;
+
+        let size = |min: i32, max: i32| usize::try_from(i64::from(max) - i64::from(min) + 1).ok().filter(|size| *size > 0);
+        let width = size(min_x, max_x).ok_or_else(|| invalid_data("Invalid image width!"))?;
+        let height = size(min_y, max_y).ok_or_else(|| invalid_data("Invalid image height!"))?;
+
+        if Surface::checked_pixel_count(width, height).is_none()
+        {
+            return Err(invalid_data("Invalid image size!"));
+        }
+
+        Ok(Self
+        {
+            channels,
+            compression: compression.ok_or_else(|| invalid_data("Missing compression!"))?,
+            min_y,
+            width,
+            height
+        })
+    }
+
+    fn lines_per_block(&self) -> std::io::Result<usize>
+    {
+        match self.compression
+        {
+            0..=2 => Ok(1),
+            3 => Ok(16),
+            _ => Err(invalid_data("Unsupported compression!"))
+        }
+    }
+}
+
+fn channel_value(color: Vec4, component: usize) -> f32
+{
+    match component
+    {
+        0 => color.get_r(),
+        1 => color.get_g(),
+        2 => color.get_b(),
+        _ => color.get_a()
+    }
+}
+
+fn set_channel_value(color: &mut Vec4, component: usize, value: f32)
+{
+    match component
+    {
+        0 => color.set_r(value),
+        1 => color.set_g(value),
+        2 => color.set_b(value),
+        _ => color.set_a(value)
+    }
+}
+
+fn write_attribute<W: Write>(writer: &mut W, name: &str, attribute_type: &str, value: &[u8]) -> std::io::Result<()>
+{
+    let size = u32::try_from(value.len()).map_err(|_| invalid_input("Attribute is too large!"))?;
+
+    writer.write_all(name.as_bytes())?;
+    writer.write_all(&[0])?;
+    writer.write_all(attribute_type.as_bytes())?;
+    writer.write_all(&[0])?;
+    writer.write_all(&size.to_le_bytes())?;
+    writer.write_all(value)
+}
+
+impl FormatEXR
+{
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn set_pixel_type(mut self, pixel_type: PixelType) -> Self
+    {
+        self.pixel_type = pixel_type;
+        self
+    }
+
+    #[must_use]
+    pub fn set_compression(mut self, compression: Compression) -> Self
+    {
+        self.compression = compression;
+        self
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when the file cannot be written.
+    pub fn save(&self, surface: &Surface, path: &Path) -> std::io::Result<()>
+    {
+        self.save_layers(&[("", surface)], path)
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when layers are empty or differ in size, or the file cannot be written.
+    pub fn save_layers(&self, layers: &[(&str, &Surface)], path: &Path) -> std::io::Result<()>
+    {
+        let image_file = OpenOptions::new().write(true).truncate(true).create(true).open(path)?;
+        let mut image_buffer = BufWriter::new(image_file);
+
+        self.write_layers(layers, &mut image_buffer)?;
+        image_buffer.flush()
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when the file cannot be read or is not a supported image.
+    pub fn load(path: &Path) -> std::io::Result<Surface>
+    {
+        let image_file = OpenOptions::new().read(true).open(path)?;
+        Self::read(BufReader::new(image_file))
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when reading fails or data is not a supported image.
+    pub fn read<R: Read>(mut reader: R) -> std::io::Result<Surface>
+    {
+        // Only unnamed layer is read, from its color channels or from luminance channel.
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+
+        let mut remaining = data.as_slice();
+        let header = Header::read(&mut remaining)?;
+        let lines_per_block = header.lines_per_block()?;
+
+        let components: Vec<&[usize]> = header.channels.iter().map(|(name, _)| match name.as_str()
+        {
+            "R" => &[0][..],
+            "G" => &[1][..],
+            "B" => &[2][..],
+            "A" => &[3][..],
+            "Y" => &[0, 1, 2][..],
+            _ => &[][..]
+        }).collect();
+
+        if !components.iter().any(|components| components.contains(&0))
+        {
+            return Err(invalid_data("Missing color channels!"));
+        }
+
+        let line_size: usize = header.channels.iter().map(|(_, pixel_type)| pixel_type.size() * header.width).sum();
+        let mut pixels = vec![Vec4::new(0.0, 0.0, 0.0, 1.0); header.width * header.height];
+
+        for _ in 0..header.height.div_ceil(lines_per_block)
+        {
+            // Blocks can be stored in any order, so their position is taken from offset table.
+            let mut offset_bytes = [0_u8; 8];
+            remaining.read_exact(&mut offset_bytes)?;
+
+            let offset = usize::try_from(u64::from_le_bytes(offset_bytes)).or(Err(invalid_data("Invalid block offset!")))?;
+            let mut block = data.get(offset..).ok_or_else(|| invalid_data("Invalid block offset!"))?;
+
+            let first_line = usize::try_from(i64::from(read_i32(&mut block)?) - i64::from(header.min_y)).or(Err(invalid_data("Invalid block position!")))?;
+            let size = usize::try_from(read_u32(&mut block)?).or(Err(invalid_data("Invalid block size!")))?;
+            let block = block.get(..size).ok_or_else(|| invalid_data("Unexpected end of block!"))?;
+
+            let line_count = header.height.saturating_sub(first_line).min(lines_per_block);
+
+            // Blocks which would not get smaller are stored without compression.
+            let block = match header.compression
+            {
+                _ if size == line_count * line_size => block.to_vec(),
+                1 => rle_decompress(block)?,
+                2 | 3 => zip_decompress(block)?,
+                _ => block.to_vec()
+            };
+
+            if block.len() != line_count * line_size
+            {
+                return Err(invalid_data("Invalid block size!"));
+            }
+
+            for (line_index, line) in block.chunks_exact(line_size).enumerate()
+            {
+                // Lines are stored from top to bottom, while surface starts from the bottom.
+                let row = (header.height - first_line - line_index - 1) * header.width;
+                let mut channel_data = line;
+
+                for ((_, pixel_type), components) in header.channels.iter().zip(&components)
+                {
+                    let (values, rest) = channel_data.split_at(pixel_type.size() * header.width);
+                    channel_data = rest;
+
+                    for (pixel, value) in pixels[row..row + header.width].iter_mut().zip(values.chunks_exact(pixel_type.size()))
+                    {
+                        let value = match pixel_type
+                        {
+                            PixelType::Half => half_to_f32(u16::from_le_bytes([value[0], value[1]])),
+                            PixelType::Float => f32::from_le_bytes([value[0], value[1], value[2], value[3]])
+                        };
+
+                        for component in *components
+                        {
+                            set_channel_value(pixel, *component, value);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(Surface::from(header.width, header.height, pixels))
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when layers are empty or differ in size, or writing fails.
+    pub fn write_layers<W: Write>(&self, layers: &[(&str, &Surface)], writer: &mut W) -> std::io::Result<()>
+    {
+        // Every layer is stored as four channels, with names prefixed by layer name unless it is empty.
+        let (_, first_surface) = layers.first().ok_or_else(|| invalid_input("Missing image layers!"))?;
+        let width = first_surface.get_width();
+        let height = first_surface.get_height();
+
+        if width == 0 || height == 0 || layers.iter().any(|(_, surface)| surface.get_width() != width || surface.get_height() != height)
+        {
+            return Err(invalid_input("Image layers must have the same nonzero size!"));
+        }
+
+        // Channels have to be sorted by name, together with index of layer and component they come from.
+        let mut channels: Vec<(String, usize, usize)> = layers.iter().enumerate().flat_map(|(layer_index, (name, _))|
+        {
+            ["R", "G", "B", "A"].iter().enumerate().map(move |(component, channel)|
+            {
+                let channel_name = if name.is_empty() { (*channel).to_string() } else { format!("{name}.{channel}") };
+                (channel_name, layer_index, component)
+            })
+        }).collect();
+
+        channels.sort();
+
+        let max_x = i32::try_from(width - 1).map_err(|_| invalid_input("Image is too wide!"))?;
+        let max_y = i32::try_from(height - 1).map_err(|_| invalid_input("Image is too tall!"))?;
+
+        let mut header = Vec::new();
+        self.write_header(&mut header, &channels, max_x, max_y)?;
+        writer.write_all(&header)?;
+
+        // Offset table is followed by blocks of scanlines, each starting with its first line and data size.
+        let lines_per_block = self.compression.lines_per_block();
+        let block_count = height.div_ceil(lines_per_block);
+
+        let blocks: Vec<Vec<u8>> = (0..block_count).map(|block_index|
+        {
+            let first_line = block_index * lines_per_block;
+            self.encode_block(layers, &channels, first_line..(first_line + lines_per_block).min(height))
+        }).collect();
+
+        let mut offset = header.len() + block_count * 8;
+
+        for block in &blocks
+        {
+            writer.write_all(&(offset as u64).to_le_bytes())?;
+            offset += 8 + block.len();
+        }
+
+        for (block_index, block) in blocks.iter().enumerate()
+        {
+            let first_line = i32::try_from(block_index * lines_per_block).map_err(|_| invalid_input("Image is too tall!"))?;
+            let size = u32::try_from(block.len()).map_err(|_| invalid_input("Image is too wide!"))?;
+
+            writer.write_all(&first_line.to_le_bytes())?;
+            writer.write_all(&size.to_le_bytes())?;
+            writer.write_all(block)?;
+        }
+
+        Ok(())
+    }
+
+    fn encode_block(&self, layers: &[(&str, &Surface)], channels: &[(String, usize, usize)], lines: std::ops::Range<usize>) -> Vec<u8>
+    {
+        let (_, first_surface) = layers[0];
+        let width = first_surface.get_width();
+        let height = first_surface.get_height();
+
+        let mut data = Vec::with_capacity(lines.len() * width * channels.len() * self.pixel_type.size());
+
+        for line in lines
+        {
+            // Lines are stored from top to bottom, while surface starts from the bottom.
+            let y = height - line - 1;
+
+            for (_, layer_index, component) in channels
+            {
+                let (_, surface) = layers[*layer_index];
+
+                for x in 0..width
+                {
+                    let value = channel_value(surface.get_pixel(x, y), *component);
+
+                    match self.pixel_type
+                    {
+                        PixelType::Half => data.extend(&f32_to_half(value).to_le_bytes()),
+                        PixelType::Float => data.extend(&value.to_le_bytes())
+                    }
+                }
+            }
+        }
+
+        // Compressed data is only kept when it is actually smaller.
+        match self.compression
+        {
+            Compression::Zip =>
+            {
+                let compressed = zip_compress(&data);
+                if compressed.len() < data.len() { compressed } else { data }
+            },
+            Compression::None => data
+        }
+    }
+
+    fn write_header<W: Write>(&self, writer: &mut W, channels: &[(String, usize, usize)], max_x: i32, max_y: i32) -> std::io::Result<()>
+    {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+
+        let mut channel_list = Vec::new();
+
+        for (name, _, _) in channels
+        {
+            // Channel name is followed by pixel type, linear flag with padding, and sampling rates.
+            channel_list.extend(name.as_bytes());
+            channel_list.push(0);
+            channel_list.extend(&self.pixel_type.id().to_le_bytes());
+            channel_list.extend(&[0, 0, 0, 0]);
+            channel_list.extend(&1_i32.to_le_bytes());
+            channel_list.extend(&1_i32.to_le_bytes());
+        }
+
+        channel_list.push(0);
+
+        let window: Vec<u8> = [0, 0, max_x, max_y].iter().flat_map(|value| value.to_le_bytes()).collect();
+
+        write_attribute(writer, "channels", "chlist", &channel_list)?;
+        write_attribute(writer, "compression", "compression", &[self.compression.id()])?;
+        write_attribute(writer, "dataWindow", "box2i", &window)?;
+        write_attribute(writer, "displayWindow", "box2i", &window)?;
+        write_attribute(writer, "lineOrder", "lineOrder", &[0])?;
+        write_attribute(writer, "pixelAspectRatio", "float", &1.0_f32.to_le_bytes())?;
+        write_attribute(writer, "screenWindowCenter", "v2f", &[0; 8])?;
+        write_attribute(writer, "screenWindowWidth", "float", &1.0_f32.to_le_bytes())?;
+
+        writer.write_all(&[0])
+    }
+}
+
+impl Format for FormatEXR
+{
+    fn save(&self, surface: &Surface, path: &Path) -> Result<(), writer::Error>
+    {
+        self.save(surface, path).or(Err(writer::Error::SaveFailed))
+    }
+
+    fn get_name(&self) -> &'static str
+    {
+        "EXR"
+    }
+
+    fn save_layers(&self, layers: &[(&str, &Surface)], path: &Path) -> Result<(), writer::Error>
+    {
+        self.save_layers(layers, path).or(Err(writer::Error::SaveFailed))
+    }
+}
+
+impl reader::Format for FormatEXR
+{
+    fn read(&self, reader: &mut dyn BufRead) -> std::io::Result<Surface>
+    {
+        Self::read(reader)
+    }
+
+    fn get_name(&self) -> &'static str
+    {
+        "EXR"
+    }
+
+    fn is_signature(&self, header: &[u8]) -> bool
+    {
+        header.starts_with(&MAGIC)
+    }
+
+    fn is_srgb(&self) -> bool
+    {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use std::convert::TryInto;
+
+    #[test]
+    fn half()
+    {
+        assert_eq!(f32_to_half(0.0), 0x0000);
+        assert_eq!(f32_to_half(1.0), 0x3c00);
+        assert_eq!(f32_to_half(-2.0), 0xc000);
+        assert_eq!(f32_to_half(0.1), 0x2e66);
+        assert_eq!(f32_to_half(65504.0), 0x7bff);
+        assert_eq!(f32_to_half(65520.0), 0x7c00);
+        assert_eq!(f32_to_half(2.0_f32.powi(-24)), 0x0001);
+        assert_eq!(f32_to_half(2.0_f32.powi(-26)), 0x0000);
+        assert_eq!(f32_to_half(f32::INFINITY), 0x7c00);
+        assert_eq!(f32_to_half(f32::NAN) & 0x7e00, 0x7e00);
+    }
+
+    #[test]
+    fn write_uncompressed()
+    {
+        let surface = Surface::from(2, 1, vec![Vec4::new(1.0, 2.0, 3.0, 1.0), Vec4::new(-1.0, 0.5, 100.0, 0.0)]);
+
+        let mut data = Vec::new();
+        FormatEXR::new().set_pixel_type(PixelType::Float).set_compression(Compression::None).write_layers(&[("", &surface)], &mut data).unwrap();
+
+        assert_eq!(data[..4], MAGIC);
+
+        // Single scanline is stored after its offset, line number and size, with channels in alphabetical order.
+        let values: Vec<f32> = data[data.len() - 32..].chunks_exact(4).map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])).collect();
+        assert_eq!(values, vec![1.0, 0.0, 3.0, 100.0, 2.0, 0.5, 1.0, -1.0]);
+
+        let offset = u64::from_le_bytes(data[data.len() - 48..data.len() - 40].try_into().unwrap());
+        assert_eq!(offset, (data.len() - 40) as u64);
+    }
+
+    #[test]
+    fn write_layers()
+    {
+        let color = Surface::from(4, 20, vec![Vec4::new(0.5, 0.5, 0.5, 1.0); 80]);
+        let depth = Surface::from(4, 20, vec![Vec4::new(2.0, 2.0, 2.0, 1.0); 80]);
+
+        let mut data = Vec::new();
+        FormatEXR::new().write_layers(&[("", &color), ("depth", &depth)], &mut data).unwrap();
+
+        assert!(String::from_utf8_lossy(&data).contains("depth.R"));
+
+        // Header ends after its last attribute, and is followed by offsets of two blocks of sixteen scanlines.
+        let last_attribute = b"screenWindowWidth\0float\0";
+        let header_end = data.windows(last_attribute.len()).position(|bytes| bytes == last_attribute).unwrap() + last_attribute.len() + 9;
+
+        for (block_index, offset) in data[header_end..header_end + 16].chunks_exact(8).enumerate()
+        {
+            let offset = usize::try_from(u64::from_le_bytes(offset.try_into().unwrap())).unwrap();
+            let first_line = i32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+
+            assert_eq!(first_line, i32::try_from(block_index * 16).unwrap());
+        }
+
+        // Uniform layers compress well below their raw size.
+        assert!(data.len() < 20 * 4 * 8 * 2);
+
+        let mut invalid = Vec::new();
+        assert!(FormatEXR::new().write_layers(&[("", &color), ("depth", &Surface::new(1, 1))], &mut invalid).is_err());
+    }
+
+    #[test]
+    fn write_read()
+    {
+        let pixels: Vec<Vec4> = (0..7 * 19).map(|index| Vec4::new(index as f32 * 0.5, 1.0 / (index as f32 + 1.0), -2.0, (index % 2) as f32)).collect();
+        let surface = Surface::from(7, 19, pixels);
+
+        for (pixel_type, compression) in &[(PixelType::Half, Compression::Zip), (PixelType::Float, Compression::Zip), (PixelType::Float, Compression::None)]
+        {
+            let mut data = Vec::new();
+            FormatEXR::new().set_pixel_type(*pixel_type).set_compression(*compression).write_layers(&[("", &surface), ("depth", &surface)], &mut data).unwrap();
+
+            let loaded = FormatEXR::read(data.as_slice()).unwrap();
+            assert_eq!((loaded.get_width(), loaded.get_height()), (7, 19));
+
+            // Half precision keeps about three significant digits.
+            let tolerance = if *pixel_type == PixelType::Half { 0.001 } else { 0.0 };
+
+            for (loaded_pixel, pixel) in loaded.as_pixel_slice().iter().zip(surface.as_pixel_slice())
+            {
+                for component in 0..4
+                {
+                    let (loaded_value, value) = (channel_value(*loaded_pixel, component), channel_value(*pixel, component));
+                    assert!((loaded_value - value).abs() <= tolerance * value.abs());
+                }
+            }
+
+            assert!(FormatEXR::read(&data[..data.len() - 1]).is_err());
+        }
+
+        // Data window that covers whole coordinate range would need more pixels than fit in memory.
+        let mut data = Vec::new();
+        FormatEXR::new().write_layers(&[("", &surface)], &mut data).unwrap();
+
+        let window = data.windows(10).position(|name| name == b"dataWindow").unwrap() + 21;
+        data[window..window + 16].copy_from_slice(&[0, 0, 0, 0x80, 0, 0, 0, 0x80, 0xff, 0xff, 0xff, 0x7f, 0xff, 0xff, 0xff, 0x7f]);
+        assert!(FormatEXR::read(data.as_slice()).is_err_and(|error| error.kind() == ErrorKind::InvalidData));
+    }
+
+    #[test]
+    fn rle()
+    {
+        // Run of three bytes and literal span of two are then delta decoded and interleaved.
+        let data = rle_decompress(&[2, 129, 0xfe, 130, 131]).unwrap();
+        assert_eq!(data, vec![129, 133, 130, 136, 131]);
+
+        assert!(rle_decompress(&[0xfd, 1]).is_err());
+    }
+}
@@ -0,0 +1,427 @@
+use std::io::{ BufRead, BufReader, BufWriter, Read, Write, Error, ErrorKind };
+use std::fs::OpenOptions;
+use std::path::Path;
+
+use super::math::Vec4;
+use super::surface::Surface;
+use super::writer::Format;
+use super::writer;
+use super::reader;
+
+// Implementation based on: http://paulbourke.net/dataformats/pic/
+// Pixels are stored as RGBE, with shared exponent for all three color channels.
+
+#[derive(Default)]
+pub struct FormatHDR
+{
+}
+
+fn invalid_data(message: &str) -> Error
+{
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+fn rgbe_to_color(rgbe: [u8; 4]) -> Vec4
+{
+    if rgbe[3] == 0
+    {
+        return Vec4::black();
+    }
+
+    let scale = 2.0_f32.powi(i32::from(rgbe[3]) - (128 + 8));
+
+    Vec4::new
+    (
+        (f32::from(rgbe[0]) + 0.5) * scale,
+        (f32::from(rgbe[1]) + 0.5) * scale,
+        (f32::from(rgbe[2]) + 0.5) * scale,
+        1.0
+    )
+}
+
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_sign_loss)]
+fn color_to_rgbe(color: Vec4) -> [u8; 4]
+{
+    // Negative and invalid values cannot be represented, so they are stored as zero.
+    let channels = [color.get_r(), color.get_g(), color.get_b()].map(|value| if value > 0.0 { value.min(f32::MAX) } else { 0.0 });
+    let max_channel = channels[0].max(channels[1]).max(channels[2]);
+
+    if max_channel < 1e-32
+    {
+        return [0, 0, 0, 0];
+    }
+
+    // Shared exponent is chosen so the largest channel has its mantissa between 128 and 255.
+    let exponent = i32::from((max_channel.to_bits() >> 23) as u8) - 126;
+    let scale = 2.0_f32.powi(8 - exponent);
+
+    let [r, g, b] = channels.map(|value| (value * scale).min(255.0) as u8);
+    [r, g, b, (exponent + 128) as u8]
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn write_scanline<W: Write>(writer: &mut W, scanline: &[[u8; 4]]) -> std::io::Result<()>
+{
+    let width = scanline.len();
+
+    // Run length encoding cannot describe scanlines outside of this range.
+    if !(8..0x8000).contains(&width)
+    {
+        return scanline.iter().try_for_each(|pixel| writer.write_all(pixel));
+    }
+
+    writer.write_all(&[2, 2, (width >> 8) as u8, (width & 0xff) as u8])?;
+
+    for channel in 0..4
+    {
+        let values: Vec<u8> = scanline.iter().map(|pixel| pixel[channel]).collect();
+        let run_length = |begin: usize| values[begin..].iter().take(127).take_while(|value| **value == values[begin]).count();
+
+        let mut index = 0;
+
+        while index < width
+        {
+            // Runs shorter than three values are cheaper to store as part of literal span.
+            let length = run_length(index);
+
+            if length >= 3
+            {
+                writer.write_all(&[128 + length as u8, values[index]])?;
+                index += length;
+                continue;
+            }
+
+            let begin = index;
+
+            while index < width && index - begin < 128 && (index == begin || run_length(index) < 3)
+            {
+                index += 1;
+            }
+
+            writer.write_all(&[(index - begin) as u8])?;
+            writer.write_all(&values[begin..index])?;
+        }
+    }
+
+    Ok(())
+}
+
+fn read_scanline<R: Read>(reader: &mut R, width: usize, scanline: &mut [[u8; 4]]) -> std::io::Result<()>
+{
+    let mut header = [0_u8; 4];
+    reader.read_exact(&mut header)?;
+
+    // Run length encoding is only used for scanlines of reasonable width.
+    let is_encoded = (8..0x8000).contains(&width) && header[0] == 2 && header[1] == 2 && header[2] & 0x80 == 0;
+
+    if !is_encoded
+    {
+        scanline[0] = header;
+
+        for pixel in scanline.iter_mut().skip(1)
+        {
+            reader.read_exact(pixel)?;
+        }
+
+        return Ok(());
+    }
+
+    if (usize::from(header[2]) << 8 | usize::from(header[3])) != width
+    {
+        return Err(invalid_data("Mismatched scanline width!"));
+    }
+
+    // Each channel is encoded separately as sequence of runs and literal spans.
+    for channel in 0..4
+    {
+        let mut index = 0;
+
+        while index < width
+        {
+            let mut count = [0_u8; 1];
+            reader.read_exact(&mut count)?;
+
+            if count[0] > 128
+            {
+                let run_length = usize::from(count[0] - 128);
+
+                if index + run_length > width
+                {
+                    return Err(invalid_data("Scanline run overflow!"));
+                }
+
+                let mut value = [0_u8; 1];
+                reader.read_exact(&mut value)?;
+
+                for pixel in &mut scanline[index..index + run_length]
+                {
+                    pixel[channel] = value[0];
+                }
+
+                index += run_length;
+            }
+            else
+            {
+                let span_length = usize::from(count[0]);
+
+                if span_length == 0 || index + span_length > width
+                {
+                    return Err(invalid_data("Scanline span overflow!"));
+                }
+
+                let mut values = [0_u8; 128];
+                reader.read_exact(&mut values[..span_length])?;
+
+                for (pixel, value) in scanline[index..index + span_length].iter_mut().zip(&values[..span_length])
+                {
+                    pixel[channel] = *value;
+                }
+
+                index += span_length;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl FormatHDR
+{
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when the file cannot be written.
+    pub fn save(surface: &Surface, path: &Path) -> std::io::Result<()>
+    {
+        let image_file = OpenOptions::new().write(true).truncate(true).create(true).open(path)?;
+        let mut image_buffer = BufWriter::new(image_file);
+
+        Self::write(surface, &mut image_buffer)?;
+        image_buffer.flush()
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when writing fails.
+    pub fn write<W: Write>(surface: &Surface, writer: &mut W) -> std::io::Result<()>
+    {
+        // Values are written as they are, without gamma or clamping, so image keeps its full dynamic range.
+        writeln!(writer, "#?RADIANCE")?;
+        writeln!(writer, "FORMAT=32-bit_rle_rgbe")?;
+        writeln!(writer)?;
+        writeln!(writer, "-Y {} +X {}", surface.get_height(), surface.get_width())?;
+
+        for y in (0..surface.get_height()).rev()
+        {
+            let scanline: Vec<[u8; 4]> = (0..surface.get_width()).map(|x| color_to_rgbe(surface.get_pixel(x, y))).collect();
+            write_scanline(writer, &scanline)?;
+        }
+
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when file cannot be read or is not a supported Radiance image.
+    pub fn load(path: &Path) -> std::io::Result<Surface>
+    {
+        let image_file = OpenOptions::new().read(true).open(path)?;
+        Self::read(&mut BufReader::new(image_file))
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when data cannot be read or is not a supported Radiance image.
+    pub fn read<R: BufRead>(reader: &mut R) -> std::io::Result<Surface>
+    {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+
+        if !line.starts_with("#?")
+        {
+            return Err(invalid_data("Missing Radiance header!"));
+        }
+
+        // Header ends with an empty line and is followed by resolution string.
+        loop
+        {
+            line.clear();
+
+            if reader.read_line(&mut line)? == 0
+            {
+                return Err(invalid_data("Unexpected end of header!"));
+            }
+
+            let statement = line.trim();
+
+            if statement.is_empty()
+            {
+                break;
+            }
+
+            if statement.starts_with("FORMAT=") && statement != "FORMAT=32-bit_rle_rgbe"
+            {
+                return Err(invalid_data("Unsupported pixel format!"));
+            }
+        }
+
+        line.clear();
+        reader.read_line(&mut line)?;
+
+        // Only standard orientation with rows stored from top to bottom is supported.
+        let resolution: Vec<&str> = line.split_whitespace().collect();
+
+        let (height, width) = match resolution.as_slice()
+        {
+            ["-Y", height, "+X", width] =>
+            {
+                (height.parse::<usize>().or(Err(invalid_data("Invalid height!")))?,
+                width.parse::<usize>().or(Err(invalid_data("Invalid width!")))?)
+            },
+            _ => return Err(invalid_data("Unsupported resolution string!"))
+        };
+
+        if width == 0 || height == 0 || Surface::checked_pixel_count(width, height).is_none()
+        {
+            return Err(invalid_data("Invalid image size!"));
+        }
+
+        let mut surface = Surface::new(width, height);
+        let mut scanline = vec![[0_u8; 4]; width];
+
+        for row in 0..height
+        {
+            read_scanline(reader, width, &mut scanline)?;
+
+            for (x, rgbe) in scanline.iter().enumerate()
+            {
+                surface.set_pixel(x, height - row - 1, rgbe_to_color(*rgbe));
+            }
+        }
+
+        Ok(surface)
+    }
+}
+
+impl Format for FormatHDR
+{
+    fn save(&self, surface: &Surface, path: &Path) -> Result<(), writer::Error>
+    {
+        Self::save(surface, path).or(Err(writer::Error::SaveFailed))
+    }
+
+    fn get_name(&self) -> &'static str
+    {
+        "HDR"
+    }
+}
+
+impl reader::Format for FormatHDR
+{
+    fn read(&self, mut reader: &mut dyn BufRead) -> std::io::Result<Surface>
+    {
+        Self::read(&mut reader)
+    }
+
+    fn get_name(&self) -> &'static str
+    {
+        "HDR"
+    }
+
+    fn is_signature(&self, header: &[u8]) -> bool
+    {
+        header.starts_with(b"#?")
+    }
+
+    fn is_srgb(&self) -> bool
+    {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn read_flat()
+    {
+        let mut data = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 2 +X 1\n".to_vec();
+        data.extend(&[128, 64, 0, 129]);
+        data.extend(&[0, 0, 0, 0]);
+
+        let surface = FormatHDR::read(&mut data.as_slice()).unwrap();
+
+        assert_eq!(surface.get_width(), 1);
+        assert_eq!(surface.get_height(), 2);
+        assert_eq!(surface.get_pixel(0, 1), Vec4::new(128.5 / 128.0, 64.5 / 128.0, 0.5 / 128.0, 1.0));
+        assert_eq!(surface.get_pixel(0, 0), Vec4::black());
+    }
+
+    #[test]
+    fn read_encoded()
+    {
+        let mut data = b"#?RADIANCE\n\n-Y 1 +X 8\n".to_vec();
+        data.extend(&[2, 2, 0, 8]);
+        data.extend(&[136, 127]);
+        data.extend(&[4, 0, 1, 2, 3, 132, 255]);
+        data.extend(&[136, 0]);
+        data.extend(&[136, 128]);
+
+        let surface = FormatHDR::read(&mut data.as_slice()).unwrap();
+
+        assert_eq!(surface.get_width(), 8);
+        assert_eq!(surface.get_height(), 1);
+        assert_eq!(surface.get_pixel(2, 0), Vec4::new(127.5 / 256.0, 2.5 / 256.0, 0.5 / 256.0, 1.0));
+        assert_eq!(surface.get_pixel(7, 0), Vec4::new(127.5 / 256.0, 255.5 / 256.0, 0.5 / 256.0, 1.0));
+    }
+
+    #[test]
+    fn write_read()
+    {
+        // Wide image uses run length encoding, while narrow one is stored flat.
+        for width in &[3, 40]
+        {
+            let pixels: Vec<Vec4> = (0..width * 2).map(|index| match index % 7
+            {
+                0 | 1 => Vec4::new(0.0, 0.0, 0.0, 1.0),
+                _ => Vec4::new(index as f32 * 10.0, 0.25, 0.001, 1.0)
+            }).collect();
+
+            let surface = Surface::from(*width, 2, pixels);
+
+            let mut data = Vec::new();
+            FormatHDR::write(&surface, &mut data).unwrap();
+
+            let loaded = FormatHDR::read(&mut data.as_slice()).unwrap();
+
+            assert_eq!((loaded.get_width(), loaded.get_height()), (*width, 2));
+
+            for (loaded_pixel, pixel) in loaded.as_pixel_slice().iter().zip(surface.as_pixel_slice())
+            {
+                let max_channel = pixel.get_r().max(pixel.get_g()).max(pixel.get_b());
+
+                for (loaded_channel, channel) in [loaded_pixel.get_r(), loaded_pixel.get_g(), loaded_pixel.get_b()].iter().zip(&[pixel.get_r(), pixel.get_g(), pixel.get_b()])
+                {
+                    assert!((loaded_channel - channel).abs() <= max_channel / 128.0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn read_bad_header()
+    {
+        let data = b"P3\n1 1\n255\n".to_vec();
+        assert!(FormatHDR::read(&mut data.as_slice()).is_err());
+
+        let huge = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 4294967296 +X 4294967296\n".to_vec();
+        assert!(FormatHDR::read(&mut huge.as_slice()).is_err_and(|error| error.kind() == ErrorKind::InvalidData));
+    }
+}
@@ -1,10 +1,12 @@
 use std::fs::OpenOptions;
-use std::io::BufWriter;
+use std::io::{ BufWriter, BufReader, BufRead, Read, ErrorKind };
 use std::path::Path;
 
+use super::math::Vec4;
 use super::surface::Surface;
 use super::writer::Format;
 use super::writer::Error;
+use super::reader;
 
 #[derive(Default)]
 pub struct FormatPNG
@@ -48,6 +50,81 @@ impl FormatPNG
 
         Ok(())
     }
+
+    /// # Errors
+    ///
+    /// Returns error when file cannot be opened or decoded.
+    pub fn load(path: &Path) -> std::io::Result<Surface>
+    {
+        let image_file = OpenOptions::new().read(true).open(path)?;
+        Self::read(BufReader::new(image_file))
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when data is not a PNG image with supported color type.
+    pub fn read<R: Read>(reader: R) -> std::io::Result<Surface>
+    {
+        let invalid_data = |error: png::DecodingError| std::io::Error::new(ErrorKind::InvalidData, error.to_string());
+
+        // Palette and low bit depth images are expanded, so only 8 and 16 bit channels need handling.
+        let mut image_decoder = png::Decoder::new(reader);
+        image_decoder.set_transformations(png::Transformations::EXPAND);
+
+        let (image_info, mut image_reader) = image_decoder.read_info().map_err(invalid_data)?;
+        let mut image_bytes = vec![0; image_info.buffer_size()];
+        image_reader.next_frame(&mut image_bytes).map_err(invalid_data)?;
+
+        let channel_count = match image_info.color_type
+        {
+            png::ColorType::Grayscale => 1,
+            png::ColorType::GrayscaleAlpha => 2,
+            png::ColorType::RGB => 3,
+            png::ColorType::RGBA => 4,
+            png::ColorType::Indexed => return Err(std::io::Error::new(ErrorKind::InvalidData, "Unexpected indexed colors!"))
+        };
+
+        let (channel_size, channel_max) = match image_info.bit_depth
+        {
+            png::BitDepth::Eight => (1, f32::from(u8::MAX)),
+            png::BitDepth::Sixteen => (2, f32::from(u16::MAX)),
+            _ => return Err(std::io::Error::new(ErrorKind::InvalidData, "Unexpected bit depth!"))
+        };
+
+        let width = image_info.width as usize;
+        let height = image_info.height as usize;
+        let mut surface = Surface::new(width, height);
+
+        // Rows are stored from top to bottom, while surface starts from the bottom.
+        for (row, row_bytes) in image_bytes.chunks_exact(image_info.line_size).take(height).enumerate()
+        {
+            for (x, pixel_bytes) in row_bytes.chunks_exact(channel_count * channel_size).take(width).enumerate()
+            {
+                let channel = |index: usize| -> f32
+                {
+                    let bytes = &pixel_bytes[index * channel_size..(index + 1) * channel_size];
+                    let value = bytes.iter().fold(0_u32, |value, byte| value << 8 | u32::from(*byte));
+
+                    #[allow(clippy::cast_precision_loss)]
+                    let value = value as f32;
+
+                    value / channel_max
+                };
+
+                let color = match channel_count
+                {
+                    1 => Vec4::new(channel(0), channel(0), channel(0), 1.0),
+                    2 => Vec4::new(channel(0), channel(0), channel(0), channel(1)),
+                    3 => Vec4::new(channel(0), channel(1), channel(2), 1.0),
+                    _ => Vec4::new(channel(0), channel(1), channel(2), channel(3))
+                };
+
+                surface.set_pixel(x, height - row - 1, color);
+            }
+        }
+
+        Ok(surface)
+    }
 }
 
 impl Format for FormatPNG
@@ -62,3 +139,26 @@ impl Format for FormatPNG
         "PNG"
     }
 }
+
+impl reader::Format for FormatPNG
+{
+    fn read(&self, reader: &mut dyn BufRead) -> std::io::Result<Surface>
+    {
+        Self::read(reader)
+    }
+
+    fn get_name(&self) -> &'static str
+    {
+        "PNG"
+    }
+
+    fn is_signature(&self, header: &[u8]) -> bool
+    {
+        header.starts_with(b"\x89PNG")
+    }
+
+    fn is_srgb(&self) -> bool
+    {
+        true
+    }
+}
@@ -1,11 +1,12 @@
-use std::io::Write;
-use std::io::BufWriter;
+use std::io::{ Write, Read, BufRead, BufWriter, BufReader, Error, ErrorKind };
 use std::fs::OpenOptions;
 use std::path::Path;
 
+use super::math::Vec4;
 use super::surface::Surface;
 use super::writer::Format;
-use super::writer::Error;
+use super::writer;
+use super::reader;
 
 #[derive(Default)]
 pub struct FormatPNM
@@ -39,13 +40,155 @@ impl FormatPNM
 
         Ok(())
     }
+
+    /// # Errors
+    ///
+    /// Returns error when file cannot be opened or parsed.
+    pub fn load(path: &Path) -> std::io::Result<Surface>
+    {
+        let image_file = OpenOptions::new().read(true).open(path)?;
+        Self::read(BufReader::new(image_file))
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when header or pixel values are missing or invalid.
+    pub fn read<R: Read>(mut reader: R) -> std::io::Result<Surface>
+    {
+        // Supports plain (P2, P3) and raw (P5, P6) grayscale and color images.
+        // Implementation based on: http://netpbm.sourceforge.net/doc/ppm.html
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+
+        let mut header = HeaderParser::new(&data);
+        let magic = header.next_token().ok_or_else(|| invalid_data("Missing magic number!"))?;
+
+        let (channel_count, is_plain) = match magic
+        {
+            b"P2" => (1, true),
+            b"P3" => (3, true),
+            b"P5" => (1, false),
+            b"P6" => (3, false),
+            _ => return Err(invalid_data("Unsupported image type!"))
+        };
+
+        let width = header.next_number()?;
+        let height = header.next_number()?;
+        let max_value = header.next_number()?;
+
+        if width == 0 || height == 0 || max_value == 0 || max_value > usize::from(u16::MAX)
+        {
+            return Err(invalid_data("Invalid image header!"));
+        }
+
+        let pixel_count = Surface::checked_pixel_count(width, height).ok_or_else(|| invalid_data("Invalid image size!"))?;
+        let value_count = pixel_count * channel_count;
+
+        let values: Vec<usize> = if is_plain
+        {
+            (0..value_count).map(|_| header.next_number()).collect::<std::io::Result<_>>()?
+        }
+        else
+        {
+            // Single whitespace character separates header from binary data.
+            let value_size = if max_value > usize::from(u8::MAX) { 2 } else { 1 };
+            let begin = header.offset + 1;
+            let end = begin + value_count * value_size;
+
+            let bytes = data.get(begin..end).ok_or_else(|| invalid_data("Unexpected end of image data!"))?;
+            bytes.chunks_exact(value_size).map(|value| value.iter().fold(0, |value, byte| value << 8 | usize::from(*byte))).collect()
+        };
+
+        #[allow(clippy::cast_precision_loss)]
+        let channel = |value: usize| value.min(max_value) as f32 / max_value as f32;
+
+        let mut surface = Surface::new(width, height);
+
+        // Rows are stored from top to bottom, while surface starts from the bottom.
+        for (index, color) in values.chunks_exact(channel_count).enumerate()
+        {
+            let x = index % width;
+            let y = height - index / width - 1;
+
+            // Grayscale value is used for all color channels.
+            let color = match color
+            {
+                [value] => Vec4::new(channel(*value), channel(*value), channel(*value), 1.0),
+                _ => Vec4::new(channel(color[0]), channel(color[1]), channel(color[2]), 1.0)
+            };
+
+            surface.set_pixel(x, y, color);
+        }
+
+        Ok(surface)
+    }
+}
+
+fn invalid_data(message: &str) -> Error
+{
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+struct HeaderParser<'a>
+{
+    data: &'a [u8],
+    offset: usize
+}
+
+impl<'a> HeaderParser<'a>
+{
+    fn new(data: &'a [u8]) -> Self
+    {
+        Self
+        {
+            data,
+            offset: 0
+        }
+    }
+
+    fn next_token(&mut self) -> Option<&'a [u8]>
+    {
+        // Tokens are separated by whitespace, with comments starting with hash and lasting until end of line.
+        loop
+        {
+            match self.data.get(self.offset)?
+            {
+                byte if byte.is_ascii_whitespace() => self.offset += 1,
+                b'#' =>
+                {
+                    while self.data.get(self.offset).is_some_and(|byte| *byte != b'\n')
+                    {
+                        self.offset += 1;
+                    }
+                },
+                _ => break
+            }
+        }
+
+        let begin = self.offset;
+
+        while self.data.get(self.offset).is_some_and(|byte| !byte.is_ascii_whitespace())
+        {
+            self.offset += 1;
+        }
+
+        Some(&self.data[begin..self.offset])
+    }
+
+    fn next_number(&mut self) -> std::io::Result<usize>
+    {
+        let token = self.next_token().ok_or_else(|| invalid_data("Unexpected end of image data!"))?;
+
+        std::str::from_utf8(token).ok().and_then(|token| token.parse().ok())
+            .ok_or_else(|| invalid_data("Invalid number!"))
+    }
 }
 
 impl Format for FormatPNM
 {
-    fn save(&self, surface: &Surface, path: &Path) -> Result<(), Error>
+    fn save(&self, surface: &Surface, path: &Path) -> Result<(), writer::Error>
     {
-        Self::save(surface, path).or(Err(Error::SaveFailed))
+        Self::save(surface, path).or(Err(writer::Error::SaveFailed))
     }
 
     fn get_name(&self) -> &'static str
@@ -53,3 +196,75 @@ impl Format for FormatPNM
         "PNM"
     }
 }
+
+impl reader::Format for FormatPNM
+{
+    fn read(&self, reader: &mut dyn BufRead) -> std::io::Result<Surface>
+    {
+        Self::read(reader)
+    }
+
+    fn get_name(&self) -> &'static str
+    {
+        "PNM"
+    }
+
+    fn is_signature(&self, header: &[u8]) -> bool
+    {
+        matches!(header, [b'P', b'2' | b'3' | b'5' | b'6', ..])
+    }
+
+    fn is_srgb(&self) -> bool
+    {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn read_plain()
+    {
+        let data = b"P3\n# Comment\n2 1\n255\n255 0 0  0 0 255\n".to_vec();
+        let surface = FormatPNM::read(data.as_slice()).unwrap();
+
+        assert_eq!(surface.get_width(), 2);
+        assert_eq!(surface.get_height(), 1);
+        assert_eq!(surface.get_pixel(0, 0), Vec4::red());
+        assert_eq!(surface.get_pixel(1, 0), Vec4::blue());
+    }
+
+    #[test]
+    fn read_raw()
+    {
+        let mut data = b"P6 1 2 255\n".to_vec();
+        data.extend(&[0, 255, 0, 0, 0, 255]);
+
+        let surface = FormatPNM::read(data.as_slice()).unwrap();
+
+        assert_eq!(surface.get_pixel(0, 1), Vec4::green());
+        assert_eq!(surface.get_pixel(0, 0), Vec4::blue());
+        assert!(FormatPNM::read(&data[..12]).is_err());
+
+        let huge = b"P6 4294967296 4294967297 255\n".to_vec();
+        assert!(FormatPNM::read(huge.as_slice()).is_err_and(|error| error.kind() == ErrorKind::InvalidData));
+    }
+
+    #[test]
+    fn read_grayscale()
+    {
+        let plain = FormatPNM::read(&b"P2 2 1 4 0 2"[..]).unwrap();
+        assert_eq!(plain.get_pixel(0, 0), Vec4::black());
+        assert_eq!(plain.get_pixel(1, 0), Vec4::new(0.5, 0.5, 0.5, 1.0));
+
+        let mut data = b"P5 1 2 65535\n".to_vec();
+        data.extend(&[0xff, 0xff, 0x00, 0x00]);
+
+        let raw = FormatPNM::read(data.as_slice()).unwrap();
+        assert_eq!(raw.get_pixel(0, 1), Vec4::white());
+        assert_eq!(raw.get_pixel(0, 0), Vec4::black());
+    }
+}
@@ -1,11 +1,18 @@
 pub use super::math;
 
 pub mod surface;
+pub mod color;
 pub mod writer;
+pub mod reader;
 pub mod format_pnm;
 pub mod format_png;
+pub mod format_hdr;
+pub mod format_exr;
 
 pub use surface::Surface;
 pub use writer::Writer;
+pub use reader::Reader;
 pub use format_pnm::FormatPNM;
 pub use format_png::FormatPNG;
+pub use format_hdr::FormatHDR;
+pub use format_exr::FormatEXR;
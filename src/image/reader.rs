@@ -0,0 +1,117 @@
+use std::io::{ BufRead, BufReader, Error, ErrorKind };
+use std::fs::OpenOptions;
+use std::path::{ Path, PathBuf };
+use super::surface::Surface;
+use super::color;
+use super::FormatPNG;
+use super::FormatPNM;
+use super::FormatHDR;
+use super::FormatEXR;
+
+pub trait Format
+{
+    /// # Errors
+    ///
+    /// Returns error when reading fails or data is not a valid image of this format.
+    fn read(&self, reader: &mut dyn BufRead) -> std::io::Result<Surface>;
+    fn get_name(&self) -> &'static str;
+
+    // Checks whether file starting with given bytes is stored in this format.
+    fn is_signature(&self, header: &[u8]) -> bool;
+
+    // Formats with limited range store colors in sRGB, while others keep linear values.
+    fn is_srgb(&self) -> bool;
+}
+
+pub struct Reader<'a>
+{
+    format: Option<Box<dyn Format + 'a>>,
+    path: Option<PathBuf>,
+    is_converted_to_linear: bool
+}
+
+impl Default for Reader<'_>
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            format: None,
+            path: None,
+            is_converted_to_linear: true
+        }
+    }
+}
+
+impl<'a> Reader<'a>
+{
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn format<FormatType: Format + 'a>(mut self, format: FormatType) -> Self
+    {
+        self.format = Some(Box::new(format));
+        self
+    }
+
+    #[must_use]
+    pub fn input<P: AsRef<Path>>(mut self, path: P) -> Self
+    {
+        self.path = Some(PathBuf::from(path.as_ref()));
+        self
+    }
+
+    #[must_use]
+    pub fn convert_to_linear(mut self, is_converted_to_linear: bool) -> Self
+    {
+        self.is_converted_to_linear = is_converted_to_linear;
+        self
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when path is missing, the file cannot be read or its format is unknown.
+    pub fn load(self) -> std::io::Result<Surface>
+    {
+        let path = self.path.as_ref().ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Missing image path!"))?;
+
+        let begin_time = std::time::Instant::now();
+
+        let image_file = OpenOptions::new().read(true).open(path)?;
+        let mut reader = BufReader::new(image_file);
+
+        // Unless format has been given, it is detected from first bytes of the file.
+        let detected_formats: [Box<dyn Format>; 4] = [Box::new(FormatPNG::new()), Box::new(FormatPNM::new()), Box::new(FormatHDR::new()), Box::new(FormatEXR::new())];
+
+        let format: &dyn Format = if let Some(format) = &self.format
+        {
+            format.as_ref()
+        }
+        else
+        {
+            let header = reader.fill_buf()?;
+
+            detected_formats.iter().find(|format| format.is_signature(header))
+                .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Unknown image format!"))?.as_ref()
+        };
+
+        let surface = format.read(&mut reader)?;
+
+        let surface = if self.is_converted_to_linear && format.is_srgb()
+        {
+            let pixels = surface.as_pixel_slice().iter().map(|pixel| color::srgb_to_linear(*pixel)).collect();
+            Surface::from(surface.get_width(), surface.get_height(), pixels)
+        }
+        else
+        {
+            surface
+        };
+
+        println!("Loaded {} file in {} seconds.", format.get_name(), begin_time.elapsed().as_secs_f32());
+
+        Ok(surface)
+    }
+}
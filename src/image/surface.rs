@@ -1,3 +1,4 @@
+use std::hash::{ Hash, Hasher };
 use super::math::Vec4;
 
 pub struct Surface
@@ -9,6 +10,14 @@ pub struct Surface
 
 impl Surface
 {
+    // Largest image that readers accept, so corrupt headers cannot request huge allocations.
+    pub const MAX_PIXEL_COUNT: usize = 1 << 27;
+
+    pub fn checked_pixel_count(width: usize, height: usize) -> Option<usize>
+    {
+        width.checked_mul(height).filter(|pixel_count| *pixel_count <= Self::MAX_PIXEL_COUNT)
+    }
+
     pub fn new(width: usize, height: usize) -> Self
     {
         debug_assert!(width > 0);
@@ -70,6 +79,24 @@ impl Surface
     }
 }
 
+impl Hash for Surface
+{
+    fn hash<H: Hasher>(&self, state: &mut H)
+    {
+        // Pixels are hashed by bits of their channels, which is exact for images loaded from the same file.
+        self.width.hash(state);
+        self.height.hash(state);
+
+        for pixel in &self.pixels
+        {
+            for channel in &[pixel.get_r(), pixel.get_g(), pixel.get_b(), pixel.get_a()]
+            {
+                channel.to_bits().hash(state);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests
 {
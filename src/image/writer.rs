@@ -7,6 +7,7 @@ pub enum Error
     MissingInput,
     MissingOutput,
     InvalidPath,
+    UnsupportedLayers,
     SaveFailed
 }
 
@@ -14,12 +15,26 @@ pub trait Format
 {
     fn save(&self, image: &Surface, path: &Path) -> Result<(), Error>;
     fn get_name(&self) -> &'static str;
+
+    /// # Errors
+    ///
+    /// Returns error when format cannot store several layers or saving fails.
+    fn save_layers(&self, layers: &[(&str, &Surface)], path: &Path) -> Result<(), Error>
+    {
+        // Most formats can only store single image.
+        match layers
+        {
+            [(_, image)] => self.save(image, path),
+            _ => Err(Error::UnsupportedLayers)
+        }
+    }
 }
 
 pub struct Writer<'a>
 {
     format: Box<dyn Format + 'a>,
     surface: Option<&'a Surface>,
+    layers: Vec<(&'a str, &'a Surface)>,
     path: Option<PathBuf>
 }
 
@@ -31,6 +46,7 @@ impl<'a> Writer<'a>
         {
             format: Box::new(format),
             surface: None,
+            layers: Vec::new(),
             path: None
         }
     }
@@ -41,6 +57,13 @@ impl<'a> Writer<'a>
         self
     }
 
+    #[must_use]
+    pub fn layer(mut self, name: &'a str, surface: &'a Surface) -> Self
+    {
+        self.layers.push((name, surface));
+        self
+    }
+
     pub fn output<P: AsRef<Path>>(mut self, path: P) -> Self
     {
         self.path = Some(PathBuf::from(path.as_ref()));
@@ -64,7 +87,18 @@ impl<'a> Writer<'a>
                 }
             };
 
-            self.format.save(surface, path)?;
+            if self.layers.is_empty()
+            {
+                self.format.save(surface, path)?;
+            }
+            else
+            {
+                // Main image is stored as unnamed layer, in front of additional ones.
+                let mut layers = vec![("", surface)];
+                layers.extend(self.layers.iter().copied());
+
+                self.format.save_layers(&layers, path)?;
+            }
 
             println!("Saved {} file in {} seconds.", self.format.get_name(), begin_time.elapsed().as_secs_f32());
         }
@@ -1,8 +1,22 @@
 use std::path::{ Path, PathBuf };
+use std::net::TcpListener;
+use std::time::{ Duration, Instant };
 use clap::{ App, Arg };
 use raytracer::render;
 use raytracer::image;
 
+fn create_writer<'a>(path: &Path) -> image::Writer<'a>
+{
+    // Image format is chosen based on file extension, with PNG used by default.
+    match path.extension().and_then(|extension| extension.to_str()).map(str::to_lowercase).as_deref()
+    {
+        Some("exr") => image::Writer::new(image::FormatEXR::new()),
+        Some("hdr") => image::Writer::new(image::FormatHDR::new()),
+        Some("pnm" | "ppm") => image::Writer::new(image::FormatPNM::new()),
+        _ => image::Writer::new(image::FormatPNG::new())
+    }
+}
+
 fn main() 
 {
     // Declare application entry.
@@ -11,15 +25,57 @@ fn main()
         .author("Piotr Doan <doanpiotr@gmail.com>")
         .about("Raytracer written in Rust.")
         .arg(Arg::with_name("input")
-            .index(1).required(true)
+            .index(1).required_unless("worker")
             .short("i").long("input")
             .help("Path to input setup file in JSON format"))
         .arg(Arg::with_name("output")
             .index(2)
             .short("o").long("output")
-            .help("Path to output image file in PNG format"))
+            .help("Path to output image file, in PNG, PNM, HDR or EXR format depending on its extension"))
+        .arg(Arg::with_name("pass-samples")
+            .long("pass-samples")
+            .takes_value(true).value_name("COUNT")
+            .default_value("1")
+            .help("Number of samples added to each pixel in single progressive pass"))
+        .arg(Arg::with_name("snapshot-passes")
+            .long("snapshot-passes")
+            .takes_value(true).value_name("COUNT")
+            .help("Render progressively and write intermediate image every given number of passes"))
+        .arg(Arg::with_name("snapshot-seconds")
+            .long("snapshot-seconds")
+            .takes_value(true).value_name("SECONDS")
+            .help("Render progressively and write intermediate image every given number of seconds"))
+        .arg(Arg::with_name("checkpoint-seconds")
+            .long("checkpoint-seconds")
+            .takes_value(true).value_name("SECONDS")
+            .help("Render progressively and write checkpoint next to output image every given number of seconds"))
+        .arg(Arg::with_name("resume")
+            .long("resume")
+            .help("Resume render from checkpoint next to output image, which must have been written for the same setup"))
+        .arg(Arg::with_name("coordinator")
+            .long("coordinator")
+            .takes_value(true).value_name("ADDRESS")
+            .help("Distribute tiles of the image to workers connecting to given address"))
+        .arg(Arg::with_name("worker")
+            .long("worker")
+            .takes_value(true).value_name("ADDRESS")
+            .conflicts_with("coordinator")
+            .help("Render tiles assigned by coordinator at given address, which also provides the setup"))
+        .arg(Arg::with_name("denoise")
+            .long("denoise")
+            .help("Denoise rendered image with default settings, unless setup file already configures denoiser"))
         .get_matches();
 
+    // Worker does not need any other arguments, as it receives everything from coordinator.
+    if let Some(address) = options.value_of("worker")
+    {
+        println!("Connecting to coordinator at {}.", address);
+
+        let tile_count = render::distributed::Worker::new().run(address).expect("Distributed rendering failed!");
+        println!("Rendered {} tiles.", tile_count);
+        return;
+    }
+
     // Parse input setup file path from arguments.
     let input_setup_path = Path::new(options.value_of("input").unwrap());
     let input_setup_filename = input_setup_path.file_name().expect("Expected input path that points to a file!");
@@ -39,20 +95,237 @@ fn main()
     println!("Output image file: {}", output_image_path.display());
 
     // Load setup from input file.
-    let setup = render::Setup::from_file(&input_setup_path).expect("Loading setup file failed!");
+    let mut setup = render::Setup::from_file(&input_setup_path).expect("Loading setup file failed!");
+
+    if options.is_present("denoise") && setup.parameters.denoiser.is_none()
+    {
+        setup.parameters.denoiser = Some(render::Denoiser::new());
+    }
 
     // Save setup to output file.
     // This can be helpful if we will want to convert old setup file to new format or compare both.
     setup.save(&output_setup_path).expect("Saving setup file failed!");
     
+    // Parse progressive rendering options from arguments.
+    let pass_samples = options.value_of("pass-samples").unwrap().parse::<usize>().expect("Expected number of samples per pass!");
+    let snapshot_passes = options.value_of("snapshot-passes").map(|value| value.parse::<usize>().expect("Expected number of passes between snapshots!"));
+    let snapshot_seconds = options.value_of("snapshot-seconds").map(|value| value.parse::<f32>().expect("Expected number of seconds between snapshots!"));
+
+    // Parse checkpoint options from arguments, checkpoint file is stored next to output image.
+    let checkpoint_path = output_image_path.with_extension("checkpoint");
+    let checkpoint_seconds = options.value_of("checkpoint-seconds").map(|value| value.parse::<f32>().expect("Expected number of seconds between checkpoints!"));
+
+    let checkpoint = if options.is_present("resume")
+    {
+        println!("Checkpoint file: {}", checkpoint_path.display());
+
+        // Checkpoint that cannot be used is reported before any rendering starts.
+        let checkpoint = match render::Checkpoint::load(&checkpoint_path, &setup.parameters)
+        {
+            Ok(checkpoint) if checkpoint.matches(&setup.parameters, &setup.scene) => checkpoint,
+            Ok(_) =>
+            {
+                println!("Setup has changed since checkpoint was written, or needs output variables it does not store, cannot resume render!");
+                std::process::exit(1);
+            },
+            Err(error) =>
+            {
+                println!("Checkpoint loading error: {}", error);
+                std::process::exit(1);
+            }
+        };
+
+        println!("Resuming render after pass {}.", checkpoint.pass_count);
+        Some(checkpoint)
+    }
+    else
+    {
+        None
+    };
+
     // Render image.
-    let image = render::Renderer::new()
+    let mut renderer = render::Renderer::new()
         .set_parameters(&setup.parameters)
         .set_scene(&setup.scene)
-        .render();
+        .set_progress_callback(|progress|
+        {
+            // Report progress of each pass in steps of ten percent.
+            if progress.tiles_done * 10 / progress.tile_count != (progress.tiles_done - 1) * 10 / progress.tile_count
+            {
+                let remaining = progress.estimated_remaining().unwrap_or_default();
+                println!("Pass {}: {:.0}% done, {:.1} seconds remaining.", progress.pass_index, progress.fraction() * 100.0, remaining.as_secs_f32());
+            }
+        });
+
+    if let Some(seconds) = checkpoint_seconds
+    {
+        renderer = renderer.set_checkpoint(&checkpoint_path, Duration::from_secs_f32(seconds));
+    }
+
+    if let Some(checkpoint) = &checkpoint
+    {
+        renderer = renderer.set_resume_checkpoint(checkpoint);
+    }
+
+    // Formats with limited range receive tone mapped image, while others keep linear values.
+    let extension = output_image_path.extension().and_then(|extension| extension.to_str()).unwrap_or("png").to_lowercase();
+    let is_layered = extension == "exr";
+    let is_high_dynamic_range = is_layered || extension == "hdr";
+
+    // Workers only send rendered tiles, so combinations that need anything else are rejected before waiting for them.
+    if options.is_present("coordinator") && !setup.parameters.rendered_aovs().is_empty()
+    {
+        println!("Output variables and denoising are not supported by distributed render, render locally to produce them!");
+        std::process::exit(1);
+    }
+
+    let (image, aovs) = if let Some(address) = options.value_of("coordinator")
+    {
+        // Resources referenced by setup are loaded by workers, so its directory needs to be available on their machines too.
+        let listener = TcpListener::bind(address).expect("Failed to listen for workers!");
+        let directory = input_setup_path.parent().and_then(|directory| directory.canonicalize().ok()).unwrap_or_default();
+        println!("Waiting for workers at {}.", address);
+
+        let image = render::distributed::Coordinator::new()
+            .set_setup(&setup)
+            .set_directory(directory)
+            .render(&listener).expect("Distributed rendering failed!");
+
+        (image, Vec::new())
+    }
+    else if snapshot_passes.is_some() || snapshot_seconds.is_some() || checkpoint_seconds.is_some()
+    {
+        // Intermediate images are written to output path, which is later overwritten with final image.
+        let mut snapshot_time = Instant::now();
+
+        renderer.render_progressive_with_aovs(pass_samples, |pass_index, image|
+        {
+            let is_pass_due = snapshot_passes.is_some_and(|passes| pass_index % passes.max(1) == 0);
+            let is_time_due = snapshot_seconds.is_some_and(|seconds| snapshot_time.elapsed().as_secs_f32() >= seconds);
+
+            if is_pass_due || is_time_due
+            {
+                println!("Writing snapshot after pass {}.", pass_index);
+                snapshot_time = Instant::now();
+
+                let tone_mapped_image;
+
+                let image = if is_high_dynamic_range
+                {
+                    image
+                }
+                else
+                {
+                    tone_mapped_image = setup.parameters.tone_mapping.apply(image);
+                    &tone_mapped_image
+                };
+
+                create_writer(&output_image_path)
+                    .input(image).output(&output_image_path)
+                    .save().expect("Failed to save snapshot image!");
+            }
+        }).expect("Rendering failed!")
+    }
+    else
+    {
+        renderer.render_with_aovs().expect("Rendering failed!")
+    };
+
+    // Denoised image replaces rendered one, which is still saved next to it.
+    let rendered_aovs = setup.parameters.rendered_aovs();
+
+    let (image, noisy_image) = match &setup.parameters.denoiser
+    {
+        Some(denoiser) if !aovs.is_empty() =>
+        {
+            let begin_time = Instant::now();
+            let denoised_image = denoiser.apply(&image, &rendered_aovs, &aovs);
+            println!("Denoised image in {} seconds.", begin_time.elapsed().as_secs_f32());
+
+            (denoised_image, Some(image))
+        },
+        _ => (image, None)
+    };
+
+    // Save rendered image, together with output variables stored as its layers if format supports them.
+    // Guides rendered only for denoiser are not saved.
+    let mut aov_layers: Vec<(&str, &image::Surface)> = rendered_aovs.iter().zip(&aovs)
+        .filter(|(aov, _)| setup.parameters.aovs.contains(aov))
+        .map(|(aov, layer)| (aov.name(), layer)).collect();
+
+    let tone_mapped_image;
+
+    let image = if is_high_dynamic_range
+    {
+        &image
+    }
+    else
+    {
+        tone_mapped_image = setup.parameters.tone_mapping.apply(&image);
+        &tone_mapped_image
+    };
+
+    let mut writer = create_writer(&output_image_path).input(image).output(&output_image_path);
+
+    if let Some(noisy_image) = &noisy_image
+    {
+        aov_layers.push(("noisy", noisy_image));
+    }
+
+    if is_layered
+    {
+        writer = aov_layers.iter().fold(writer, |writer, (name, layer)| writer.layer(name, layer));
+    }
+
+    writer.save().expect("Failed to save rendered image!");
+
+    // Without layers, image from before denoising is saved as separate file.
+    if let (Some(noisy_image), false) = (&noisy_image, is_layered)
+    {
+        let noisy_image_path = output_image_path.with_extension(format!("noisy.{}", extension));
+        let tone_mapped_noisy_image;
+
+        let noisy_image = if is_high_dynamic_range
+        {
+            noisy_image
+        }
+        else
+        {
+            tone_mapped_noisy_image = setup.parameters.tone_mapping.apply(noisy_image);
+            &tone_mapped_noisy_image
+        };
+
+        println!("Noisy image file: {}", noisy_image_path.display());
+
+        create_writer(&noisy_image_path)
+            .input(noisy_image).output(&noisy_image_path)
+            .save().expect("Failed to save noisy image!");
+    }
+
+    // Otherwise save each output variable next to rendered image, named after the variable.
+    // Formats limited to displayable range receive visualization instead of raw values.
+    if !is_layered
+    {
+        for (aov, layer) in rendered_aovs.iter().zip(&aovs).filter(|(aov, _)| setup.parameters.aovs.contains(aov))
+        {
+            let aov_image_path = output_image_path.with_extension(format!("{}.{}", aov.name(), extension));
+            let visualized_layer;
+
+            let aov_image = if is_high_dynamic_range
+            {
+                layer
+            }
+            else
+            {
+                visualized_layer = aov.visualize(layer);
+                &visualized_layer
+            };
+
+            println!("Output variable file: {}", aov_image_path.display());
 
-    // Save rendered image.
-    image::Writer::new(image::FormatPNG::new())
-        .input(&image).output(&output_image_path)
-        .save().expect("Failed to save rendered image!");
+            create_writer(&aov_image_path)
+                .input(aov_image).output(&aov_image_path)
+                .save().expect("Failed to save output variable image!");
+        }
+    }
 }
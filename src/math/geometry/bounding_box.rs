@@ -0,0 +1,183 @@
+use super::types::vec3::Vec3;
+use super::types::ray::Ray;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BoundingBox
+{
+    pub min: Vec3,
+    pub max: Vec3
+}
+
+impl Default for BoundingBox
+{
+    fn default() -> Self
+    {
+        Self::empty()
+    }
+}
+
+impl BoundingBox
+{
+    pub fn new(min: Vec3, max: Vec3) -> Self
+    {
+        Self
+        {
+            min,
+            max
+        }
+    }
+
+    pub fn empty() -> Self
+    {
+        Self
+        {
+            min: Vec3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY),
+            max: Vec3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY)
+        }
+    }
+
+    pub fn from_point(point: Vec3) -> Self
+    {
+        Self::new(point, point)
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.min.get_x() > self.max.get_x() ||
+        self.min.get_y() > self.max.get_y() ||
+        self.min.get_z() > self.max.get_z()
+    }
+
+    #[must_use]
+    pub fn merged(&self, other: &Self) -> Self
+    {
+        Self
+        {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max)
+        }
+    }
+
+    #[must_use]
+    pub fn expanded(&self, point: Vec3) -> Self
+    {
+        Self
+        {
+            min: self.min.min(point),
+            max: self.max.max(point)
+        }
+    }
+
+    pub fn center(&self) -> Vec3
+    {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn extent(&self) -> Vec3
+    {
+        self.max - self.min
+    }
+
+    pub fn surface_area(&self) -> f32
+    {
+        if self.is_empty()
+        {
+            return 0.0;
+        }
+
+        let extent = self.extent();
+        2.0 * (extent.get_x() * extent.get_y() + extent.get_y() * extent.get_z() + extent.get_z() * extent.get_x())
+    }
+
+    pub fn largest_axis(&self) -> usize
+    {
+        let extent = self.extent();
+
+        if extent.get_x() >= extent.get_y() && extent.get_x() >= extent.get_z()
+        {
+            0
+        }
+        else if extent.get_y() >= extent.get_z()
+        {
+            1
+        }
+        else
+        {
+            2
+        }
+    }
+
+    pub fn intersect(&self, ray: &Ray, inverse_direction: Vec3, min_length: f32, max_length: f32) -> bool
+    {
+        // Slab test based on: https://tavianator.com/fast-branchless-raybounding-box-intersections/
+        // Inverse direction is passed in so it can be calculated once per ray instead of once per box.
+
+        let near = (self.min - ray.origin()) * inverse_direction;
+        let far = (self.max - ray.origin()) * inverse_direction;
+
+        let entry = near.min(far);
+        let exit = near.max(far);
+
+        let entry_length = entry.get_x().max(entry.get_y()).max(entry.get_z()).max(min_length);
+        let exit_length = exit.get_x().min(exit.get_y()).min(exit.get_z()).min(max_length);
+
+        entry_length <= exit_length
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn new()
+    {
+        let bounds = BoundingBox::new(Vec3::new(-1.0, -2.0, -3.0), Vec3::new(1.0, 2.0, 3.0));
+
+        assert!(!bounds.is_empty());
+        assert_eq!(bounds.center(), Vec3::new(0.0, 0.0, 0.0));
+        assert_eq!(bounds.extent(), Vec3::new(2.0, 4.0, 6.0));
+        assert_eq!(bounds.surface_area(), 88.0);
+        assert_eq!(bounds.largest_axis(), 2);
+
+        assert!(BoundingBox::empty().is_empty());
+        assert_eq!(BoundingBox::empty().surface_area(), 0.0);
+        assert_eq!(BoundingBox::default(), BoundingBox::empty());
+    }
+
+    #[test]
+    fn merge()
+    {
+        let bounds = BoundingBox::empty()
+            .expanded(Vec3::new(1.0, 0.0, 0.0))
+            .expanded(Vec3::new(0.0, -1.0, 2.0));
+
+        assert_eq!(bounds, BoundingBox::new(Vec3::new(0.0, -1.0, 0.0), Vec3::new(1.0, 0.0, 2.0)));
+
+        let merged = bounds.merged(&BoundingBox::from_point(Vec3::new(3.0, 3.0, 3.0)));
+        assert_eq!(merged, BoundingBox::new(Vec3::new(0.0, -1.0, 0.0), Vec3::new(3.0, 3.0, 3.0)));
+        assert_eq!(merged.merged(&BoundingBox::empty()), merged);
+    }
+
+    #[test]
+    fn intersect()
+    {
+        let bounds = BoundingBox::new(Vec3::new(-1.0, 4.0, -1.0), Vec3::new(1.0, 6.0, 1.0));
+
+        let ray_forward = Ray::new(Vec3::zero(), Vec3::forward(), 0.0);
+        let ray_backward = Ray::new(Vec3::zero(), Vec3::forward() * -1.0, 0.0);
+        let ray_side = Ray::new(Vec3::new(2.0, 0.0, 0.0), Vec3::forward(), 0.0);
+
+        let inverse_forward = Vec3::one() / ray_forward.direction();
+        let inverse_backward = Vec3::one() / ray_backward.direction();
+        let inverse_side = Vec3::one() / ray_side.direction();
+
+        assert!(bounds.intersect(&ray_forward, inverse_forward, 0.0, f32::MAX));
+        assert!(!bounds.intersect(&ray_forward, inverse_forward, 0.0, 3.0));
+        assert!(!bounds.intersect(&ray_forward, inverse_forward, 7.0, f32::MAX));
+        assert!(!bounds.intersect(&ray_backward, inverse_backward, 0.0, f32::MAX));
+        assert!(!bounds.intersect(&ray_side, inverse_side, 0.0, f32::MAX));
+    }
+}
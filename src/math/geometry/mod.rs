@@ -3,3 +3,7 @@ use super::intersection;
 
 pub mod sphere;
 pub use sphere::Sphere;
+pub mod triangle;
+pub use triangle::Triangle;
+pub mod bounding_box;
+pub use bounding_box::BoundingBox;
@@ -1,8 +1,10 @@
 use serde::{ Serialize, Deserialize };
+use super::types::vec2::Vec2;
 use super::types::vec3::Vec3;
 use super::types::ray::Ray;
 use super::intersection::Intersectable;
 use super::intersection::Intersection;
+use super::bounding_box::BoundingBox;
 
 #[derive(Copy, Clone, Serialize, Deserialize)]
 pub struct Sphere
@@ -21,6 +23,38 @@ impl Sphere
             radius
         }
     }
+
+    pub fn uv(&self, point: Vec3) -> Vec2
+    {
+        // Spherical mapping with U going around up axis and V going from bottom to top pole.
+        let direction = (point - self.center) / self.radius.abs();
+
+        Vec2::new
+        (
+            0.5 + direction.get_y().atan2(direction.get_x()) / (2.0 * std::f32::consts::PI),
+            0.5 + direction.get_z().clamp(-1.0, 1.0).asin() / std::f32::consts::PI
+        )
+    }
+
+    fn intersection_at(&self, ray: &Ray, length: f32) -> Intersection
+    {
+        let point = ray.point_at(length);
+
+        Intersection
+        {
+            point,
+            normal: (point - self.center) / self.radius,
+            uv: self.uv(point),
+            length
+        }
+    }
+
+    pub fn bounding_box(&self) -> BoundingBox
+    {
+        // Radius can be negative for inverted spheres, which are used as hollow glass shells.
+        let radius = Vec3::one() * self.radius.abs();
+        BoundingBox::new(self.center - radius, self.center + radius)
+    }
 }
 
 impl Intersectable for Sphere
@@ -48,14 +82,7 @@ impl Intersectable for Sphere
                 
                 if min_length < r1 && r1 < max_length
                 {
-                    let intersection_point = ray.point_at(r1);
-
-                    return Some(Intersection
-                    {
-                        point: intersection_point,
-                        normal: (intersection_point - self.center) / self.radius,
-                        length: r1
-                    });
+                    return Some(self.intersection_at(ray, r1));
                 }
             }
 
@@ -64,14 +91,7 @@ impl Intersectable for Sphere
 
                 if min_length < r2 && r2 < max_length
                 {
-                    let intersection_point = ray.point_at(r2);
-
-                    return Some(Intersection
-                    {
-                        point: intersection_point,
-                        normal: (intersection_point - self.center) / self.radius,
-                        length: r2
-                    });
+                    return Some(self.intersection_at(ray, r2));
                 }
             }
         }
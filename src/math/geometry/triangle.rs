@@ -0,0 +1,109 @@
+use serde::{ Serialize, Deserialize };
+use super::types::vec2::Vec2;
+use super::types::vec3::Vec3;
+use super::types::ray::Ray;
+use super::intersection::Intersectable;
+use super::intersection::Intersection;
+use super::bounding_box::BoundingBox;
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub struct Triangle
+{
+    pub a: Vec3,
+    pub b: Vec3,
+    pub c: Vec3
+}
+
+impl Triangle
+{
+    pub fn new(a: Vec3, b: Vec3, c: Vec3) -> Self
+    {
+        Self
+        {
+            a,
+            b,
+            c
+        }
+    }
+
+    pub fn normal(&self) -> Vec3
+    {
+        // Front face is determined by counter clockwise winding order.
+        // Cross product of small triangle is tiny, so it is divided by its length directly instead of checked normalization.
+        let cross = (self.b - self.a).cross(self.c - self.a);
+        cross / cross.length()
+    }
+
+    pub fn bounding_box(&self) -> BoundingBox
+    {
+        BoundingBox::new(self.a.min(self.b).min(self.c), self.a.max(self.b).max(self.c))
+    }
+
+    #[allow(clippy::similar_names)]
+    pub fn intersect_barycentric(&self, ray: &Ray, min_length: f32, max_length: f32) -> Option<(f32, f32, f32)>
+    {
+        // Implementation based on: https://en.wikipedia.org/wiki/M%C3%B6ller%E2%80%93Trumbore_intersection_algorithm
+        // Returns length along the ray and barycentric coordinates along AB and AC edges.
+
+        debug_assert!(ray.direction().is_unit());
+
+        let edge_ab = self.b - self.a;
+        let edge_ac = self.c - self.a;
+
+        let direction_cross_ac = ray.direction().cross(edge_ac);
+        let determinant = edge_ab.dot(direction_cross_ac);
+
+        // Ray is parallel to the triangle plane or triangle is degenerate.
+        // Threshold is scaled by the edges, so small triangles of detailed models are still hit.
+        if determinant.abs() <= 1.0e-6 * edge_ab.length() * edge_ac.length()
+        {
+            return None;
+        }
+
+        let determinant_inv = 1.0 / determinant;
+        let origin_offset = ray.origin() - self.a;
+        let along_ab = origin_offset.dot(direction_cross_ac) * determinant_inv;
+
+        if !(0.0..=1.0).contains(&along_ab)
+        {
+            return None;
+        }
+
+        let offset_cross_ab = origin_offset.cross(edge_ab);
+        let along_ac = ray.direction().dot(offset_cross_ab) * determinant_inv;
+
+        if along_ac < 0.0 || along_ab + along_ac > 1.0
+        {
+            return None;
+        }
+
+        let length = edge_ac.dot(offset_cross_ab) * determinant_inv;
+
+        if min_length < length && length < max_length
+        {
+            Some((length, along_ab, along_ac))
+        }
+        else
+        {
+            None
+        }
+    }
+}
+
+impl Intersectable for Triangle
+{
+    fn intersect(&self, ray: &Ray, min_length: f32, max_length: f32) -> Option<Intersection>
+    {
+        // Without texture coordinates, barycentric coordinates are used as UVs.
+        self.intersect_barycentric(ray, min_length, max_length).map(|(length, along_ab, along_ac)|
+        {
+            Intersection
+            {
+                point: ray.point_at(length),
+                normal: self.normal(),
+                uv: Vec2::new(along_ab, along_ac),
+                length
+            }
+        })
+    }
+}
@@ -0,0 +1,312 @@
+use super::types::vec3::Vec3;
+use super::types::ray::Ray;
+use super::geometry::bounding_box::BoundingBox;
+use super::intersection::Intersection;
+
+// Implementation based on: http://www.pbr-book.org/3ed-2018/Primitives_and_Intersection_Acceleration/Bounding_Volume_Hierarchies.html
+// Nodes are stored in depth first order, so first child of a branch always directly follows its parent.
+
+const BUCKET_COUNT: usize = 12;
+const LEAF_ITEMS_MAX: usize = 4;
+const DEPTH_MAX: usize = 64;
+const TRAVERSAL_COST: f32 = 0.125;
+
+enum NodeKind
+{
+    Leaf
+    {
+        first_item: usize,
+        item_count: usize
+    },
+    Branch
+    {
+        second_child: usize,
+        split_axis: usize
+    }
+}
+
+struct Node
+{
+    bounds: BoundingBox,
+    kind: NodeKind
+}
+
+#[derive(Default)]
+pub struct Hierarchy
+{
+    nodes: Vec<Node>,
+    items: Vec<usize>
+}
+
+impl Hierarchy
+{
+    pub fn new(bounds: &[BoundingBox]) -> Self
+    {
+        let mut items: Vec<usize> = (0..bounds.len()).filter(|index| !bounds[*index].is_empty()).collect();
+        let centroids: Vec<Vec3> = bounds.iter().map(BoundingBox::center).collect();
+
+        let mut nodes = Vec::with_capacity(items.len() * 2);
+
+        if !items.is_empty()
+        {
+            Self::build_node(&mut nodes, bounds, &centroids, &mut items, 0, 0);
+        }
+
+        Self
+        {
+            nodes,
+            items
+        }
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.nodes.is_empty()
+    }
+
+    pub fn bounding_box(&self) -> BoundingBox
+    {
+        self.nodes.first().map_or_else(BoundingBox::empty, |root| root.bounds)
+    }
+
+    fn build_node(nodes: &mut Vec<Node>, bounds: &[BoundingBox], centroids: &[Vec3], items: &mut [usize], first_item: usize, depth: usize) -> usize
+    {
+        debug_assert!(!items.is_empty());
+
+        let node_index = nodes.len();
+        let node_bounds = items.iter().fold(BoundingBox::empty(), |merged, item| merged.merged(&bounds[*item]));
+        let centroid_bounds = items.iter().fold(BoundingBox::empty(), |merged, item| merged.expanded(centroids[*item]));
+
+        let split_axis = centroid_bounds.largest_axis();
+        let centroid_min = centroid_bounds.min.get_axis(split_axis);
+        let centroid_extent = centroid_bounds.extent().get_axis(split_axis);
+
+        let make_leaf = |nodes: &mut Vec<Node>|
+        {
+            nodes.push(Node
+            {
+                bounds: node_bounds,
+                kind: NodeKind::Leaf
+                {
+                    first_item,
+                    item_count: items.len()
+                }
+            });
+
+            node_index
+        };
+
+        // Items whose centroids overlap cannot be separated any further.
+        if items.len() == 1 || centroid_extent <= 0.0 || depth + 1 >= DEPTH_MAX
+        {
+            return make_leaf(nodes);
+        }
+
+        // Evaluate surface area heuristic for splits between each bucket.
+        let bucket_index = |item: usize|
+        {
+            let offset = (centroids[item].get_axis(split_axis) - centroid_min) / centroid_extent;
+
+            #[allow(clippy::cast_sign_loss)]
+            #[allow(clippy::cast_possible_truncation)]
+            std::cmp::min((offset * BUCKET_COUNT as f32) as usize, BUCKET_COUNT - 1)
+        };
+
+        let mut bucket_counts = [0_usize; BUCKET_COUNT];
+        let mut bucket_bounds = [BoundingBox::empty(); BUCKET_COUNT];
+
+        for item in items.iter()
+        {
+            let bucket = bucket_index(*item);
+            bucket_counts[bucket] += 1;
+            bucket_bounds[bucket] = bucket_bounds[bucket].merged(&bounds[*item]);
+        }
+
+        let mut best_split = 0;
+        let mut best_cost = f32::MAX;
+
+        for split in 0..BUCKET_COUNT - 1
+        {
+            let (below_count, below_bounds) = (0..=split).fold((0, BoundingBox::empty()),
+                |(count, merged), bucket| (count + bucket_counts[bucket], merged.merged(&bucket_bounds[bucket])));
+
+            let (above_count, above_bounds) = (split + 1..BUCKET_COUNT).fold((0, BoundingBox::empty()),
+                |(count, merged), bucket| (count + bucket_counts[bucket], merged.merged(&bucket_bounds[bucket])));
+
+            let cost = TRAVERSAL_COST + (below_count as f32 * below_bounds.surface_area() +
+                above_count as f32 * above_bounds.surface_area()) / node_bounds.surface_area().max(f32::EPSILON);
+
+            if cost < best_cost
+            {
+                best_cost = cost;
+                best_split = split;
+            }
+        }
+
+        if items.len() <= LEAF_ITEMS_MAX && best_cost >= items.len() as f32
+        {
+            return make_leaf(nodes);
+        }
+
+        // Partition items in place so they end up on the correct side of the split.
+        let mut below_count = 0;
+
+        for index in 0..items.len()
+        {
+            if bucket_index(items[index]) <= best_split
+            {
+                items.swap(index, below_count);
+                below_count += 1;
+            }
+        }
+
+        debug_assert!(below_count > 0 && below_count < items.len());
+
+        nodes.push(Node
+        {
+            bounds: node_bounds,
+            kind: NodeKind::Branch
+            {
+                second_child: 0,
+                split_axis
+            }
+        });
+
+        let (below_items, above_items) = items.split_at_mut(below_count);
+        Self::build_node(nodes, bounds, centroids, below_items, first_item, depth + 1);
+        let above_child = Self::build_node(nodes, bounds, centroids, above_items, first_item + below_count, depth + 1);
+
+        if let NodeKind::Branch { second_child, .. } = &mut nodes[node_index].kind
+        {
+            *second_child = above_child;
+        }
+
+        node_index
+    }
+
+    pub fn intersect<T, F>(&self, ray: &Ray, min_length: f32, max_length: f32, mut intersect_item: F) -> Option<(Intersection, T)>
+        where F: FnMut(usize, f32, f32) -> Option<(Intersection, T)>
+    {
+        if self.nodes.is_empty()
+        {
+            return None;
+        }
+
+        let inverse_direction = Vec3::one() / ray.direction();
+        let direction_negative = [ray.direction().get_x() < 0.0, ray.direction().get_y() < 0.0, ray.direction().get_z() < 0.0];
+
+        let mut closest_intersection: Option<(Intersection, T)> = None;
+        let mut closest_length = max_length;
+
+        let mut stack = [0_usize; DEPTH_MAX];
+        let mut stack_size = 0;
+        let mut node_index = 0;
+
+        loop
+        {
+            let node = &self.nodes[node_index];
+
+            if node.bounds.intersect(ray, inverse_direction, min_length, closest_length)
+            {
+                match node.kind
+                {
+                    NodeKind::Leaf { first_item, item_count } =>
+                    {
+                        for item in &self.items[first_item..first_item + item_count]
+                        {
+                            if let Some((intersection, value)) = intersect_item(*item, min_length, closest_length)
+                            {
+                                debug_assert!(intersection.length <= closest_length);
+
+                                closest_length = intersection.length;
+                                closest_intersection = Some((intersection, value));
+                            }
+                        }
+                    },
+                    NodeKind::Branch { second_child, split_axis } =>
+                    {
+                        // Visit nearer child first, so farther one can be culled by closest length.
+                        if direction_negative[split_axis]
+                        {
+                            stack[stack_size] = node_index + 1;
+                            node_index = second_child;
+                        }
+                        else
+                        {
+                            stack[stack_size] = second_child;
+                            node_index += 1;
+                        }
+
+                        stack_size += 1;
+                        continue;
+                    }
+                }
+            }
+
+            if stack_size == 0
+            {
+                break;
+            }
+
+            stack_size -= 1;
+            node_index = stack[stack_size];
+        }
+
+        closest_intersection
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::math::geometry::sphere::Sphere;
+    use crate::math::Random;
+    use crate::math::intersection::Intersectable;
+
+    #[test]
+    fn empty()
+    {
+        let hierarchy = Hierarchy::new(&[]);
+        let ray = Ray::new(Vec3::zero(), Vec3::forward(), 0.0);
+
+        assert!(hierarchy.is_empty());
+        assert!(hierarchy.bounding_box().is_empty());
+        assert!(hierarchy.intersect(&ray, 0.0, f32::MAX, |_, _, _| -> Option<(Intersection, ())> { unreachable!() }).is_none());
+    }
+
+    #[test]
+    fn intersect()
+    {
+        let mut random = Random::new(0, 0);
+
+        let spheres: Vec<Sphere> = (0..500).map(|_|
+        {
+            Sphere::new(Vec3::random_in_unit_sphere(&mut random) * 10.0, random.next_f32() * 0.5 + 0.01)
+        }).collect();
+
+        let bounds: Vec<BoundingBox> = spheres.iter().map(Sphere::bounding_box).collect();
+        let hierarchy = Hierarchy::new(&bounds);
+
+        assert!(!hierarchy.is_empty());
+        assert_eq!(hierarchy.bounding_box(), bounds.iter().fold(BoundingBox::empty(), |merged, bounds| merged.merged(bounds)));
+
+        for _ in 0..500
+        {
+            let ray = Ray::new(Vec3::random_in_unit_sphere(&mut random) * 12.0, Vec3::random_direction(&mut random), 0.0);
+
+            let expected = spheres.iter().enumerate().fold(None, |closest: Option<(f32, usize)>, (index, sphere)|
+            {
+                let max_length = closest.map_or(f32::MAX, |(length, _)| length);
+                sphere.intersect(&ray, 0.0001, max_length).map_or(closest, |intersection| Some((intersection.length, index)))
+            });
+
+            let result = hierarchy.intersect(&ray, 0.0001, f32::MAX, |index, min_length, max_length|
+            {
+                spheres[index].intersect(&ray, min_length, max_length).map(|intersection| (intersection, index))
+            });
+
+            assert_eq!(expected.map(|(_, index)| index), result.map(|(_, index)| index));
+        }
+    }
+}
@@ -1,10 +1,13 @@
+use super::types::vec2::Vec2;
 use super::types::vec3::Vec3;
 use super::types::ray::Ray;
 
+#[derive(Copy, Clone)]
 pub struct Intersection
 {
     pub point: Vec3,
     pub normal: Vec3,
+    pub uv: Vec2,
     pub length: f32
 }
 
@@ -3,9 +3,17 @@ pub use types::vec2::Vec2;
 pub use types::vec3::Vec3;
 pub use types::vec4::Vec4;
 pub use types::ray::Ray;
+pub use types::mat4::Mat4;
+pub use types::transform::Transform;
 pub mod geometry;
 pub use geometry::sphere::Sphere;
+pub use geometry::triangle::Triangle;
+pub use geometry::bounding_box::BoundingBox;
 pub mod intersection;
 pub use intersection::Intersectable;
 pub use intersection::Intersection;
+pub mod hierarchy;
+pub use hierarchy::Hierarchy;
+pub mod random;
+pub use random::Random;
 pub mod serialization;
@@ -0,0 +1,98 @@
+// Small and fast PCG32 generator, so every pixel can own its random sequence.
+// Implementation based on: https://www.pcg-random.org/download.html
+
+const MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Random
+{
+    state: u64,
+    increment: u64
+}
+
+impl Random
+{
+    pub fn new(seed: u64, stream: u64) -> Self
+    {
+        // Different streams produce independent sequences for the same seed.
+        let mut random = Self
+        {
+            state: 0,
+            increment: (stream << 1) | 1
+        };
+
+        random.next_u32();
+        random.state = random.state.wrapping_add(seed);
+        random.next_u32();
+        random
+    }
+
+    pub fn from_state(state: u64, increment: u64) -> Self
+    {
+        // Restores generator saved with get_state, for example from render checkpoint.
+        Self
+        {
+            state,
+            increment
+        }
+    }
+
+    pub fn get_state(&self) -> (u64, u64)
+    {
+        (self.state, self.increment)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn next_u32(&mut self) -> u32
+    {
+        let state = self.state;
+        self.state = state.wrapping_mul(MULTIPLIER).wrapping_add(self.increment);
+
+        let shifted = (((state >> 18) ^ state) >> 27) as u32;
+        let rotation = (state >> 59) as u32;
+
+        shifted.rotate_right(rotation)
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    pub fn next_f32(&mut self) -> f32
+    {
+        // Uses upper 24 bits, which is the precision of f32 mantissa, so result is always below one.
+        (self.next_u32() >> 8) as f32 * (1.0 / 16_777_216.0)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn sequence()
+    {
+        let mut first = Random::new(42, 54);
+        let mut second = Random::new(42, 54);
+        let mut other = Random::new(42, 55);
+
+        let values: Vec<u32> = (0..16).map(|_| first.next_u32()).collect();
+
+        assert_eq!(values, (0..16).map(|_| second.next_u32()).collect::<Vec<u32>>());
+        assert_ne!(values, (0..16).map(|_| other.next_u32()).collect::<Vec<u32>>());
+
+        let (state, increment) = first.get_state();
+        let mut restored = Random::from_state(state, increment);
+        assert_eq!(restored.next_u32(), first.next_u32());
+    }
+
+    #[test]
+    fn range()
+    {
+        let mut random = Random::new(7, 0);
+
+        for _ in 0..1000
+        {
+            let value = random.next_f32();
+            assert!((0.0..1.0).contains(&value));
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use std::ops;
+use super::vec3::Vec3;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Mat4
+{
+    pub rows: [[f32; 4]; 4]
+}
+
+impl Default for Mat4
+{
+    #[inline]
+    fn default() -> Self
+    {
+        Self::identity()
+    }
+}
+
+impl Mat4
+{
+    #[inline]
+    pub fn new(rows: [[f32; 4]; 4]) -> Self
+    {
+        Self
+        {
+            rows
+        }
+    }
+
+    #[inline]
+    pub fn identity() -> Self
+    {
+        Self::new
+        ([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0]
+        ])
+    }
+
+    #[inline]
+    pub fn translation(offset: Vec3) -> Self
+    {
+        Self::new
+        ([
+            [1.0, 0.0, 0.0, offset.get_x()],
+            [0.0, 1.0, 0.0, offset.get_y()],
+            [0.0, 0.0, 1.0, offset.get_z()],
+            [0.0, 0.0, 0.0, 1.0]
+        ])
+    }
+
+    #[inline]
+    pub fn scale(scale: Vec3) -> Self
+    {
+        Self::new
+        ([
+            [scale.get_x(), 0.0, 0.0, 0.0],
+            [0.0, scale.get_y(), 0.0, 0.0],
+            [0.0, 0.0, scale.get_z(), 0.0],
+            [0.0, 0.0, 0.0, 1.0]
+        ])
+    }
+
+    #[inline]
+    pub fn rotation_x(angle: f32) -> Self
+    {
+        let (sine, cosine) = angle.sin_cos();
+
+        Self::new
+        ([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, cosine, -sine, 0.0],
+            [0.0, sine, cosine, 0.0],
+            [0.0, 0.0, 0.0, 1.0]
+        ])
+    }
+
+    #[inline]
+    pub fn rotation_y(angle: f32) -> Self
+    {
+        let (sine, cosine) = angle.sin_cos();
+
+        Self::new
+        ([
+            [cosine, 0.0, sine, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [-sine, 0.0, cosine, 0.0],
+            [0.0, 0.0, 0.0, 1.0]
+        ])
+    }
+
+    #[inline]
+    pub fn rotation_z(angle: f32) -> Self
+    {
+        let (sine, cosine) = angle.sin_cos();
+
+        Self::new
+        ([
+            [cosine, -sine, 0.0, 0.0],
+            [sine, cosine, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0]
+        ])
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn transposed(&self) -> Self
+    {
+        let mut result = Self::identity();
+
+        for (row, values) in result.rows.iter_mut().enumerate()
+        {
+            for (column, value) in values.iter_mut().enumerate()
+            {
+                *value = self.rows[column][row];
+            }
+        }
+
+        result
+    }
+
+    pub fn inverse(&self) -> Option<Self>
+    {
+        // Gauss-Jordan elimination with partial pivoting.
+        let mut matrix = self.rows;
+        let mut inverse = Self::identity().rows;
+
+        for column in 0..4
+        {
+            let pivot = (column..4).max_by(|a, b|
+            {
+                matrix[*a][column].abs().partial_cmp(&matrix[*b][column].abs()).unwrap_or(std::cmp::Ordering::Equal)
+            })?;
+
+            if matrix[pivot][column].abs() <= f32::EPSILON
+            {
+                return None;
+            }
+
+            matrix.swap(column, pivot);
+            inverse.swap(column, pivot);
+
+            let divisor = matrix[column][column];
+
+            for index in 0..4
+            {
+                matrix[column][index] /= divisor;
+                inverse[column][index] /= divisor;
+            }
+
+            for row in 0..4
+            {
+                if row == column
+                {
+                    continue;
+                }
+
+                let factor = matrix[row][column];
+
+                for index in 0..4
+                {
+                    matrix[row][index] -= factor * matrix[column][index];
+                    inverse[row][index] -= factor * inverse[column][index];
+                }
+            }
+        }
+
+        Some(Self::new(inverse))
+    }
+
+    #[inline]
+    pub fn transform_point(&self, point: Vec3) -> Vec3
+    {
+        // Matrices are expected to be affine, so projective division is not needed.
+        let row = |index: usize|
+        {
+            let values = self.rows[index];
+            values[0] * point.get_x() + values[1] * point.get_y() + values[2] * point.get_z() + values[3]
+        };
+
+        Vec3::new(row(0), row(1), row(2))
+    }
+
+    #[inline]
+    pub fn transform_vector(&self, vector: Vec3) -> Vec3
+    {
+        let row = |index: usize|
+        {
+            let values = self.rows[index];
+            values[0] * vector.get_x() + values[1] * vector.get_y() + values[2] * vector.get_z()
+        };
+
+        Vec3::new(row(0), row(1), row(2))
+    }
+}
+
+impl ops::Mul<Mat4> for Mat4
+{
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, other: Self) -> Self
+    {
+        let mut result = Self::new([[0.0; 4]; 4]);
+
+        for (row, values) in result.rows.iter_mut().enumerate()
+        {
+            for (column, value) in values.iter_mut().enumerate()
+            {
+                *value = (0..4).map(|index| self.rows[row][index] * other.rows[index][column]).sum();
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn assert_near(a: Vec3, b: Vec3)
+    {
+        assert!((a - b).length() < 0.0001, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn new()
+    {
+        assert_eq!(Mat4::default(), Mat4::identity());
+        assert_eq!(Mat4::identity().transposed(), Mat4::identity());
+        assert_eq!(Mat4::identity() * Mat4::identity(), Mat4::identity());
+    }
+
+    #[test]
+    fn transform()
+    {
+        let point = Vec3::new(1.0, 2.0, 3.0);
+
+        assert_eq!(Mat4::translation(Vec3::new(1.0, 1.0, 1.0)).transform_point(point), Vec3::new(2.0, 3.0, 4.0));
+        assert_eq!(Mat4::translation(Vec3::new(1.0, 1.0, 1.0)).transform_vector(point), point);
+        assert_eq!(Mat4::scale(Vec3::new(2.0, 3.0, 4.0)).transform_point(point), Vec3::new(2.0, 6.0, 12.0));
+
+        let quarter = std::f32::consts::FRAC_PI_2;
+        assert_near(Mat4::rotation_x(quarter).transform_vector(Vec3::new(0.0, 1.0, 0.0)), Vec3::new(0.0, 0.0, 1.0));
+        assert_near(Mat4::rotation_y(quarter).transform_vector(Vec3::new(0.0, 0.0, 1.0)), Vec3::new(1.0, 0.0, 0.0));
+        assert_near(Mat4::rotation_z(quarter).transform_vector(Vec3::new(1.0, 0.0, 0.0)), Vec3::new(0.0, 1.0, 0.0));
+
+        let combined = Mat4::translation(Vec3::new(0.0, 0.0, 5.0)) * Mat4::scale(Vec3::new(2.0, 2.0, 2.0));
+        assert_eq!(combined.transform_point(point), Vec3::new(2.0, 4.0, 11.0));
+    }
+
+    #[test]
+    fn inverse()
+    {
+        let matrix = Mat4::translation(Vec3::new(1.0, -2.0, 3.0)) * Mat4::rotation_z(0.7) * Mat4::rotation_x(-0.3) * Mat4::scale(Vec3::new(2.0, 0.5, 3.0));
+        let inverse = matrix.inverse().expect("Matrix should be invertible!");
+        let point = Vec3::new(0.3, 4.0, -1.5);
+
+        assert_near(inverse.transform_point(matrix.transform_point(point)), point);
+        assert_near(matrix.transform_point(inverse.transform_point(point)), point);
+        assert!(Mat4::scale(Vec3::new(1.0, 0.0, 1.0)).inverse().is_none());
+    }
+}
@@ -2,3 +2,5 @@ pub mod vec2;
 pub mod vec3;
 pub mod vec4;
 pub mod ray;
+pub mod mat4;
+pub mod transform;
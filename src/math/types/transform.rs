@@ -0,0 +1,96 @@
+use serde::{ Serialize, Deserialize };
+use super::vec3::Vec3;
+use super::mat4::Mat4;
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transform
+{
+    #[serde(default = "Vec3::zero")]
+    pub translation: Vec3,
+
+    #[serde(default = "Vec3::zero")]
+    pub rotation: Vec3,
+
+    #[serde(default = "Vec3::one")]
+    pub scale: Vec3
+}
+
+impl Default for Transform
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            translation: Vec3::zero(),
+            rotation: Vec3::zero(),
+            scale: Vec3::one()
+        }
+    }
+}
+
+impl Transform
+{
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn set_translation(mut self, translation: Vec3) -> Self
+    {
+        self.translation = translation;
+        self
+    }
+
+    #[must_use]
+    pub fn set_rotation(mut self, rotation: Vec3) -> Self
+    {
+        self.rotation = rotation;
+        self
+    }
+
+    #[must_use]
+    pub fn set_scale(mut self, scale: Vec3) -> Self
+    {
+        self.scale = scale;
+        self
+    }
+
+    pub fn is_identity(&self) -> bool
+    {
+        *self == Self::default()
+    }
+
+    pub fn matrix(&self) -> Mat4
+    {
+        // Rotation is specified as Euler angles in degrees, applied around X, Y and then Z axis.
+        Mat4::translation(self.translation) *
+            Mat4::rotation_z(self.rotation.get_z().to_radians()) *
+            Mat4::rotation_y(self.rotation.get_y().to_radians()) *
+            Mat4::rotation_x(self.rotation.get_x().to_radians()) *
+            Mat4::scale(self.scale)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn matrix()
+    {
+        assert!(Transform::new().is_identity());
+        assert_eq!(Transform::new().matrix(), Mat4::identity());
+
+        let transform = Transform::new()
+            .set_translation(Vec3::new(1.0, 2.0, 3.0))
+            .set_rotation(Vec3::new(0.0, 0.0, 90.0))
+            .set_scale(Vec3::new(2.0, 2.0, 2.0));
+
+        assert!(!transform.is_identity());
+
+        let point = transform.matrix().transform_point(Vec3::new(1.0, 0.0, 0.0));
+        assert!((point - Vec3::new(1.0, 4.0, 3.0)).length() < 0.0001);
+    }
+}
@@ -1,5 +1,6 @@
 use std::cmp;
 use std::ops;
+use super::super::random::Random;
 
 #[derive(Debug, Default, Copy, Clone)]
 pub struct Vec2
@@ -21,9 +22,9 @@ impl Vec2
     }
 
     #[inline]
-    pub fn random_direction() -> Self
+    pub fn random_direction(random: &mut Random) -> Self
     {
-        let azimuth = rand::random::<f32>() * 2.0 * std::f32::consts::PI;
+        let azimuth = random.next_f32() * 2.0 * std::f32::consts::PI;
 
         Self
         {
@@ -158,9 +159,11 @@ mod tests
     #[test]
     fn random()
     {
+        let mut random = Random::new(0, 0);
+
         for _ in 0..100
         {
-            let direction = Vec2::random_direction();
+            let direction = Vec2::random_direction(&mut random);
             assert!(direction.is_unit());
         }
     }
@@ -1,5 +1,5 @@
 use super::vec2::Vec2;
-use rand::Rng;
+use super::super::random::Random;
 
 #[cfg(target_feature = "sse2")]
 pub use self::sse2::*;
@@ -178,6 +178,26 @@ pub mod sse2
         {
             (self.as_zxy() * other - *self * other.as_zxy()).as_zxy()
         }
+
+        #[inline]
+        #[must_use]
+        pub fn min(&self, other: Self) -> Self
+        {
+            unsafe
+            {
+                Self(_mm_min_ps(self.0, other.0))
+            }
+        }
+
+        #[inline]
+        #[must_use]
+        pub fn max(&self, other: Self) -> Self
+        {
+            unsafe
+            {
+                Self(_mm_max_ps(self.0, other.0))
+            }
+        }
     }
 
     impl cmp::PartialEq for Vec3
@@ -404,6 +424,30 @@ pub mod scalar
                 self.0 * other.1 - other.0 * self.1
             )
         }
+
+        #[inline]
+        #[must_use]
+        pub fn min(&self, other: Self) -> Self
+        {
+            Self
+            (
+                self.0.min(other.0),
+                self.1.min(other.1),
+                self.2.min(other.2)
+            )
+        }
+
+        #[inline]
+        #[must_use]
+        pub fn max(&self, other: Self) -> Self
+        {
+            Self
+            (
+                self.0.max(other.0),
+                self.1.max(other.1),
+                self.2.max(other.2)
+            )
+        }
     }
 
     impl cmp::PartialEq for Vec3
@@ -556,6 +600,18 @@ macro_rules! vec3_shared
                 self.get_z()
             }
 
+            #[inline]
+            pub fn get_axis(&self, axis: usize) -> f32
+            {
+                match axis
+                {
+                    0 => self.get_x(),
+                    1 => self.get_y(),
+                    2 => self.get_z(),
+                    _ => panic!("Invalid axis index!")
+                }
+            }
+
             #[inline]
             pub fn length_sqr(&self) -> f32
             {
@@ -616,21 +672,70 @@ macro_rules! vec3_shared
             }
 
             #[inline]
-            pub fn random_direction() -> Self
+            pub fn orthonormal_basis(&self) -> (Self, Self)
             {
-                let z = 2.0 * rand::random::<f32>() - 1.0;
-                let planar = Vec2::random_direction() * (1.0 - z * z).sqrt();
-                Self::new(planar.x, planar.y, z)
+                // Implementation based on: https://graphics.pixar.com/library/OrthonormalB/paper.pdf
+                debug_assert!(self.is_unit());
+
+                let sign = 1.0_f32.copysign(self.get_z());
+                let a = -1.0 / (sign + self.get_z());
+                let b = self.get_x() * self.get_y() * a;
+
+                let tangent = Self::new(1.0 + sign * self.get_x() * self.get_x() * a, sign * b, -sign * self.get_x());
+                let bitangent = Self::new(b, sign + self.get_y() * self.get_y() * a, -self.get_y());
+
+                (tangent, bitangent)
             }
 
             #[inline]
-            pub fn random_in_unit_sphere() -> Self
+            pub fn uniform_direction(sample: Vec2) -> Self
             {
-                let mut thread_rng = rand::thread_rng();
+                // Maps point from unit square to direction uniformly distributed on unit sphere.
+                let z = 2.0 * sample.x - 1.0;
+                let azimuth = sample.y * 2.0 * std::f32::consts::PI;
+                let planar = (1.0 - z * z).max(0.0).sqrt();
+                Self::new(azimuth.cos() * planar, azimuth.sin() * planar, z)
+            }
 
+            #[inline]
+            pub fn uniform_in_unit_disc(sample: Vec2) -> Self
+            {
+                // Concentric mapping from unit square to unit disc, which preserves stratification of samples.
+                // Implementation based on: https://www.pbr-book.org/3ed-2018/Monte_Carlo_Integration/2D_Sampling_with_Multidimensional_Transformations
+                let offset_x = 2.0 * sample.x - 1.0;
+                let offset_y = 2.0 * sample.y - 1.0;
+
+                if offset_x == 0.0 && offset_y == 0.0
+                {
+                    return Self::zero();
+                }
+
+                let (radius, angle) = if offset_x.abs() > offset_y.abs()
+                {
+                    (offset_x, std::f32::consts::FRAC_PI_4 * (offset_y / offset_x))
+                }
+                else
+                {
+                    (offset_y, std::f32::consts::FRAC_PI_2 - std::f32::consts::FRAC_PI_4 * (offset_x / offset_y))
+                };
+
+                Self::new(radius * angle.cos(), radius * angle.sin(), 0.0)
+            }
+
+            #[inline]
+            pub fn random_direction(random: &mut Random) -> Self
+            {
+                let z = random.next_f32();
+                let azimuth = random.next_f32();
+                Self::uniform_direction(Vec2::new(z, azimuth))
+            }
+
+            #[inline]
+            pub fn random_in_unit_sphere(random: &mut Random) -> Self
+            {
                 loop
                 {
-                    let point = Self::new(thread_rng.gen::<f32>(), thread_rng.gen::<f32>(), thread_rng.gen::<f32>()) * 2.0 - Self::new(1.0, 1.0, 1.0);
+                    let point = Self::new(random.next_f32(), random.next_f32(), random.next_f32()) * 2.0 - Self::new(1.0, 1.0, 1.0);
 
                     if point.length_sqr() <= 1.0
                     {
@@ -640,13 +745,11 @@ macro_rules! vec3_shared
             }
 
             #[inline]
-            pub fn random_in_unit_disc() -> Self
+            pub fn random_in_unit_disc(random: &mut Random) -> Self
             {
-                let mut thread_rng = rand::thread_rng();
-
                 loop
                 {
-                    let point = Self::new(thread_rng.gen::<f32>(), thread_rng.gen::<f32>(), 0.0) * 2.0 - Self::new(1.0, 1.0, 0.0);
+                    let point = Self::new(random.next_f32(), random.next_f32(), 0.0) * 2.0 - Self::new(1.0, 1.0, 0.0);
 
                     if point.length_sqr() <= 1.0
                     {
@@ -674,6 +777,8 @@ mod tests
             mod $module
             {
                 use crate::math::types::vec3::$module::Vec3;
+                use crate::math::Random;
+                use crate::math::Vec2;
 
                 #[test]
                 fn new()
@@ -711,27 +816,46 @@ mod tests
                 #[test]
                 fn random()
                 {
+                    let mut random = Random::new(0, 0);
+
                     for _ in 0..100
                     {
-                        let direction = Vec3::random_direction();
+                        let direction = Vec3::random_direction(&mut random);
                         assert!(direction.is_unit());
                     }
 
                     for _ in 0..100
                     {
-                        let point = Vec3::random_in_unit_sphere();
+                        let point = Vec3::random_in_unit_sphere(&mut random);
                         assert!(point.length_sqr() <= 1.0);
                     }
 
                     for _ in 0..100
                     {
-                        let point = Vec3::random_in_unit_disc();
+                        let point = Vec3::random_in_unit_disc(&mut random);
                         assert!(point.length_sqr() <= 1.0);
                         assert!(point.get_z() == 0.0);
                     }
                 }
 
                 #[test]
+                fn uniform()
+                {
+                    assert_eq!(Vec3::uniform_direction(Vec2::new(1.0, 0.0)), Vec3::new(0.0, 0.0, 1.0));
+                    assert_eq!(Vec3::uniform_in_unit_disc(Vec2::new(0.5, 0.5)), Vec3::zero());
+                    assert!((Vec3::uniform_in_unit_disc(Vec2::new(1.0, 0.5)) - Vec3::new(1.0, 0.0, 0.0)).length() < 0.0001);
+
+                    for index in 0..100
+                    {
+                        let sample = Vec2::new((index % 10) as f32 / 10.0, (index / 10) as f32 / 10.0);
+
+                        assert!(Vec3::uniform_direction(sample).is_unit());
+                        assert!(Vec3::uniform_in_unit_disc(sample).length_sqr() <= 1.0 + 0.0001);
+                    }
+                }
+
+                #[test]
+                #[allow(clippy::float_cmp)]
                 fn calculate()
                 {
                     let vec_a = Vec3::new(1.0, 2.0, 3.0);
@@ -758,6 +882,14 @@ mod tests
                     assert_eq!(vec_a - vec_b, Vec3::new(-1.0, -2.0, -3.0));
                     assert_eq!(vec_a * 4.0, Vec3::new(4.0, 8.0, 12.0));
                     assert_eq!(vec_b / 2.0, Vec3::new(1.0, 2.0, 3.0));
+
+                    let vec_c = Vec3::new(3.0, 1.0, 5.0);
+
+                    assert_eq!(vec_a.min(vec_c), Vec3::new(1.0, 1.0, 3.0));
+                    assert_eq!(vec_a.max(vec_c), Vec3::new(3.0, 2.0, 5.0));
+                    assert_eq!(vec_c.get_axis(0), 3.0);
+                    assert_eq!(vec_c.get_axis(1), 1.0);
+                    assert_eq!(vec_c.get_axis(2), 5.0);
                 }
 
                 #[test]
@@ -783,6 +915,21 @@ mod tests
                     Vec3::forward().reflected(Vec3::new(0.5, 0.5, 0.5));
                 }
 
+                #[test]
+                fn basis()
+                {
+                    for normal in &[Vec3::up(), Vec3::up() * -1.0, Vec3::forward(), Vec3::new(1.0, -2.0, 3.0).normalized()]
+                    {
+                        let (tangent, bitangent) = normal.orthonormal_basis();
+
+                        assert!(tangent.is_unit());
+                        assert!(bitangent.is_unit());
+                        assert!(tangent.dot(*normal).abs() < 0.0001);
+                        assert!(bitangent.dot(*normal).abs() < 0.0001);
+                        assert!(tangent.dot(bitangent).abs() < 0.0001);
+                    }
+                }
+
                 #[test]
                 fn refract()
                 {
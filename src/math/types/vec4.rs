@@ -632,6 +632,22 @@ macro_rules! vec4_shared
                 true
             }
 
+            #[inline]
+            pub fn is_finite(&self) -> bool
+            {
+                self.get_x().is_finite() &&
+                self.get_y().is_finite() &&
+                self.get_z().is_finite() &&
+                self.get_w().is_finite()
+            }
+
+            #[inline]
+            pub fn luminance(&self) -> f32
+            {
+                // Relative luminance of linear color with Rec. 709 primaries.
+                0.2126 * self.get_r() + 0.7152 * self.get_g() + 0.0722 * self.get_b()
+            }
+
             #[inline]
             #[allow(clippy::cast_sign_loss)]
             #[allow(clippy::cast_possible_truncation)]
@@ -708,11 +724,23 @@ mod tests
                     assert_eq!(color.clamped(), Vec4::new(0.0, 0.0, 1.0, 1.0));
                 }
 
+                #[test]
+                fn luminance()
+                {
+                    assert!((Vec4::white().luminance() - 1.0).abs() < 0.0001);
+                    assert!(Vec4::black().luminance() == 0.0);
+                    assert!(Vec4::green().luminance() > Vec4::red().luminance());
+                }
+
                 #[test]
                 fn validate()
                 {
                     assert!(Vec4::new(0.0, 0.25, 0.5, 1.0).is_valid() == true);
                     assert!(Vec4::new(-1.0, 0.0, 1.0, 2.0).is_valid() == false);
+
+                    assert!(Vec4::new(-1.0, 0.0, 1.0, 2.0).is_finite() == true);
+                    assert!(Vec4::new(0.0, f32::INFINITY, 0.0, 1.0).is_finite() == false);
+                    assert!(Vec4::new(0.0, 0.0, f32::NAN, 1.0).is_finite() == false);
                 }
 
                 #[test]
@@ -0,0 +1,106 @@
+use serde::{ Serialize, Deserialize };
+use super::math::Vec4;
+use super::image;
+use super::samplers;
+
+// Arbitrary output variables are extra image layers, computed from the same camera rays as the rendered image.
+// Layers store raw values, such as depth in scene units or motion in pixels per unit of time.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Aov
+{
+    Depth,
+    Normal,
+    Albedo,
+    ObjectIndex,
+    MaterialIndex,
+    MotionVector,
+    SampleCount
+}
+
+impl Aov
+{
+    // Every variable in fixed order, which also numbers them in checkpoint files.
+    pub const ALL: [Self; 7] = [Self::Depth, Self::Normal, Self::Albedo, Self::ObjectIndex, Self::MaterialIndex, Self::MotionVector, Self::SampleCount];
+
+    pub fn name(self) -> &'static str
+    {
+        match self
+        {
+            Self::Depth => "depth",
+            Self::Normal => "normal",
+            Self::Albedo => "albedo",
+            Self::ObjectIndex => "object_index",
+            Self::MaterialIndex => "material_index",
+            Self::MotionVector => "motion_vector",
+            Self::SampleCount => "sample_count"
+        }
+    }
+
+    pub fn is_averaged(self) -> bool
+    {
+        // Values that cannot be blended, like depth or indices, are taken from the first sample of each pixel instead.
+        matches!(self, Self::Normal | Self::Albedo | Self::MotionVector)
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn visualize(self, surface: &image::Surface) -> image::Surface
+    {
+        // Maps raw values into displayable range, so layers can be saved in formats limited to it.
+        let pixels = surface.as_pixel_slice();
+
+        let max_value = |value: fn(&Vec4) -> f32| pixels.iter().map(value).filter(|value| value.is_finite()).fold(0.0_f32, f32::max).max(f32::EPSILON);
+        let max_scalar = max_value(Vec4::get_r);
+        let max_motion = max_value(|pixel| pixel.get_r().abs().max(pixel.get_g().abs()));
+
+        let visualized = pixels.iter().map(|pixel| match self
+        {
+            Self::Depth if pixel.get_r().is_finite() =>
+            {
+                // Farthest surfaces remain slightly brighter than empty background.
+                let closeness = 1.0 - pixel.get_r() / max_scalar * 0.8;
+                Vec4::new(closeness, closeness, closeness, 1.0)
+            },
+            Self::Normal => Vec4::new((pixel.get_r() + 1.0) * 0.5, (pixel.get_g() + 1.0) * 0.5, (pixel.get_b() + 1.0) * 0.5, 1.0),
+            Self::Albedo => pixel.clamped(),
+            Self::ObjectIndex | Self::MaterialIndex if pixel.get_r() >= 0.0 =>
+            {
+                // Every index gets its own distinct color.
+                let bytes = samplers::hash(pixel.get_r() as u32).to_le_bytes();
+                Vec4::new(f32::from(bytes[0]) / 255.0, f32::from(bytes[1]) / 255.0, f32::from(bytes[2]) / 255.0, 1.0)
+            },
+            // Background has no depth or index.
+            Self::Depth | Self::ObjectIndex | Self::MaterialIndex => Vec4::black(),
+            Self::MotionVector => Vec4::new(0.5 + pixel.get_r() / max_motion * 0.5, 0.5 + pixel.get_g() / max_motion * 0.5, 0.5, 1.0),
+            Self::SampleCount =>
+            {
+                let fraction = pixel.get_r() / max_scalar;
+                Vec4::new(fraction, fraction, fraction, 1.0)
+            }
+        }).collect();
+
+        image::Surface::from(surface.get_width(), surface.get_height(), visualized)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn visualize()
+    {
+        let depth = image::Surface::from(2, 1, vec![Vec4::new(2.0, 2.0, 2.0, 1.0), Vec4::new(f32::INFINITY, f32::INFINITY, f32::INFINITY, 1.0)]);
+        let visualized = Aov::Depth.visualize(&depth);
+
+        assert!((visualized.get_pixel(0, 0).get_r() - 0.2).abs() < 0.0001);
+        assert_eq!(visualized.get_pixel(1, 0), Vec4::black());
+
+        let indices = image::Surface::from(3, 1, vec![Vec4::new(-1.0, -1.0, -1.0, 1.0), Vec4::new(3.0, 3.0, 3.0, 1.0), Vec4::new(4.0, 4.0, 4.0, 1.0)]);
+        let visualized = Aov::ObjectIndex.visualize(&indices);
+
+        assert_eq!(visualized.get_pixel(0, 0), Vec4::black());
+        assert_ne!(visualized.get_pixel(1, 0), visualized.get_pixel(2, 0));
+        assert!(visualized.as_pixel_slice().iter().all(Vec4::is_valid));
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{ Serialize, Deserialize };
+use crate::math::Vec2;
 use crate::math::Vec3;
 use crate::math::Ray;
+use crate::render::samplers::PixelSampler;
 
 #[derive(Debug)]
 pub enum InvalidParameter
@@ -166,6 +168,7 @@ impl Parameters
             near_plane_width,
             near_plane_height,
 
+            forward_direction,
             right_direction,
             up_direction
         })
@@ -184,21 +187,79 @@ pub struct Compiled
     near_plane_width: Vec3,
     near_plane_height: Vec3,
 
+    forward_direction: Vec3,
     right_direction: Vec3,
     up_direction: Vec3
 }
 
 impl Compiled
 {
-    pub fn calculate_ray(&self, u: f32, v: f32) -> Ray
+    pub fn calculate_ray(&self, u: f32, v: f32, sampler: &mut PixelSampler) -> Ray
     {
-        let random = Vec3::random_in_unit_disc() * self.aperture_radius;
-        let offset = self.right_direction * random.get_x() + self.up_direction * random.get_y();
+        let lens_point = Vec3::uniform_in_unit_disc(sampler.next_2d()) * self.aperture_radius;
+        let offset = self.right_direction * lens_point.get_x() + self.up_direction * lens_point.get_y();
 
         let origin = self.origin + offset;
         let direction = self.near_plane_corner + self.near_plane_width * u + self.near_plane_height * v - origin;
-        let time = self.shutter_open_time + rand::random::<f32>() * (self.shutter_close_time - self.shutter_open_time);
+        let time = self.shutter_open_time + sampler.next_1d() * (self.shutter_close_time - self.shutter_open_time);
 
         Ray::new(origin, direction.normalized(), time)
     }
+
+    pub fn depth(&self, point: Vec3) -> f32
+    {
+        // Distance from camera plane, rather than from its origin.
+        (point - self.origin).dot(self.forward_direction)
+    }
+
+    pub fn project(&self, point: Vec3) -> Option<Vec2>
+    {
+        // Inverse of ray calculation for pinhole camera, returns image coordinates of given point.
+        let depth = self.depth(point);
+
+        if depth <= 0.0
+        {
+            return None;
+        }
+
+        let focus_distance = (self.near_plane_corner - self.origin).dot(self.forward_direction);
+        let plane_point = self.origin + (point - self.origin) * (focus_distance / depth) - self.near_plane_corner;
+
+        Some(Vec2::new
+        (
+            plane_point.dot(self.near_plane_width) / self.near_plane_width.dot(self.near_plane_width),
+            plane_point.dot(self.near_plane_height) / self.near_plane_height.dot(self.near_plane_height)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::render::samplers::Sampler;
+
+    #[test]
+    fn project()
+    {
+        let camera = Parameters::new()
+            .set_origin(Vec3::new(1.0, -2.0, 0.5))
+            .set_look_at(Some(Vec3::new(0.0, 1.0, 0.0)))
+            .set_field_of_view(60.0)
+            .build(16.0 / 9.0).unwrap();
+
+        let mut sampler = Sampler::default().begin_pixel(0, 0, 0, 1);
+
+        for (u, v) in &[(0.5, 0.5), (0.1, 0.8), (0.9, 0.25)]
+        {
+            let ray = camera.calculate_ray(*u, *v, &mut sampler);
+            let point = ray.origin() + ray.direction() * 4.0;
+            let projected = camera.project(point).unwrap();
+
+            assert!((projected.x - u).abs() < 0.0001 && (projected.y - v).abs() < 0.0001);
+            assert!(camera.depth(point) > 0.0 && camera.depth(point) <= 4.0);
+        }
+
+        assert!(camera.project(Vec3::new(1.0, -3.0, 0.5)).is_none());
+    }
 }
@@ -0,0 +1,401 @@
+use std::io::{ BufReader, BufWriter, Read, Write, Error, ErrorKind };
+use std::convert::TryFrom;
+use std::fs::OpenOptions;
+use std::path::Path;
+use super::math::Vec4;
+use super::math::Random;
+use super::film::FilmPixel;
+use super::aovs::Aov;
+use super::parameters::Parameters;
+use super::scene::Scene;
+use super::setup;
+
+// Checkpoint keeps accumulated state of every pixel, so interrupted render can continue where it stopped.
+// File starts with magic, version, setup hash and list of output variables, followed by pixel records and film tiles in little endian.
+const MAGIC: &[u8; 4] = b"RTCP";
+const VERSION: u32 = 4;
+
+pub struct PixelCheckpoint
+{
+    pub aovs: Vec<Vec4>,
+    pub sample_count: usize,
+    pub mean: f32,
+    pub squared_deviations: f32,
+    pub is_finished: bool,
+    pub random: Random
+}
+
+pub struct Checkpoint
+{
+    pub setup_hash: u64,
+    pub image_width: usize,
+    pub image_height: usize,
+    pub pass_count: usize,
+
+    // Output variables stored by each pixel, which can include more than resumed render needs.
+    pub aovs: Vec<Aov>,
+    pub pixels: Vec<PixelCheckpoint>,
+    pub film_tiles: Vec<Vec<FilmPixel>>
+}
+
+fn invalid_data(message: &str) -> Error
+{
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> std::io::Result<u64>
+{
+    let mut bytes = [0_u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_usize<R: Read>(reader: &mut R) -> std::io::Result<usize>
+{
+    usize::try_from(read_u64(reader)?).or(Err(invalid_data("Value does not fit in memory!")))
+}
+
+fn read_f32<R: Read>(reader: &mut R) -> std::io::Result<f32>
+{
+    let mut bytes = [0_u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(f32::from_le_bytes(bytes))
+}
+
+fn read_vec4<R: Read>(reader: &mut R) -> std::io::Result<Vec4>
+{
+    Ok(Vec4::new(read_f32(reader)?, read_f32(reader)?, read_f32(reader)?, read_f32(reader)?))
+}
+
+fn write_vec4<W: Write>(writer: &mut W, value: Vec4) -> std::io::Result<()>
+{
+    for channel in &[value.get_r(), value.get_g(), value.get_b(), value.get_a()]
+    {
+        writer.write_all(&channel.to_le_bytes())?;
+    }
+
+    Ok(())
+}
+
+impl Checkpoint
+{
+    pub fn matches(&self, parameters: &Parameters, scene: &Scene) -> bool
+    {
+        self.setup_hash == setup::hash(parameters, scene) &&
+            self.image_width == parameters.image_width &&
+            self.image_height == parameters.image_height &&
+            self.pixels.len() == parameters.image_width * parameters.image_height &&
+            parameters.rendered_aovs().iter().all(|aov| self.aovs.contains(aov))
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when the file cannot be read or does not match the parameters.
+    pub fn load(path: &Path, parameters: &Parameters) -> std::io::Result<Self>
+    {
+        let checkpoint_file = OpenOptions::new().read(true).open(path)?;
+        Self::read(&mut BufReader::new(checkpoint_file), parameters)
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when the file cannot be written or replaced.
+    pub fn save(&self, path: &Path) -> std::io::Result<()>
+    {
+        // Checkpoint is written next to the old one and then swapped, so killed process never leaves broken file behind.
+        let temporary_path = path.with_extension("tmp");
+
+        {
+            let checkpoint_file = OpenOptions::new().write(true).truncate(true).create(true).open(&temporary_path)?;
+            let mut writer = BufWriter::new(checkpoint_file);
+            self.write(&mut writer)?;
+            writer.flush()?;
+        }
+
+        std::fs::rename(temporary_path, path)
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when reading fails or the data is not a checkpoint of these parameters.
+    pub fn read<R: Read>(reader: &mut R, parameters: &Parameters) -> std::io::Result<Self>
+    {
+        let mut magic = [0_u8; 4];
+        reader.read_exact(&mut magic)?;
+
+        if &magic != MAGIC
+        {
+            return Err(invalid_data("Missing checkpoint header!"));
+        }
+
+        let mut version = [0_u8; 4];
+        reader.read_exact(&mut version)?;
+
+        if u32::from_le_bytes(version) != VERSION
+        {
+            return Err(invalid_data("Unsupported checkpoint version!"));
+        }
+
+        let setup_hash = read_u64(reader)?;
+        let image_width = read_usize(reader)?;
+        let image_height = read_usize(reader)?;
+        let pass_count = read_usize(reader)?;
+        let aov_count = read_usize(reader)?;
+
+        // Sizes are checked against the setup before anything is allocated, so corrupt file cannot request huge buffers.
+        if image_width != parameters.image_width || image_height != parameters.image_height
+        {
+            return Err(invalid_data("Checkpoint does not match image size!"));
+        }
+
+        if aov_count > Aov::ALL.len()
+        {
+            return Err(invalid_data("Invalid output variable count!"));
+        }
+
+        let aovs = (0..aov_count).map(|_|
+        {
+            let mut index = [0_u8; 4];
+            reader.read_exact(&mut index)?;
+            Aov::ALL.get(u32::from_le_bytes(index) as usize).copied().ok_or_else(|| invalid_data("Unknown output variable!"))
+        }).collect::<std::io::Result<Vec<_>>>()?;
+
+        let pixel_count = image_width * image_height;
+        let mut pixels = Vec::with_capacity(pixel_count);
+
+        for _ in 0..pixel_count
+        {
+            let aovs = (0..aov_count).map(|_| read_vec4(reader)).collect::<std::io::Result<Vec<_>>>()?;
+            let sample_count = read_usize(reader)?;
+            let mean = read_f32(reader)?;
+            let squared_deviations = read_f32(reader)?;
+
+            let mut is_finished = [0_u8; 1];
+            reader.read_exact(&mut is_finished)?;
+
+            let random = Random::from_state(read_u64(reader)?, read_u64(reader)?);
+
+            pixels.push(PixelCheckpoint
+            {
+                aovs,
+                sample_count,
+                mean,
+                squared_deviations,
+                is_finished: is_finished[0] != 0,
+                random
+            });
+        }
+
+        // Film tiles are stored separately, since their pixels overlap where filter reaches into neighbouring tiles.
+        let film_tile_count = read_usize(reader)?;
+        let mut film_tiles = Vec::with_capacity(film_tile_count.min(pixel_count));
+
+        for _ in 0..film_tile_count
+        {
+            let film_pixel_count = read_usize(reader)?;
+            let mut film_pixels = Vec::with_capacity(film_pixel_count.min(pixel_count));
+
+            for _ in 0..film_pixel_count
+            {
+                film_pixels.push(FilmPixel { color: read_vec4(reader)?, weight: read_f32(reader)? });
+            }
+
+            film_tiles.push(film_pixels);
+        }
+
+        Ok(Self
+        {
+            setup_hash,
+            image_width,
+            image_height,
+            pass_count,
+            aovs,
+            pixels,
+            film_tiles
+        })
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when writing fails.
+    ///
+    /// # Panics
+    ///
+    /// Panics when checkpoint lists an output variable that is not known.
+    pub fn write<W: Write>(&self, writer: &mut W) -> std::io::Result<()>
+    {
+        debug_assert_eq!(self.pixels.len(), self.image_width * self.image_height);
+
+        // All pixels store the same output variables, so they are listed once in header.
+        let aov_count = self.aovs.len();
+        debug_assert!(self.pixels.iter().all(|pixel| pixel.aovs.len() == aov_count));
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&self.setup_hash.to_le_bytes())?;
+
+        for value in &[self.image_width, self.image_height, self.pass_count, aov_count]
+        {
+            writer.write_all(&(*value as u64).to_le_bytes())?;
+        }
+
+        for aov in &self.aovs
+        {
+            #[allow(clippy::cast_possible_truncation)]
+            let index = Aov::ALL.iter().position(|other| other == aov).expect("Output variable is not listed!") as u32;
+            writer.write_all(&index.to_le_bytes())?;
+        }
+
+        for pixel in &self.pixels
+        {
+            for aov in &pixel.aovs
+            {
+                write_vec4(writer, *aov)?;
+            }
+
+            let (state, increment) = pixel.random.get_state();
+
+            writer.write_all(&(pixel.sample_count as u64).to_le_bytes())?;
+            writer.write_all(&pixel.mean.to_le_bytes())?;
+            writer.write_all(&pixel.squared_deviations.to_le_bytes())?;
+            writer.write_all(&[u8::from(pixel.is_finished)])?;
+            writer.write_all(&state.to_le_bytes())?;
+            writer.write_all(&increment.to_le_bytes())?;
+        }
+
+        writer.write_all(&(self.film_tiles.len() as u64).to_le_bytes())?;
+
+        for film_pixels in &self.film_tiles
+        {
+            writer.write_all(&(film_pixels.len() as u64).to_le_bytes())?;
+
+            for pixel in film_pixels
+            {
+                write_vec4(writer, pixel.color)?;
+                writer.write_all(&pixel.weight.to_le_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use super::super::environment;
+    use super::super::environment::Environment;
+
+    #[test]
+    fn write_read()
+    {
+        let checkpoint = Checkpoint
+        {
+            setup_hash: 0x1234_5678_9abc_def0,
+            image_width: 2,
+            image_height: 1,
+            pass_count: 3,
+            aovs: vec![Aov::Depth, Aov::Normal],
+            pixels: (0..2).map(|index| PixelCheckpoint
+            {
+                aovs: vec![Vec4::new(index as f32, 2.0, 3.0, 1.0), Vec4::zero()],
+                sample_count: 7 + index,
+                mean: 0.25,
+                squared_deviations: 0.125,
+                is_finished: index == 1,
+                random: Random::new(3, index as u64)
+            }).collect(),
+            film_tiles: vec![vec![FilmPixel { color: Vec4::new(0.5, 1.5, 2.5, 1.0), weight: 0.75 }; 3], Vec::new()]
+        };
+
+        let mut data = Vec::new();
+        checkpoint.write(&mut data).unwrap();
+
+        let parameters = Parameters
+        {
+            image_width: 2,
+            image_height: 1,
+            aovs: vec![Aov::Depth, Aov::Normal],
+            ..Parameters::default()
+        };
+
+        let loaded = Checkpoint::read(&mut data.as_slice(), &parameters).unwrap();
+
+        assert_eq!(loaded.setup_hash, checkpoint.setup_hash);
+        assert_eq!((loaded.image_width, loaded.image_height, loaded.pass_count), (2, 1, 3));
+        assert_eq!(loaded.aovs, checkpoint.aovs);
+
+        for (loaded_pixel, pixel) in loaded.pixels.iter().zip(&checkpoint.pixels)
+        {
+            assert_eq!(loaded_pixel.aovs, pixel.aovs);
+            assert_eq!(loaded_pixel.sample_count, pixel.sample_count);
+            assert_eq!(loaded_pixel.is_finished, pixel.is_finished);
+            assert_eq!(loaded_pixel.random, pixel.random);
+        }
+
+        assert_eq!(loaded.film_tiles, checkpoint.film_tiles);
+
+        assert!(Checkpoint::read(&mut &data[..data.len() - 1], &parameters).is_err());
+        assert!(Checkpoint::read(&mut &b"P6\n1 1\n255\n"[..], &parameters).is_err());
+
+        // Image size that does not match the setup is rejected before pixels are read.
+        let mut corrupt_data = data.clone();
+        corrupt_data[16..24].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(Checkpoint::read(&mut corrupt_data.as_slice(), &parameters).is_err());
+        assert!(Checkpoint::read(&mut data.as_slice(), &Parameters { image_width: 3, ..parameters }).is_err());
+    }
+
+    #[test]
+    fn matches()
+    {
+        let mut parameters = Parameters
+        {
+            image_width: 1,
+            image_height: 1,
+            ..Parameters::default()
+        };
+
+        let scene = Scene::new();
+
+        let checkpoint = Checkpoint
+        {
+            setup_hash: setup::hash(&parameters, &scene),
+            image_width: parameters.image_width,
+            image_height: parameters.image_height,
+            pass_count: 0,
+            aovs: vec![Aov::Depth],
+            pixels: vec![PixelCheckpoint
+            {
+                aovs: Vec::new(),
+                sample_count: 0,
+                mean: 0.0,
+                squared_deviations: 0.0,
+                is_finished: false,
+                random: Random::new(0, 0)
+            }],
+            film_tiles: Vec::new()
+        };
+
+        assert!(checkpoint.matches(&parameters, &scene));
+
+        // Settings applied to finished image can change, as long as checkpoint stores all needed output variables.
+        parameters.tone_mapping.exposure += 1.0;
+        parameters.aovs = vec![Aov::Depth];
+        assert!(checkpoint.matches(&parameters, &scene));
+
+        parameters.aovs = vec![Aov::Depth, Aov::Normal];
+        assert!(!checkpoint.matches(&parameters, &scene));
+
+        parameters.aovs.clear();
+        parameters.seed += 1;
+        assert!(!checkpoint.matches(&parameters, &scene));
+
+        // Contents of images referenced by scene are hashed too, not only their paths.
+        let mut image_scene = Scene::new().set_environment(Environment::Image(environment::Image::new("textures/grid.png", 0.0, 1.0)));
+        let unloaded_hash = setup::hash(&parameters, &image_scene);
+
+        image_scene.load_environment(Path::new("examples")).unwrap();
+        assert_ne!(setup::hash(&parameters, &image_scene), unloaded_hash);
+    }
+}
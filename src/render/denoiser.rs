@@ -0,0 +1,195 @@
+use rayon::prelude::*;
+use serde::{ Serialize, Deserialize };
+use super::math::Vec4;
+use super::aovs::Aov;
+use super::image;
+
+// Joint bilateral filter, which averages noisy pixels with neighbours that share the same surface.
+// Similarity is judged mostly by albedo, normal and depth guides, which are nearly noise free even with few samples.
+// Implementation based on: https://people.csail.mit.edu/sparis/bf_course/
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Denoiser
+{
+    // Number of neighbouring pixels on each side that are blended together.
+    pub radius: usize,
+
+    // Standard deviations of each weight, where smaller values keep more detail and larger ones remove more noise.
+    pub spatial_sigma: f32,
+    pub color_sigma: f32,
+    pub albedo_sigma: f32,
+    pub normal_sigma: f32,
+
+    // Depth differences are relative to depth of filtered pixel, so the same value works at any scene scale.
+    pub depth_sigma: f32
+}
+
+impl Default for Denoiser
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            radius: 5,
+            spatial_sigma: 3.0,
+            color_sigma: 0.5,
+            albedo_sigma: 0.1,
+            normal_sigma: 0.2,
+            depth_sigma: 0.05
+        }
+    }
+}
+
+fn gaussian(squared_distance: f32, sigma: f32) -> f32
+{
+    (-squared_distance / (2.0 * sigma * sigma)).exp()
+}
+
+fn squared_distance(first: Vec4, second: Vec4) -> f32
+{
+    let difference = first - second;
+    difference.get_r().powi(2) + difference.get_g().powi(2) + difference.get_b().powi(2)
+}
+
+fn compressed(color: Vec4) -> Vec4
+{
+    // Colors are compared after compressing their range, so bright outliers do not dominate the weights.
+    let compress = |value: f32| value / (1.0 + value);
+    Vec4::new(compress(color.get_r()), compress(color.get_g()), compress(color.get_b()), color.get_a())
+}
+
+impl Denoiser
+{
+    // Output variables that guide the filter, which renderer produces along with the image.
+    pub const GUIDES: [Aov; 3] = [Aov::Albedo, Aov::Normal, Aov::Depth];
+
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    /// # Panics
+    ///
+    /// Panics when albedo, normal or depth output variable is missing or does not match image size.
+    pub fn apply(&self, image: &image::Surface, aovs: &[Aov], layers: &[image::Surface]) -> image::Surface
+    {
+        let guide = |guide_aov: Aov| aovs.iter().zip(layers).find(|(aov, _)| **aov == guide_aov).map(|(_, layer)| layer)
+            .expect("Missing output variable required by denoiser!");
+
+        self.denoise(image, guide(Aov::Albedo), guide(Aov::Normal), guide(Aov::Depth))
+    }
+
+    /// # Panics
+    ///
+    /// Panics when guides do not match image size.
+    pub fn denoise(&self, image: &image::Surface, albedo: &image::Surface, normal: &image::Surface, depth: &image::Surface) -> image::Surface
+    {
+        let (width, height) = (image.get_width(), image.get_height());
+
+        assert!([albedo, normal, depth].iter().all(|guide| guide.get_width() == width && guide.get_height() == height), "Denoiser guides do not match image size!");
+
+        let mut pixels = vec![Vec4::zero(); width * height];
+
+        pixels.par_chunks_mut(width).enumerate().for_each(|(y, row)|
+        {
+            for (x, pixel) in row.iter_mut().enumerate()
+            {
+                *pixel = self.denoise_pixel(x, y, image, albedo, normal, depth);
+            }
+        });
+
+        image::Surface::from(width, height, pixels)
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn denoise_pixel(&self, x: usize, y: usize, image: &image::Surface, albedo: &image::Surface, normal: &image::Surface, depth: &image::Surface) -> Vec4
+    {
+        let center_color = compressed(image.get_pixel(x, y));
+        let center_albedo = albedo.get_pixel(x, y);
+        let center_normal = normal.get_pixel(x, y);
+        let center_depth = depth.get_pixel(x, y).get_r();
+
+        let mut color_sum = Vec4::zero();
+        let mut weight_sum = 0.0;
+
+        for neighbour_y in y.saturating_sub(self.radius)..(y + self.radius + 1).min(image.get_height())
+        {
+            for neighbour_x in x.saturating_sub(self.radius)..(x + self.radius + 1).min(image.get_width())
+            {
+                let color = image.get_pixel(neighbour_x, neighbour_y);
+                let neighbour_depth = depth.get_pixel(neighbour_x, neighbour_y).get_r();
+
+                // Background has infinite depth, so it is only blended with other background pixels.
+                let depth_weight = match (center_depth.is_finite(), neighbour_depth.is_finite())
+                {
+                    (true, true) => gaussian(((neighbour_depth - center_depth) / center_depth.max(f32::EPSILON)).powi(2), self.depth_sigma),
+                    (false, false) => 1.0,
+                    _ => 0.0
+                };
+
+                let spatial_distance = (neighbour_x as f32 - x as f32).powi(2) + (neighbour_y as f32 - y as f32).powi(2);
+
+                let weight = depth_weight *
+                    gaussian(spatial_distance, self.spatial_sigma) *
+                    gaussian(squared_distance(compressed(color), center_color), self.color_sigma) *
+                    gaussian(squared_distance(albedo.get_pixel(neighbour_x, neighbour_y), center_albedo), self.albedo_sigma) *
+                    gaussian(squared_distance(normal.get_pixel(neighbour_x, neighbour_y), center_normal), self.normal_sigma);
+
+                color_sum += color * weight;
+                weight_sum += weight;
+            }
+        }
+
+        // Filtered pixel itself always has full weight, so the sum is never zero.
+        let denoised = color_sum / weight_sum;
+        Vec4::new(denoised.get_r(), denoised.get_g(), denoised.get_b(), image.get_pixel(x, y).get_a())
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn denoise()
+    {
+        // Checkerboard noise over two flat surfaces, split by a sharp edge in the guides.
+        let (width, height) = (8, 4);
+        let surface = |value: fn(usize, usize) -> Vec4| image::Surface::from(width, height, (0..width * height).map(|index| value(index % width, index / width)).collect());
+
+        let noisy = surface(|x, y| match x
+        {
+            0..=3 if (x + y) % 2 == 0 => Vec4::new(0.75, 0.75, 0.75, 1.0),
+            0..=3 => Vec4::new(0.25, 0.25, 0.25, 1.0),
+            _ => Vec4::new(4.0, 0.0, 0.0, 1.0)
+        });
+        let albedo = surface(|x, _| if x < 4 { Vec4::new(0.8, 0.8, 0.8, 1.0) } else { Vec4::new(0.9, 0.1, 0.1, 1.0) });
+        let normal = surface(|_, _| Vec4::new(0.0, 0.0, 1.0, 1.0));
+        let depth = surface(|_, _| Vec4::new(2.0, 2.0, 2.0, 1.0));
+
+        let denoised = Denoiser::new().denoise(&noisy, &albedo, &normal, &depth);
+
+        // Noise is reduced, while pixels across the edge are left untouched.
+        for y in 0..height
+        {
+            for x in 0..width
+            {
+                let pixel = denoised.get_pixel(x, y);
+
+                if x < 4
+                {
+                    assert!((pixel.get_r() - 0.5).abs() < (noisy.get_pixel(x, y).get_r() - 0.5).abs());
+                    assert!(pixel.get_g() > 0.0);
+                }
+                else
+                {
+                    assert!((pixel.get_r() - 4.0).abs() < 1e-4 && pixel.get_g() < 1e-4);
+                }
+
+                assert_eq!(pixel.get_a(), 1.0);
+            }
+        }
+    }
+}
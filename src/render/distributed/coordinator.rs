@@ -0,0 +1,297 @@
+use std::collections::VecDeque;
+use std::io::{ BufReader, ErrorKind };
+use std::net::{ TcpListener, TcpStream };
+use std::path::{ Path, PathBuf };
+use std::sync::{ Mutex, Condvar };
+use std::time::Duration;
+use super::math::Vec4;
+use super::image;
+use super::tiles;
+use super::tiles::Tile;
+use super::film::{ Film, FilmTile, FilmPixel, Filter };
+use super::Setup;
+use super::{ Message, read_message, write_message, invalid_data };
+
+// Workers that do not respond for this long are disconnected, so it needs to exceed time it takes to render a tile.
+const DEFAULT_TIMEOUT: Duration = Duration::from_mins(10);
+
+struct Schedule
+{
+    image_width: usize,
+    image_height: usize,
+    filter: Filter,
+    tiles: Vec<Tile>,
+    pending: VecDeque<usize>,
+    finished_count: usize,
+    film_tiles: Vec<Option<FilmTile>>
+}
+
+impl Schedule
+{
+    fn is_finished(&self) -> bool
+    {
+        self.finished_count == self.tiles.len()
+    }
+}
+
+pub struct Coordinator<'a>
+{
+    setup: Option<&'a Setup>,
+    directory: PathBuf,
+    timeout: Duration
+}
+
+impl Default for Coordinator<'_>
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            setup: None,
+            directory: PathBuf::new(),
+            timeout: DEFAULT_TIMEOUT
+        }
+    }
+}
+
+impl<'a> Coordinator<'a>
+{
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn set_setup(mut self, setup: &'a Setup) -> Self
+    {
+        self.setup = Some(setup);
+        self
+    }
+
+    #[must_use]
+    pub fn set_directory<P: AsRef<Path>>(mut self, directory: P) -> Self
+    {
+        // Workers load resources referenced by setup from this directory, so it should be reachable from every machine.
+        self.directory = PathBuf::from(directory.as_ref());
+        self
+    }
+
+    #[must_use]
+    pub fn set_timeout(mut self, timeout: Duration) -> Self
+    {
+        // Tiles of worker that does not send or receive anything for this long are given to other workers.
+        self.timeout = timeout;
+        self
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when setup is not supported by distributed render or listener fails.
+    ///
+    /// # Panics
+    ///
+    /// Panics when setup has not been set.
+    pub fn render(&self, listener: &TcpListener) -> std::io::Result<image::Surface>
+    {
+        let setup = self.setup.expect("Cannot render image without setup!");
+        let parameters = &setup.parameters;
+
+        // Workers only send film pixels of their tiles, which output variables and denoiser cannot be computed from.
+        if !parameters.rendered_aovs().is_empty()
+        {
+            return Err(std::io::Error::new(ErrorKind::InvalidInput, "Output variables and denoising are not supported by distributed render!"));
+        }
+
+        let tiles = tiles::generate(parameters.image_width, parameters.image_height, parameters.tile_size, parameters.tile_order);
+
+        let schedule = Mutex::new(Schedule
+        {
+            image_width: parameters.image_width,
+            image_height: parameters.image_height,
+            filter: parameters.filter,
+            pending: (0..tiles.len()).collect(),
+            film_tiles: tiles.iter().map(|_| None).collect(),
+            tiles,
+            finished_count: 0
+        });
+
+        let schedule_changed = Condvar::new();
+
+        let setup_message = Message::Setup
+        {
+            setup: serde_json::to_string(setup)?,
+            directory: self.directory.clone()
+        };
+
+        // Workers can join at any point until all tiles are finished, each one is served by its own thread.
+        listener.set_nonblocking(true)?;
+
+        std::thread::scope(|scope|
+        {
+            while !schedule.lock().expect("Schedule has been poisoned!").is_finished()
+            {
+                match listener.accept()
+                {
+                    Ok((stream, address)) =>
+                    {
+                        println!("Worker {address} connected.");
+
+                        let schedule = &schedule;
+                        let schedule_changed = &schedule_changed;
+                        let setup_message = &setup_message;
+                        let timeout = self.timeout;
+
+                        scope.spawn(move ||
+                        {
+                            match serve_worker(stream, timeout, schedule, schedule_changed, setup_message)
+                            {
+                                Ok(tile_count) => println!("Worker {address} finished after rendering {tile_count} tiles."),
+                                Err(error) => println!("Worker {address} disconnected: {error}")
+                            }
+                        });
+                    },
+                    Err(error) if error.kind() == ErrorKind::WouldBlock => std::thread::sleep(Duration::from_millis(10)),
+
+                    // Connection that failed before it was accepted only affects that worker, which can connect again.
+                    Err(error) if matches!(error.kind(), ErrorKind::ConnectionAborted | ErrorKind::ConnectionReset | ErrorKind::Interrupted | ErrorKind::TimedOut) =>
+                    {
+                        println!("Failed to accept worker: {error}");
+                    },
+                    Err(error) => return Err(error)
+                }
+            }
+
+            Ok(())
+        })?;
+
+        // Film tiles are merged in the same order as in local render, so overlapping pixels are summed identically.
+        let schedule = schedule.into_inner().expect("Schedule has been poisoned!");
+        let mut film = Film::new(parameters.image_width, parameters.image_height);
+
+        for film_tile in schedule.film_tiles.iter().flatten()
+        {
+            film.merge(film_tile);
+        }
+
+        Ok(film.resolve())
+    }
+}
+
+fn serve_worker(stream: TcpStream, timeout: Duration, schedule: &Mutex<Schedule>, schedule_changed: &Condvar, setup_message: &Message) -> std::io::Result<usize>
+{
+    let mut assigned = Vec::new();
+    let result = exchange_tiles(stream, timeout, schedule, schedule_changed, setup_message, &mut assigned);
+
+    // Tiles of disconnected or timed out worker are put back in front of the queue, so other workers render them next.
+    if !assigned.is_empty()
+    {
+        let mut schedule = schedule.lock().expect("Schedule has been poisoned!");
+
+        for index in assigned.into_iter().rev()
+        {
+            schedule.pending.push_front(index);
+        }
+
+        schedule_changed.notify_all();
+    }
+
+    result
+}
+
+fn exchange_tiles(stream: TcpStream, timeout: Duration, schedule: &Mutex<Schedule>, schedule_changed: &Condvar, setup_message: &Message, assigned: &mut Vec<usize>) -> std::io::Result<usize>
+{
+    // Worker that stops responding would keep its tiles forever, while others wait for them to be finished.
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+
+    let thread_count = match read_message(&mut reader)?
+    {
+        Message::Hello { thread_count } => thread_count.max(1),
+        _ => return Err(invalid_data("Expected hello message!"))
+    };
+
+    write_message(&mut writer, setup_message)?;
+
+    let mut tile_count = 0;
+
+    loop
+    {
+        // Keep worker busy with one tile per thread, or wait until tiles of other disconnected workers become available.
+        let mut new_tiles: Vec<(usize, Tile)> = Vec::new();
+
+        {
+            let mut locked_schedule = schedule.lock().expect("Schedule has been poisoned!");
+
+            loop
+            {
+                while assigned.len() < thread_count
+                {
+                    let Some(index) = locked_schedule.pending.pop_front() else
+                    {
+                        break;
+                    };
+
+                    assigned.push(index);
+                    new_tiles.push((index, locked_schedule.tiles[index]));
+                }
+
+                if !assigned.is_empty() || locked_schedule.is_finished()
+                {
+                    break;
+                }
+
+                // Tiles of other workers are either finished or put back once their connection times out, so waiting always ends.
+                locked_schedule = schedule_changed.wait(locked_schedule).expect("Schedule has been poisoned!");
+            }
+        }
+
+        if assigned.is_empty()
+        {
+            write_message(&mut writer, &Message::Finish)?;
+            return Ok(tile_count);
+        }
+
+        for (index, tile) in new_tiles
+        {
+            write_message(&mut writer, &Message::Tile { index, tile })?;
+        }
+
+        let Message::Pixels { index, pixels } = read_message(&mut reader)? else
+        {
+            return Err(invalid_data("Expected tile pixels message!"));
+        };
+
+        let Some(position) = assigned.iter().position(|assigned_index| *assigned_index == index) else
+        {
+            return Err(invalid_data("Received tile that was not assigned!"));
+        };
+
+        let mut locked_schedule = schedule.lock().expect("Schedule has been poisoned!");
+        let mut film_tile = FilmTile::new(&locked_schedule.tiles[index], locked_schedule.filter, locked_schedule.image_width, locked_schedule.image_height);
+
+        if pixels.len() != film_tile.pixels.len() * 5
+        {
+            return Err(invalid_data("Mismatched tile size!"));
+        }
+
+        for (pixel, channels) in film_tile.pixels.iter_mut().zip(pixels.chunks(5))
+        {
+            *pixel = FilmPixel
+            {
+                color: Vec4::new(f32::from_bits(channels[0]), f32::from_bits(channels[1]), f32::from_bits(channels[2]), f32::from_bits(channels[3])),
+                weight: f32::from_bits(channels[4])
+            };
+        }
+
+        locked_schedule.film_tiles[index] = Some(film_tile);
+        assigned.remove(position);
+        locked_schedule.finished_count += 1;
+        tile_count += 1;
+
+        schedule_changed.notify_all();
+    }
+}
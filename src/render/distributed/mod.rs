@@ -0,0 +1,114 @@
+use std::io::{ Read, Write, Error, ErrorKind };
+use std::path::PathBuf;
+use serde::{ Serialize, Deserialize };
+use super::math;
+use super::image;
+use super::tiles;
+use super::tiles::Tile;
+use super::film;
+use super::setup::Setup;
+use super::renderer::Renderer;
+
+pub mod coordinator;
+pub use coordinator::Coordinator;
+pub mod worker;
+pub use worker::Worker;
+
+// Coordinator and workers exchange JSON messages, each prefixed with its length in bytes.
+// Largest accepted message protects against allocating memory for corrupted length.
+const MAX_MESSAGE_LENGTH: usize = 1 << 30;
+
+#[derive(Serialize, Deserialize)]
+pub enum Message
+{
+    // Sent by worker after connecting, coordinator keeps up to thread count tiles assigned to it.
+    Hello
+    {
+        thread_count: usize
+    },
+
+    // Serialized setup with directory used to load resources it references.
+    Setup
+    {
+        setup: String,
+        directory: PathBuf
+    },
+
+    Tile
+    {
+        index: usize,
+        tile: Tile
+    },
+
+    // Film pixels of tile are sent as raw bits of weighted color channels and weight, so merged image is identical to the one rendered locally.
+    Pixels
+    {
+        index: usize,
+        pixels: Vec<u32>
+    },
+
+    Finish
+}
+
+fn invalid_data(message: &str) -> Error
+{
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// # Errors
+///
+/// Returns error when message cannot be serialized or written.
+pub fn write_message<W: Write>(writer: &mut W, message: &Message) -> std::io::Result<()>
+{
+    let data = serde_json::to_vec(message)?;
+
+    writer.write_all(&(data.len() as u64).to_le_bytes())?;
+    writer.write_all(&data)?;
+    writer.flush()
+}
+
+/// # Errors
+///
+/// Returns error when reading fails or message is too long or malformed.
+pub fn read_message<R: Read>(reader: &mut R) -> std::io::Result<Message>
+{
+    let mut length = [0_u8; 8];
+    reader.read_exact(&mut length)?;
+
+    let length = u64::from_le_bytes(length);
+
+    if length > MAX_MESSAGE_LENGTH as u64
+    {
+        return Err(invalid_data("Message is too long!"));
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    let mut data = vec![0_u8; length as usize];
+    reader.read_exact(&mut data)?;
+
+    Ok(serde_json::from_slice(&data)?)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn messages()
+    {
+        let mut data = Vec::new();
+        write_message(&mut data, &Message::Hello { thread_count: 3 }).unwrap();
+        write_message(&mut data, &Message::Pixels { index: 5, pixels: vec![0.5_f32.to_bits(), 7] }).unwrap();
+
+        let mut reader = data.as_slice();
+
+        assert!(matches!(read_message(&mut reader).unwrap(), Message::Hello { thread_count: 3 }));
+        assert!(matches!(read_message(&mut reader).unwrap(), Message::Pixels { index: 5, pixels } if pixels == vec![0.5_f32.to_bits(), 7]));
+        assert!(read_message(&mut reader).is_err());
+
+        let mut corrupted = u64::MAX.to_le_bytes().to_vec();
+        corrupted.extend(b"{}");
+        assert!(read_message(&mut corrupted.as_slice()).is_err());
+    }
+}
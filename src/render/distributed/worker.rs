@@ -0,0 +1,146 @@
+use std::io::BufReader;
+use std::net::{ TcpStream, ToSocketAddrs, Shutdown };
+use std::sync::{ Mutex, mpsc };
+use std::sync::atomic::{ AtomicUsize, Ordering };
+use super::Setup;
+use super::Renderer;
+use super::{ Message, read_message, write_message, invalid_data };
+
+#[derive(Default)]
+pub struct Worker
+{
+    thread_count: usize
+}
+
+impl Worker
+{
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn set_thread_count(mut self, thread_count: usize) -> Self
+    {
+        // Zero uses all available cores.
+        self.thread_count = thread_count;
+        self
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when connection fails, coordinator sends invalid setup or tile cannot be rendered.
+    ///
+    /// # Panics
+    ///
+    /// Panics when a rendering thread panics.
+    pub fn run<A: ToSocketAddrs>(&self, address: A) -> std::io::Result<usize>
+    {
+        let stream = TcpStream::connect(address)?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let writer = Mutex::new(stream);
+
+        let thread_count = if self.thread_count == 0
+        {
+            std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get)
+        }
+        else
+        {
+            self.thread_count
+        };
+
+        write_message(&mut *writer.lock().expect("Connection has been poisoned!"), &Message::Hello { thread_count })?;
+
+        let setup = match read_message(&mut reader)?
+        {
+            Message::Setup { setup, directory } =>
+            {
+                Setup::from_reader(setup.as_bytes(), &directory).or(Err(invalid_data("Failed to load setup!")))?
+            },
+            _ => return Err(invalid_data("Expected setup message!"))
+        };
+
+        // Each thread renders whole tile on its own, so renderer does not need more than one thread in its pool.
+        let renderer = Renderer::new()
+            .set_parameters(&setup.parameters)
+            .set_scene(&setup.scene)
+            .set_thread_count(1);
+
+        let (sender, receiver) = mpsc::channel();
+        let receiver = Mutex::new(receiver);
+        let tile_count = AtomicUsize::new(0);
+        let render_error = Mutex::new(None);
+
+        let result = std::thread::scope(|scope|
+        {
+            for _ in 0..thread_count
+            {
+                scope.spawn(||
+                {
+                    loop
+                    {
+                        // Channel is closed once coordinator finishes render or connection is lost.
+                        let job = receiver.lock().expect("Channel has been poisoned!").recv();
+
+                        let Ok((index, tile)) = job else
+                        {
+                            break;
+                        };
+
+                        let film_tile = match renderer.render_tile(tile)
+                        {
+                            Ok(film_tile) => film_tile,
+                            Err(error) =>
+                            {
+                                // Closed connection lets coordinator give assigned tiles to other workers right away.
+                                *render_error.lock().expect("Render error has been poisoned!") = Some(error);
+                                let _ = writer.lock().expect("Connection has been poisoned!").shutdown(Shutdown::Both);
+                                break;
+                            }
+                        };
+
+                        let pixels = film_tile.pixels.iter()
+                            .flat_map(|pixel| vec![pixel.color.get_r().to_bits(), pixel.color.get_g().to_bits(), pixel.color.get_b().to_bits(), pixel.color.get_a().to_bits(), pixel.weight.to_bits()])
+                            .collect();
+
+                        if write_message(&mut *writer.lock().expect("Connection has been poisoned!"), &Message::Pixels { index, pixels }).is_err()
+                        {
+                            break;
+                        }
+
+                        tile_count.fetch_add(1, Ordering::Relaxed);
+                    }
+                });
+            }
+
+            let result = loop
+            {
+                match read_message(&mut reader)
+                {
+                    Ok(Message::Tile { index, tile }) =>
+                    {
+                        if sender.send((index, tile)).is_err()
+                        {
+                            break Err(invalid_data("Render threads have stopped!"));
+                        }
+                    },
+                    Ok(Message::Finish) => break Ok(()),
+                    Ok(_) => break Err(invalid_data("Expected tile message!")),
+                    Err(error) => break Err(error)
+                }
+            };
+
+            drop(sender);
+            result
+        });
+
+        // Failed render is reported instead of connection error it causes.
+        if let Some(error) = render_error.into_inner().expect("Render error has been poisoned!")
+        {
+            return Err(std::io::Error::other(format!("Rendering tile failed: {error:?}")));
+        }
+
+        result?;
+        Ok(tile_count.into_inner())
+    }
+}
@@ -0,0 +1,169 @@
+use std::path::{ Path, PathBuf };
+use std::hash::{ Hash, Hasher };
+use serde::{ Serialize, Deserialize };
+use super::math::Vec2;
+use super::math::Vec3;
+use super::math::Vec4;
+use super::image;
+use super::textures;
+use super::textures::Wrap;
+
+#[derive(Serialize, Deserialize)]
+pub struct Gradient
+{
+    pub bottom: Vec4,
+    pub top: Vec4,
+    pub up_direction: Vec3
+}
+
+impl Default for Gradient
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            bottom: Vec4::new(1.0, 1.0, 1.0, 1.0),
+            top: Vec4::new(0.5, 0.7, 1.0, 1.0),
+            up_direction: Vec3::up()
+        }
+    }
+}
+
+impl Gradient
+{
+    pub fn sample(&self, direction: Vec3) -> Vec4
+    {
+        let alpha = (direction.dot(self.up_direction.normalized()) + 1.0) * 0.5;
+        self.bottom * (1.0 - alpha) + self.top * alpha
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Image
+{
+    pub path: PathBuf,
+
+    #[serde(default)]
+    pub rotation: f32,
+
+    #[serde(default = "default_intensity")]
+    pub intensity: f32,
+
+    #[serde(skip)]
+    surface: Option<image::Surface>
+}
+
+fn default_intensity() -> f32
+{
+    1.0
+}
+
+impl Image
+{
+    pub fn new<P: AsRef<Path>>(path: P, rotation: f32, intensity: f32) -> Self
+    {
+        Self
+        {
+            path: PathBuf::from(path.as_ref()),
+            rotation,
+            intensity,
+            surface: None
+        }
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when image file cannot be loaded.
+    pub fn load(&mut self, directory: &Path) -> std::io::Result<()>
+    {
+        self.surface = Some(image::Reader::new().input(directory.join(&self.path)).load()?);
+        Ok(())
+    }
+
+    pub fn is_loaded(&self) -> bool
+    {
+        self.surface.is_some()
+    }
+
+    pub fn hash_resources<H: Hasher>(&self, hasher: &mut H)
+    {
+        self.surface.hash(hasher);
+    }
+
+    /// # Panics
+    ///
+    /// Panics when image has not been loaded, which setup validation reports before rendering.
+    pub fn sample(&self, direction: Vec3) -> Vec4
+    {
+        // Image is expected to use equirectangular projection with up axis in the middle of each row.
+        let surface = self.surface.as_ref().expect("Environment image has not been loaded!");
+
+        let azimuth = direction.get_y().atan2(direction.get_x()) - self.rotation.to_radians();
+        let elevation = direction.get_z().clamp(-1.0, 1.0).acos();
+
+        let uv = Vec2::new(azimuth / (2.0 * std::f32::consts::PI), 1.0 - elevation / std::f32::consts::PI);
+
+        // Horizontal coordinate wraps around while vertical one is clamped at poles.
+        let mut color = textures::bitmap::sample_bilinear(surface, uv, Wrap::Repeat, Wrap::Clamp) * self.intensity;
+        color.set_a(1.0);
+        color
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum Environment
+{
+    Color(Vec4),
+    Gradient(Gradient),
+    Image(Image)
+}
+
+impl Default for Environment
+{
+    fn default() -> Self
+    {
+        Self::Gradient(Gradient::default())
+    }
+}
+
+impl Environment
+{
+    /// # Errors
+    ///
+    /// Returns error when environment image cannot be loaded.
+    pub fn load(&mut self, directory: &Path) -> std::io::Result<()>
+    {
+        match self
+        {
+            Self::Image(image) => image.load(directory),
+            _ => Ok(())
+        }
+    }
+
+    pub fn find_unloaded_image(&self) -> Option<&Path>
+    {
+        match self
+        {
+            Self::Image(image) if !image.is_loaded() => Some(&image.path),
+            _ => None
+        }
+    }
+
+    pub fn hash_resources<H: Hasher>(&self, hasher: &mut H)
+    {
+        if let Self::Image(image) = self
+        {
+            image.hash_resources(hasher);
+        }
+    }
+
+    pub fn sample(&self, direction: Vec3) -> Vec4
+    {
+        match self
+        {
+            Self::Color(color) => *color,
+            Self::Gradient(gradient) => gradient.sample(direction),
+            Self::Image(image) => image.sample(direction)
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use serde::{ Serialize, Deserialize };
+use super::math::Vec2;
+use super::math::Vec4;
+use super::tiles::Tile;
+use super::image;
+
+// Reconstruction filters weight each sample by its distance from pixel center, measured in pixels.
+// Filters are separable, so the same curve is applied along both axes.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Filter
+{
+    Box { radius: f32 },
+    Tent { radius: f32 },
+    Gaussian { radius: f32 },
+    Mitchell { radius: f32 },
+    Lanczos { radius: f32 }
+}
+
+impl Default for Filter
+{
+    fn default() -> Self
+    {
+        // Covers exactly one pixel, so every sample only contributes to pixel it was taken for.
+        Self::Box { radius: 0.5 }
+    }
+}
+
+fn sinc(x: f32) -> f32
+{
+    if x.abs() < 1e-5
+    {
+        return 1.0;
+    }
+
+    let x = x * std::f32::consts::PI;
+    x.sin() / x
+}
+
+impl Filter
+{
+    // Every film tile is extended by filter radius on each side, so it is limited to keep tiles small.
+    pub const MAX_RADIUS: f32 = 16.0;
+
+    pub fn radius(self) -> f32
+    {
+        match self
+        {
+            Self::Box { radius } |
+            Self::Tent { radius } |
+            Self::Gaussian { radius } |
+            Self::Mitchell { radius } |
+            Self::Lanczos { radius } => radius
+        }
+    }
+
+    pub fn evaluate(self, offset: Vec2) -> f32
+    {
+        self.evaluate_axis(offset.x) * self.evaluate_axis(offset.y)
+    }
+
+    fn evaluate_axis(self, offset: f32) -> f32
+    {
+        let radius = self.radius();
+        let distance = offset.abs();
+
+        // Box filter includes samples at its edge, so with default radius every sample covers exactly one pixel.
+        if distance > radius
+        {
+            return 0.0;
+        }
+
+        match self
+        {
+            Self::Box { .. } => 1.0,
+            Self::Tent { .. } => 1.0 - distance / radius,
+            Self::Gaussian { .. } =>
+            {
+                // Most of the curve fits within radius, and the rest is cut off so it reaches zero at the edge.
+                let gaussian = |x: f32| (-x * x / (2.0 * (radius / 3.0).powi(2))).exp();
+                gaussian(distance) - gaussian(radius)
+            },
+            Self::Mitchell { .. } =>
+            {
+                // Implementation based on: https://www.cs.utexas.edu/~fussell/courses/cs384g-fall2013/lectures/mitchell/Mitchell.pdf
+                // Uses recommended B = C = 1/3, with curve scaled to cover whole radius.
+                let (b, c) = (1.0 / 3.0, 1.0 / 3.0);
+                let x = 2.0 * distance / radius;
+
+                if x < 1.0
+                {
+                    ((12.0 - 9.0 * b - 6.0 * c) * x.powi(3) + (-18.0 + 12.0 * b + 6.0 * c) * x.powi(2) + (6.0 - 2.0 * b)) / 6.0
+                }
+                else
+                {
+                    ((-b - 6.0 * c) * x.powi(3) + (6.0 * b + 30.0 * c) * x.powi(2) + (-12.0 * b - 48.0 * c) * x + (8.0 * b + 24.0 * c)) / 6.0
+                }
+            },
+            Self::Lanczos { .. } => sinc(distance) * sinc(distance / radius)
+        }
+    }
+
+    fn margin(self) -> usize
+    {
+        // Number of neighbouring pixels reached by samples on each side.
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let margin = (self.radius() - 0.5).max(0.0).ceil() as usize;
+        margin
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct FilmPixel
+{
+    // Sum of sample colors multiplied by their weights, along with sum of those weights.
+    pub color: Vec4,
+    pub weight: f32
+}
+
+// Samples of single tile are splatted into its own buffer, which also covers neighbouring pixels reached by filter.
+// Every tile is owned by one thread at a time, so splatting does not need any synchronization,
+// and buffers are later merged into film in fixed order, which keeps image independent of thread count.
+pub struct FilmTile
+{
+    filter: Filter,
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    pub pixels: Vec<FilmPixel>
+}
+
+impl FilmTile
+{
+    pub fn new(tile: &Tile, filter: Filter, image_width: usize, image_height: usize) -> Self
+    {
+        let margin = filter.margin();
+
+        let x = tile.x.saturating_sub(margin);
+        let y = tile.y.saturating_sub(margin);
+        let width = (tile.x + tile.width + margin).min(image_width) - x;
+        let height = (tile.y + tile.height + margin).min(image_height) - y;
+
+        Self
+        {
+            filter,
+            x,
+            y,
+            width,
+            height,
+            pixels: vec![FilmPixel::default(); width * height]
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_precision_loss)]
+    pub fn add_sample(&mut self, x: usize, y: usize, offset: Vec2, color: Vec4)
+    {
+        // Sample is at given offset within its pixel, and reaches neighbours whose centers are within filter radius.
+        // Ranges are computed relative to sample pixel, so they stay exact regardless of image size.
+        let radius = self.filter.radius();
+        let range = |offset: f32| ((offset - 0.5 - radius).floor() as isize + 1)..=((offset - 0.5 + radius).floor() as isize);
+
+        for neighbour_y in range(offset.y)
+        {
+            let Some(pixel_y) = y.checked_add_signed(neighbour_y).filter(|pixel_y| (self.y..self.y + self.height).contains(pixel_y)) else
+            {
+                continue;
+            };
+
+            for neighbour_x in range(offset.x)
+            {
+                let Some(pixel_x) = x.checked_add_signed(neighbour_x).filter(|pixel_x| (self.x..self.x + self.width).contains(pixel_x)) else
+                {
+                    continue;
+                };
+
+                let weight = self.filter.evaluate(Vec2::new(offset.x - 0.5 - neighbour_x as f32, offset.y - 0.5 - neighbour_y as f32));
+                let pixel = &mut self.pixels[(pixel_y - self.y) * self.width + pixel_x - self.x];
+
+                pixel.color += color * weight;
+                pixel.weight += weight;
+            }
+        }
+    }
+
+    pub fn set_pixel(&mut self, x: usize, y: usize, pixel: FilmPixel)
+    {
+        self.pixels[(y - self.y) * self.width + x - self.x] = pixel;
+    }
+
+    pub fn clear(&mut self)
+    {
+        self.pixels.iter_mut().for_each(|pixel| *pixel = FilmPixel::default());
+    }
+}
+
+pub struct Film
+{
+    width: usize,
+    height: usize,
+    pixels: Vec<FilmPixel>
+}
+
+impl Film
+{
+    pub fn new(width: usize, height: usize) -> Self
+    {
+        Self
+        {
+            width,
+            height,
+            pixels: vec![FilmPixel::default(); width * height]
+        }
+    }
+
+    pub fn merge(&mut self, tile: &FilmTile)
+    {
+        for (row, tile_pixels) in tile.pixels.chunks_exact(tile.width).enumerate()
+        {
+            let begin = (tile.y + row) * self.width + tile.x;
+
+            for (pixel, tile_pixel) in self.pixels[begin..begin + tile.width].iter_mut().zip(tile_pixels)
+            {
+                pixel.color += tile_pixel.color;
+                pixel.weight += tile_pixel.weight;
+            }
+        }
+    }
+
+    pub fn resolve(&self) -> image::Surface
+    {
+        // Filters with negative lobes can produce negative values near sharp edges, which are clamped to zero.
+        // Pixels without any weight remain black.
+        let pixels = self.pixels.iter().map(|pixel| if pixel.weight > 0.0
+        {
+            let color = pixel.color / pixel.weight;
+            Vec4::new(color.get_r().max(0.0), color.get_g().max(0.0), color.get_b().max(0.0), color.get_a())
+        }
+        else
+        {
+            Vec4::black()
+        }).collect();
+
+        image::Surface::from(self.width, self.height, pixels)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn evaluate()
+    {
+        let filters = [Filter::Box { radius: 1.0 }, Filter::Tent { radius: 1.5 }, Filter::Gaussian { radius: 1.5 }, Filter::Mitchell { radius: 2.0 }, Filter::Lanczos { radius: 2.0 }];
+
+        for filter in &filters
+        {
+            // Filters peak at pixel center and vanish beyond their radius.
+            let center = filter.evaluate(Vec2::new(0.0, 0.0));
+
+            assert!(center > 0.0);
+            assert!(filter.evaluate(Vec2::new(0.3, -0.2)) <= center);
+            assert_eq!(filter.evaluate(Vec2::new(filter.radius() + 0.01, 0.0)), 0.0);
+            assert_eq!(filter.evaluate(Vec2::new(0.0, -filter.radius() - 1.0)), 0.0);
+        }
+
+        // Mitchell and Lanczos filters have negative lobes away from center.
+        assert!(Filter::Mitchell { radius: 2.0 }.evaluate(Vec2::new(1.5, 0.0)) < 0.0);
+        assert!(Filter::Lanczos { radius: 2.0 }.evaluate(Vec2::new(1.5, 0.0)) < 0.0);
+    }
+
+    #[test]
+    fn splat()
+    {
+        let tile = Tile { x: 2, y: 0, width: 2, height: 2 };
+
+        // Default filter keeps every sample within its own pixel.
+        let mut box_tile = FilmTile::new(&tile, Filter::default(), 8, 4);
+        box_tile.add_sample(2, 0, Vec2::new(0.0, 0.999), Vec4::white());
+
+        assert_eq!((box_tile.width, box_tile.height), (2, 2));
+        assert_eq!(box_tile.pixels.iter().filter(|pixel| pixel.weight > 0.0).count(), 1);
+        assert_eq!(box_tile.pixels[0], FilmPixel { color: Vec4::white(), weight: 1.0 });
+
+        // Wider filter reaches neighbouring pixels, but not beyond image edges.
+        let mut tent_tile = FilmTile::new(&tile, Filter::Tent { radius: 1.5 }, 8, 4);
+        tent_tile.add_sample(2, 0, Vec2::new(0.5, 0.5), Vec4::white());
+
+        assert_eq!((tent_tile.x, tent_tile.y, tent_tile.width, tent_tile.height), (1, 0, 4, 3));
+        assert_eq!(tent_tile.pixels.iter().filter(|pixel| pixel.weight > 0.0).count(), 3 * 2);
+
+        let mut film = Film::new(8, 4);
+        film.merge(&tent_tile);
+        film.merge(&box_tile);
+
+        let surface = film.resolve();
+
+        assert_eq!(surface.get_pixel(2, 0), Vec4::white());
+        assert_eq!(surface.get_pixel(1, 1), Vec4::white());
+        assert_eq!(surface.get_pixel(5, 0), Vec4::black());
+    }
+}
@@ -0,0 +1,56 @@
+use super::math::Vec4;
+use super::math::Ray;
+use super::materials;
+use super::samplers::PixelSampler;
+use super::statistics::Statistics;
+use super::scene::Scene;
+use super::{ Integrator, FirstHit };
+
+// Ambient occlusion, which shows how much of the hemisphere above the first hit is not blocked by nearby geometry.
+// Lights and materials are ignored, so the result only depends on scene geometry.
+pub struct AmbientOcclusion
+{
+    distance: f32
+}
+
+impl AmbientOcclusion
+{
+    pub fn new(distance: f32) -> Self
+    {
+        Self
+        {
+            distance
+        }
+    }
+}
+
+impl Integrator for AmbientOcclusion
+{
+    fn sample<'a>(&self, scene: &'a Scene, ray: Ray, sampler: &mut PixelSampler, stats: &mut Statistics, first_hit: &mut Option<FirstHit<'a>>) -> Vec4
+    {
+        stats.samples += 1;
+
+        let hit = scene.intersect_object(&ray, 0.0001, f32::MAX);
+        *first_hit = hit.as_ref().map(FirstHit::new);
+
+        let Some((intersection, _, _)) = hit else
+        {
+            return Vec4::white();
+        };
+
+        stats.intersections += 1;
+        stats.shadows += 1;
+
+        // Cosine weighted directions make occlusion near the horizon count less, same as it would for diffuse lighting.
+        let occlusion_ray = materials::Diffuse::scatter_lambertian(&ray, &intersection, sampler);
+
+        if scene.intersect(&occlusion_ray, 0.0001, self.distance).is_some()
+        {
+            Vec4::black()
+        }
+        else
+        {
+            Vec4::white()
+        }
+    }
+}
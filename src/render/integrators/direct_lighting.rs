@@ -0,0 +1,47 @@
+use super::math::Vec4;
+use super::math::Ray;
+use super::samplers::PixelSampler;
+use super::statistics::Statistics;
+use super::scene::Scene;
+use super::{ Integrator, FirstHit, sample_lights, intersect_lights };
+
+// Lighting that reaches surfaces seen by the camera straight from lights, without any light bouncing between surfaces.
+#[derive(Default)]
+pub struct DirectLighting
+{
+}
+
+impl DirectLighting
+{
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+}
+
+impl Integrator for DirectLighting
+{
+    fn sample<'a>(&self, scene: &'a Scene, ray: Ray, sampler: &mut PixelSampler, stats: &mut Statistics, first_hit: &mut Option<FirstHit<'a>>) -> Vec4
+    {
+        stats.samples += 1;
+
+        let hit = scene.intersect_object(&ray, 0.0001, f32::MAX);
+        let max_length = hit.as_ref().map_or(f32::MAX, |(intersection, _, _)| intersection.length);
+        *first_hit = hit.as_ref().map(FirstHit::new);
+
+        if let Some(light_hit) = intersect_lights(scene, &ray, max_length)
+        {
+            return light_hit.radiance;
+        }
+
+        let Some((intersection, material, _)) = hit else
+        {
+            return scene.environment.sample(ray.direction());
+        };
+
+        stats.intersections += 1;
+
+        // Environment is not sampled as a light, so it only lights surfaces through indirect bounces.
+        material.emitted() + sample_lights(scene, material, &ray, &intersection, sampler, stats)
+    }
+}
@@ -0,0 +1,169 @@
+use serde::{ Serialize, Deserialize };
+
+use super::math;
+use super::math::Vec4;
+use super::math::Ray;
+use super::math::Intersection;
+use super::materials;
+use super::lights::LightHit;
+use super::samplers;
+use super::samplers::PixelSampler;
+use super::statistics;
+use super::statistics::Statistics;
+use super::parameters;
+use super::parameters::Parameters;
+use super::scene;
+use super::scene::Scene;
+
+pub mod path_tracer;
+pub use path_tracer::PathTracer;
+pub mod ambient_occlusion;
+pub use ambient_occlusion::AmbientOcclusion;
+pub mod direct_lighting;
+pub use direct_lighting::DirectLighting;
+pub mod whitted;
+pub use whitted::Whitted;
+pub mod preview;
+pub use preview::Preview;
+
+// Closest surface hit by camera ray, which output variables are taken from.
+pub struct FirstHit<'a>
+{
+    pub intersection: Intersection,
+    pub material: &'a materials::Material,
+    pub object_index: usize
+}
+
+impl<'a> FirstHit<'a>
+{
+    pub fn new(hit: &(Intersection, &'a materials::Material, usize)) -> Self
+    {
+        let (intersection, material, object_index) = *hit;
+
+        Self
+        {
+            intersection,
+            material,
+            object_index
+        }
+    }
+}
+
+// Integrator computes light arriving along camera ray, and is shared by all render threads.
+// Only color channels of returned sample are used, since renderer makes every sample opaque.
+// Scene hit of camera ray is reported through first hit, so renderer does not need to intersect the scene again.
+pub trait Integrator: Send + Sync
+{
+    fn sample<'a>(&self, scene: &'a Scene, ray: Ray, sampler: &mut PixelSampler, stats: &mut Statistics, first_hit: &mut Option<FirstHit<'a>>) -> Vec4;
+}
+
+// Selects integrator used by the renderer, together with its settings.
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum IntegratorKind
+{
+    #[default]
+    PathTracer,
+
+    // Path tracer with all materials replaced by plain gray diffuse one and lit only by uniform background.
+    Diffuse,
+
+    // Path tracer whose image is replaced by heatmap of samples taken for each pixel.
+    SampleCount,
+
+    // Occlusion is tested up to given distance, so it only shows how surfaces are shadowed by nearby geometry.
+    AmbientOcclusion { distance: f32 },
+    DirectLighting,
+    Whitted,
+    Albedo,
+    Normals
+}
+
+impl IntegratorKind
+{
+    pub fn build(self, parameters: &Parameters) -> Box<dyn Integrator>
+    {
+        match self
+        {
+            Self::PathTracer | Self::SampleCount => Box::new(PathTracer::new(parameters)),
+            Self::Diffuse => Box::new(PathTracer::new(parameters)
+                .set_override_material(materials::Diffuse::new(Vec4::new(0.5, 0.5, 0.5, 1.0)), Vec4::new(0.5, 0.5, 0.5, 1.0))),
+            Self::AmbientOcclusion { distance } => Box::new(AmbientOcclusion::new(distance)),
+            Self::DirectLighting => Box::new(DirectLighting::new()),
+            Self::Whitted => Box::new(Whitted::new(parameters)),
+            Self::Albedo => Box::new(Preview::Albedo),
+            Self::Normals => Box::new(Preview::Normals)
+        }
+    }
+}
+
+pub fn power_heuristic(pdf: f32, other_pdf: f32) -> f32
+{
+    // Multiple importance sampling weight, see: https://graphics.stanford.edu/courses/cs348b-03/papers/veach-chapter9.pdf
+    let pdf_sqr = pdf * pdf;
+    pdf_sqr / (pdf_sqr + other_pdf * other_pdf)
+}
+
+pub fn sample_lights(scene: &Scene, material: &materials::Material, ray: &Ray, intersection: &Intersection, sampler: &mut PixelSampler, stats: &mut Statistics) -> Vec4
+{
+    // Next event estimation, where each light is sampled directly and tested for visibility with a shadow ray.
+    let mut radiance = Vec4::zero();
+
+    for light in scene.lights()
+    {
+        let Some(light_sample) = light.sample(intersection.point, sampler) else
+        {
+            continue;
+        };
+
+        // Materials that scatter in a single direction cannot be lit by sampled lights.
+        let Some((attenuation, scatter_pdf)) = material.evaluate(ray, intersection, light_sample.direction) else
+        {
+            return Vec4::zero();
+        };
+
+        if scatter_pdf <= 0.0 || light_sample.pdf <= 0.0
+        {
+            continue;
+        }
+
+        stats.shadows += 1;
+
+        let shadow_ray = Ray::new(intersection.point, light_sample.direction, ray.time());
+        let shadow_length = light_sample.distance * (1.0 - 0.0001);
+
+        if scene.intersect(&shadow_ray, 0.0001, shadow_length).is_some()
+        {
+            continue;
+        }
+
+        let weight = if light_sample.is_delta
+        {
+            1.0
+        }
+        else
+        {
+            power_heuristic(light_sample.pdf, scatter_pdf)
+        };
+
+        radiance += light_sample.radiance * attenuation * (weight / light_sample.pdf);
+    }
+
+    radiance
+}
+
+pub fn intersect_lights(scene: &Scene, ray: &Ray, max_length: f32) -> Option<LightHit>
+{
+    let mut closest_hit: Option<LightHit> = None;
+
+    for light in scene.lights()
+    {
+        let max_length = closest_hit.as_ref().map_or(max_length, |light_hit| light_hit.distance);
+
+        if let Some(light_hit) = light.intersect(ray, max_length)
+        {
+            closest_hit = Some(light_hit);
+        }
+    }
+
+    closest_hit
+}
@@ -0,0 +1,244 @@
+use super::math::Vec4;
+use super::math::Ray;
+use super::math::Intersection;
+use super::materials;
+use super::samplers::PixelSampler;
+use super::statistics::Statistics;
+use super::parameters::Parameters;
+use super::scene::Scene;
+use super::{ Integrator, FirstHit, power_heuristic, sample_lights, intersect_lights };
+
+// State carried by path from one scattering event to the next.
+pub struct Path
+{
+    pub ray: Ray,
+
+    // Product of attenuations along the path, which weights all light it gathers from now on.
+    pub throughput: Vec4,
+    pub scatter_index: u16,
+
+    // Density of direction sampled at last scattering, which is unknown for rays from camera or specular surfaces.
+    pub scatter_pdf: Option<f32>,
+
+    // Index of object whose interior the path currently travels through.
+    pub medium: Option<usize>
+}
+
+impl Path
+{
+    pub fn new(ray: Ray) -> Self
+    {
+        Self
+        {
+            ray,
+            throughput: Vec4::one(),
+            scatter_index: 0,
+            scatter_pdf: None,
+            medium: None
+        }
+    }
+
+    fn scatter(&mut self, ray: Ray, intersection: &Intersection, object_index: usize, attenuation: Vec4, scatter_pdf: Option<f32>)
+    {
+        // Rays that pass through the surface enter or leave interior of the object.
+        let incoming_side = self.ray.direction().dot(intersection.normal);
+        let outgoing_side = ray.direction().dot(intersection.normal);
+
+        if incoming_side * outgoing_side > 0.0
+        {
+            self.medium = if incoming_side < 0.0 { Some(object_index) } else { None };
+        }
+
+        self.ray = ray;
+        self.throughput = self.throughput * attenuation;
+        self.scatter_index += 1;
+        self.scatter_pdf = scatter_pdf;
+    }
+}
+
+// Unidirectional path tracer with next event estimation, which follows a single path of each sample until it escapes or is terminated.
+pub struct PathTracer
+{
+    scatter_limit: u16,
+    roulette_depth: u16,
+
+    // Material replacing all scene materials, along with uniform background, so output only depends on scene geometry.
+    override_material: Option<(materials::Material, Vec4)>
+}
+
+impl PathTracer
+{
+    pub fn new(parameters: &Parameters) -> Self
+    {
+        Self
+        {
+            scatter_limit: parameters.scatter_limit,
+            roulette_depth: parameters.roulette_depth,
+            override_material: None
+        }
+    }
+
+    #[must_use]
+    pub fn set_override_material(mut self, material: materials::Material, background: Vec4) -> Self
+    {
+        self.override_material = Some((material, background));
+        self
+    }
+
+    fn roulette(&self, path: &Path, sampler: &mut PixelSampler) -> Option<f32>
+    {
+        // Russian roulette, which randomly terminates paths that could only carry little light.
+        // Paths survive with probability given by their throughput, see: https://www.pbr-book.org/3ed-2018/Monte_Carlo_Integration/Russian_Roulette_and_Splitting
+        if path.scatter_index < self.roulette_depth
+        {
+            return Some(1.0);
+        }
+
+        let survival_probability = path.throughput.get_r().max(path.throughput.get_g()).max(path.throughput.get_b()).min(1.0);
+
+        if sampler.next_1d() < survival_probability
+        {
+            Some(survival_probability)
+        }
+        else
+        {
+            None
+        }
+    }
+}
+
+impl Integrator for PathTracer
+{
+    fn sample<'a>(&self, scene: &'a Scene, ray: Ray, sampler: &mut PixelSampler, stats: &mut Statistics, first_hit: &mut Option<FirstHit<'a>>) -> Vec4
+    {
+        let mut path = Path::new(ray);
+        let mut radiance = Vec4::zero();
+
+        let override_material = self.override_material.as_ref().map(|(material, _)| material);
+
+        loop
+        {
+            // Scatter limit only caps paths that keep surviving roulette, such as ones trapped between specular surfaces.
+            if path.scatter_index > self.scatter_limit
+            {
+                return radiance;
+            }
+
+            stats.samples += 1;
+
+            let hit = scene.intersect_object(&path.ray, 0.0001, f32::MAX);
+
+            if path.scatter_index == 0
+            {
+                *first_hit = hit.as_ref().map(FirstHit::new);
+            }
+
+            // Lights with area are not part of scene geometry and are checked separately.
+            // Override material ignores lights as well.
+            if override_material.is_none()
+            {
+                let max_length = hit.as_ref().map_or(f32::MAX, |(intersection, _, _)| intersection.length);
+
+                if let Some(light_hit) = intersect_lights(scene, &path.ray, max_length)
+                {
+                    // Lights hit by scattered rays are weighted against next event estimation that could also sample them.
+                    // Rays from camera or specular surfaces could not have sampled them, so they are taken at full weight.
+                    let weight = match path.scatter_pdf
+                    {
+                        Some(scatter_pdf) => power_heuristic(scatter_pdf, light_hit.pdf),
+                        None => 1.0
+                    };
+
+                    // Lights absorb all rays that hit them.
+                    return radiance + light_hit.radiance * weight * path.throughput;
+                }
+            }
+
+            let Some((intersection, object_material, object_index)) = hit else
+            {
+                let background = match &self.override_material
+                {
+                    Some((_, background)) => *background,
+                    None => scene.environment.sample(path.ray.direction())
+                };
+
+                return radiance + background * path.throughput;
+            };
+
+            stats.intersections += 1;
+
+            let material = override_material.unwrap_or(object_material);
+            let scattering = material.scatter(&path.ray, &intersection, path.scatter_index, sampler);
+
+            let direct_light = if override_material.is_none()
+            {
+                sample_lights(scene, material, &path.ray, &intersection, sampler, stats)
+            }
+            else
+            {
+                Vec4::zero()
+            };
+
+            radiance += (scattering.emission + direct_light) * path.throughput;
+
+            let Some(scattered_ray) = scattering.ray else
+            {
+                return radiance + scattering.attenuation * path.throughput;
+            };
+
+            stats.scatters += 1;
+            stats.max_scatters = std::cmp::max(stats.max_scatters, path.scatter_index);
+
+            path.scatter(scattered_ray, &intersection, object_index, scattering.attenuation, scattering.pdf);
+
+            // Surviving paths are boosted by the chance of their survival, which keeps result unbiased.
+            let Some(survival_probability) = self.roulette(&path, sampler) else
+            {
+                stats.roulette_terminations += 1;
+                return radiance;
+            };
+
+            path.throughput = path.throughput * Vec4::new(1.0 / survival_probability, 1.0 / survival_probability, 1.0 / survival_probability, 1.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use super::super::math::Vec2;
+    use super::super::math::Vec3;
+
+    #[test]
+    fn medium()
+    {
+        let surface = |z: f32, normal_z: f32| Intersection
+        {
+            point: Vec3::new(0.0, 0.0, z),
+            normal: Vec3::new(0.0, 0.0, normal_z),
+            uv: Vec2::new(0.0, 0.0),
+            length: 1.0
+        };
+
+        let down = Vec3::new(0.0, 0.0, -1.0);
+        let mut path = Path::new(Ray::new(Vec3::new(0.0, 0.0, 2.0), down, 0.0));
+
+        // Reflected ray stays outside of the object.
+        path.scatter(Ray::new(Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.0, 0.0, 1.0), 0.0), &surface(1.0, 1.0), 3, Vec4::new(0.5, 0.5, 0.5, 1.0), Some(0.25));
+
+        assert_eq!(path.medium, None);
+        assert_eq!(path.scatter_index, 1);
+        assert_eq!(path.scatter_pdf, Some(0.25));
+        assert_eq!(path.throughput, Vec4::new(0.5, 0.5, 0.5, 1.0));
+
+        // Transmitted rays enter the object through its top and leave through its bottom.
+        path.ray = Ray::new(Vec3::new(0.0, 0.0, 2.0), down, 0.0);
+        path.scatter(Ray::new(Vec3::new(0.0, 0.0, 1.0), down, 0.0), &surface(1.0, 1.0), 3, Vec4::one(), None);
+        assert_eq!(path.medium, Some(3));
+
+        path.scatter(Ray::new(Vec3::new(0.0, 0.0, -1.0), down, 0.0), &surface(-1.0, -1.0), 3, Vec4::one(), None);
+        assert_eq!(path.medium, None);
+        assert_eq!(path.scatter_pdf, None);
+    }
+}
@@ -0,0 +1,42 @@
+use super::math::Vec4;
+use super::math::Ray;
+use super::materials;
+use super::samplers::PixelSampler;
+use super::statistics::Statistics;
+use super::scene::Scene;
+use super::{ Integrator, FirstHit };
+
+// Fast preview that shows single property of surfaces seen by the camera, without any lighting.
+pub enum Preview
+{
+    Albedo,
+    Normals
+}
+
+impl Integrator for Preview
+{
+    fn sample<'a>(&self, scene: &'a Scene, ray: Ray, _sampler: &mut PixelSampler, stats: &mut Statistics, first_hit: &mut Option<FirstHit<'a>>) -> Vec4
+    {
+        stats.samples += 1;
+
+        let hit = scene.intersect_object(&ray, 0.0001, f32::MAX);
+        *first_hit = hit.as_ref().map(FirstHit::new);
+
+        let Some((intersection, material, _)) = hit else
+        {
+            return match self
+            {
+                Self::Albedo => Vec4::black(),
+                Self::Normals => Vec4::new(0.5, 0.0, 0.5, 1.0)
+            };
+        };
+
+        stats.intersections += 1;
+
+        match self
+        {
+            Self::Albedo => material.albedo(&intersection),
+            Self::Normals => materials::Normals::albedo(&intersection)
+        }
+    }
+}
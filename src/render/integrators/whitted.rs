@@ -0,0 +1,81 @@
+use super::math::Vec4;
+use super::math::Ray;
+use super::samplers::PixelSampler;
+use super::statistics::Statistics;
+use super::parameters::Parameters;
+use super::scene::Scene;
+use super::{ Integrator, FirstHit, sample_lights, intersect_lights };
+
+// Whitted style ray tracing, where rays only continue through reflections and refractions of specular materials.
+// Other surfaces are lit directly by lights, without light bouncing between them.
+pub struct Whitted
+{
+    scatter_limit: u16
+}
+
+impl Whitted
+{
+    pub fn new(parameters: &Parameters) -> Self
+    {
+        Self
+        {
+            scatter_limit: parameters.scatter_limit
+        }
+    }
+}
+
+impl Integrator for Whitted
+{
+    fn sample<'a>(&self, scene: &'a Scene, mut ray: Ray, sampler: &mut PixelSampler, stats: &mut Statistics, first_hit: &mut Option<FirstHit<'a>>) -> Vec4
+    {
+        let mut radiance = Vec4::zero();
+        let mut throughput = Vec4::one();
+
+        for scatter_index in 0..=self.scatter_limit
+        {
+            stats.samples += 1;
+
+            let hit = scene.intersect_object(&ray, 0.0001, f32::MAX);
+            let max_length = hit.as_ref().map_or(f32::MAX, |(intersection, _, _)| intersection.length);
+
+            if scatter_index == 0
+            {
+                *first_hit = hit.as_ref().map(FirstHit::new);
+            }
+
+            if let Some(light_hit) = intersect_lights(scene, &ray, max_length)
+            {
+                return radiance + light_hit.radiance * throughput;
+            }
+
+            let Some((intersection, material, _)) = hit else
+            {
+                return radiance + scene.environment.sample(ray.direction()) * throughput;
+            };
+
+            stats.intersections += 1;
+
+            // Materials that cannot be lit by sampled lights are specular, and are followed along their scattered ray instead.
+            if material.evaluate(&ray, &intersection, intersection.normal).is_some()
+            {
+                return radiance + (material.emitted() + sample_lights(scene, material, &ray, &intersection, sampler, stats)) * throughput;
+            }
+
+            let scattering = material.scatter(&ray, &intersection, scatter_index, sampler);
+
+            let Some(scattered_ray) = scattering.ray else
+            {
+                return radiance + (scattering.emission + scattering.attenuation) * throughput;
+            };
+
+            stats.scatters += 1;
+            stats.max_scatters = std::cmp::max(stats.max_scatters, scatter_index);
+
+            radiance += scattering.emission * throughput;
+            throughput = throughput * scattering.attenuation;
+            ray = scattered_ray;
+        }
+
+        radiance
+    }
+}
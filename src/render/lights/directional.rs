@@ -0,0 +1,40 @@
+use serde::{ Serialize, Deserialize };
+use super::math::Vec3;
+use super::math::Vec4;
+use super::Light;
+use super::LightSample;
+
+#[derive(Serialize, Deserialize)]
+pub struct Directional
+{
+    direction: Vec3,
+    color: Vec4,
+    intensity: f32
+}
+
+impl Directional
+{
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(direction: Vec3, color: Vec4, intensity: f32) -> Light
+    {
+        Light::Directional(Self
+        {
+            direction,
+            color,
+            intensity
+        })
+    }
+
+    pub fn sample(&self) -> LightSample
+    {
+        // Light arrives from infinitely far away along its direction.
+        LightSample
+        {
+            direction: self.direction.normalized() * -1.0,
+            distance: f32::MAX,
+            radiance: super::radiance(self.color, self.intensity),
+            pdf: 1.0,
+            is_delta: true
+        }
+    }
+}
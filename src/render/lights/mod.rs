@@ -0,0 +1,74 @@
+use serde::{ Serialize, Deserialize };
+
+use super::math;
+use super::math::Vec3;
+use super::math::Vec4;
+use super::math::Ray;
+use super::samplers;
+use super::samplers::PixelSampler;
+
+pub mod point;
+pub use point::Point;
+pub mod spot;
+pub use spot::Spot;
+pub mod directional;
+pub use directional::Directional;
+pub mod sphere;
+pub use sphere::Sphere;
+
+#[derive(Serialize, Deserialize)]
+pub enum Light
+{
+    Point(Point),
+    Spot(Spot),
+    Directional(Directional),
+    Sphere(Sphere)
+}
+
+pub struct LightSample
+{
+    pub direction: Vec3,
+    pub distance: f32,
+    pub radiance: Vec4,
+    pub pdf: f32,
+    pub is_delta: bool
+}
+
+pub struct LightHit
+{
+    pub distance: f32,
+    pub radiance: Vec4,
+    pub pdf: f32
+}
+
+impl Light
+{
+    pub fn sample(&self, point: Vec3, sampler: &mut PixelSampler) -> Option<LightSample>
+    {
+        match &self
+        {
+            Self::Point(point_light) => point_light.sample(point),
+            Self::Spot(spot_light) => spot_light.sample(point),
+            Self::Directional(directional_light) => Some(directional_light.sample()),
+            Self::Sphere(sphere_light) => sphere_light.sample(point, sampler)
+        }
+    }
+
+    pub fn intersect(&self, ray: &Ray, max_length: f32) -> Option<LightHit>
+    {
+        // Only lights with area can be hit by rays, others are infinitely small.
+        match &self
+        {
+            Self::Sphere(sphere_light) => sphere_light.intersect(ray, max_length),
+            _ => None
+        }
+    }
+}
+
+fn radiance(color: Vec4, intensity: f32) -> Vec4
+{
+    // Radiance has zero alpha like emission, so it can be added on top of scattered light.
+    let mut radiance = color * intensity;
+    radiance.set_a(0.0);
+    radiance
+}
@@ -0,0 +1,49 @@
+use serde::{ Serialize, Deserialize };
+use super::math::Vec3;
+use super::math::Vec4;
+use super::Light;
+use super::LightSample;
+
+#[derive(Serialize, Deserialize)]
+pub struct Point
+{
+    position: Vec3,
+    color: Vec4,
+    intensity: f32
+}
+
+impl Point
+{
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(position: Vec3, color: Vec4, intensity: f32) -> Light
+    {
+        Light::Point(Self
+        {
+            position,
+            color,
+            intensity
+        })
+    }
+
+    pub fn sample(&self, point: Vec3) -> Option<LightSample>
+    {
+        let offset = self.position - point;
+        let distance_sqr = offset.length_sqr();
+
+        if distance_sqr <= 0.0
+        {
+            return None;
+        }
+
+        let distance = distance_sqr.sqrt();
+
+        Some(LightSample
+        {
+            direction: offset / distance,
+            distance,
+            radiance: super::radiance(self.color, self.intensity / distance_sqr),
+            pdf: 1.0,
+            is_delta: true
+        })
+    }
+}
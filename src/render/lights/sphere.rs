@@ -0,0 +1,102 @@
+use serde::{ Serialize, Deserialize };
+use super::math::Vec3;
+use super::math::Vec4;
+use super::math::Ray;
+use super::math::geometry;
+use super::math::Intersectable;
+use super::Light;
+use super::LightSample;
+use super::LightHit;
+use super::samplers::PixelSampler;
+
+#[derive(Serialize, Deserialize)]
+pub struct Sphere
+{
+    #[serde(flatten)]
+    pub shape: geometry::Sphere,
+    color: Vec4,
+    intensity: f32
+}
+
+impl Sphere
+{
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(center: Vec3, radius: f32, color: Vec4, intensity: f32) -> Light
+    {
+        Light::Sphere(Self
+        {
+            shape: geometry::Sphere
+            {
+                center,
+                radius
+            },
+            color,
+            intensity
+        })
+    }
+
+    fn cone(&self, point: Vec3) -> Option<(Vec3, f32)>
+    {
+        // Returns direction towards center and one minus cosine of the cone angle subtended by the sphere.
+        // It is computed this way to avoid precision loss for small or distant lights.
+        let offset = self.shape.center - point;
+        let distance_sqr = offset.length_sqr();
+        let radius_sqr = self.shape.radius * self.shape.radius;
+
+        if distance_sqr <= radius_sqr
+        {
+            return None;
+        }
+
+        let sine_sqr = radius_sqr / distance_sqr;
+        let cosine = (1.0 - sine_sqr).sqrt();
+        let one_minus_cosine = sine_sqr / (1.0 + cosine);
+
+        Some((offset / distance_sqr.sqrt(), one_minus_cosine))
+    }
+
+    pub fn sample(&self, point: Vec3, sampler: &mut PixelSampler) -> Option<LightSample>
+    {
+        // Directions are sampled uniformly within cone subtended by the sphere.
+        // Implementation based on: https://www.pbr-book.org/3ed-2018/Light_Transport_I_Surface_Reflection/Sampling_Light_Sources
+        let (axis, one_minus_cosine) = self.cone(point)?;
+        let (tangent, bitangent) = axis.orthonormal_basis();
+
+        let sample = sampler.next_2d();
+        let cosine = 1.0 - sample.x * one_minus_cosine;
+        let sine = (1.0 - cosine * cosine).max(0.0).sqrt();
+        let angle = 2.0 * std::f32::consts::PI * sample.y;
+
+        let direction = (axis * cosine + tangent * (sine * angle.cos()) + bitangent * (sine * angle.sin())).normalized();
+        let ray = Ray::new(point, direction, 0.0);
+
+        // Sampled direction can graze sphere silhouette and miss due to precision.
+        let distance = match self.shape.intersect(&ray, 0.0, f32::MAX)
+        {
+            Some(intersection) => intersection.length,
+            None => (self.shape.center - point).dot(direction)
+        };
+
+        Some(LightSample
+        {
+            direction,
+            distance,
+            radiance: super::radiance(self.color, self.intensity),
+            pdf: 1.0 / (2.0 * std::f32::consts::PI * one_minus_cosine),
+            is_delta: false
+        })
+    }
+
+    pub fn intersect(&self, ray: &Ray, max_length: f32) -> Option<LightHit>
+    {
+        let (_, one_minus_cosine) = self.cone(ray.origin())?;
+        let intersection = self.shape.intersect(ray, 0.0, max_length)?;
+
+        Some(LightHit
+        {
+            distance: intersection.length,
+            radiance: super::radiance(self.color, self.intensity),
+            pdf: 1.0 / (2.0 * std::f32::consts::PI * one_minus_cosine)
+        })
+    }
+}
@@ -0,0 +1,83 @@
+use serde::{ Serialize, Deserialize };
+use super::math::Vec3;
+use super::math::Vec4;
+use super::Light;
+use super::LightSample;
+
+#[derive(Serialize, Deserialize)]
+pub struct Spot
+{
+    position: Vec3,
+    direction: Vec3,
+    color: Vec4,
+    intensity: f32,
+    inner_angle: f32,
+    outer_angle: f32
+}
+
+impl Spot
+{
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(position: Vec3, direction: Vec3, color: Vec4, intensity: f32, inner_angle: f32, outer_angle: f32) -> Light
+    {
+        Light::Spot(Self
+        {
+            position,
+            direction,
+            color,
+            intensity,
+            inner_angle,
+            outer_angle
+        })
+    }
+
+    fn falloff(&self, direction: Vec3) -> f32
+    {
+        // Light fades smoothly between inner and outer cone angles, which are specified in degrees.
+        let cosine = direction.dot(self.direction.normalized());
+        let inner_cosine = self.inner_angle.to_radians().cos();
+        let outer_cosine = self.outer_angle.to_radians().cos();
+
+        if cosine >= inner_cosine
+        {
+            return 1.0;
+        }
+
+        if cosine <= outer_cosine
+        {
+            return 0.0;
+        }
+
+        let alpha = (cosine - outer_cosine) / (inner_cosine - outer_cosine);
+        alpha * alpha * (3.0 - 2.0 * alpha)
+    }
+
+    pub fn sample(&self, point: Vec3) -> Option<LightSample>
+    {
+        let offset = self.position - point;
+        let distance_sqr = offset.length_sqr();
+
+        if distance_sqr <= 0.0
+        {
+            return None;
+        }
+
+        let distance = distance_sqr.sqrt();
+        let direction = offset / distance;
+        let falloff = self.falloff(direction * -1.0);
+
+        if falloff <= 0.0
+        {
+            return None;
+        }
+
+        Some(LightSample
+        {
+            direction,
+            distance,
+            radiance: super::radiance(self.color, self.intensity * falloff / distance_sqr),
+            pdf: 1.0,
+            is_delta: true
+        })
+    }
+}
@@ -1,14 +1,18 @@
+use std::path::Path;
+use std::hash::Hasher;
 use serde::{ Serialize, Deserialize };
 use super::math::Vec3;
 use super::math::Vec4;
 use super::math::Ray;
 use super::math::Intersection;
 use super::Material;
+use super::textures::Texture;
+use super::samplers::PixelSampler;
 
 #[derive(Serialize, Deserialize)]
 pub struct Diffuse
 {
-    albedo: Vec4
+    albedo: Texture
 }
 
 impl Default for Diffuse
@@ -17,7 +21,7 @@ impl Default for Diffuse
     {
         Self
         {
-            albedo: Vec4::new(0.5, 0.5, 0.5, 1.0)
+            albedo: Texture::from(Vec4::new(0.5, 0.5, 0.5, 1.0))
         }
     }
 }
@@ -25,19 +29,85 @@ impl Default for Diffuse
 impl Diffuse
 {
     #[allow(clippy::new_ret_no_self)]
-    pub fn new(albedo: Vec4) -> Material
+    pub fn new<T: Into<Texture>>(albedo: T) -> Material
     {
         Material::Diffuse(Self
         {
-            albedo
+            albedo: albedo.into()
         })
     }
 
-    pub fn scatter(&self, ray: &Ray, intersection: &Intersection) -> (Option<Ray>, Vec4)
+    /// # Errors
+    ///
+    /// Returns error when albedo texture cannot be loaded.
+    pub fn load(&mut self, directory: &Path) -> std::io::Result<()>
     {
-        let scatter_target = intersection.point + intersection.normal + Vec3::random_in_unit_sphere();
-        let scattered_ray = Ray::new(intersection.point, (scatter_target - intersection.point).normalized(), ray.time());
+        self.albedo.load(directory)
+    }
+
+    pub fn find_unloaded_image(&self) -> Option<&Path>
+    {
+        self.albedo.find_unloaded_image()
+    }
+
+    pub fn hash_resources<H: Hasher>(&self, hasher: &mut H)
+    {
+        self.albedo.hash_resources(hasher);
+    }
+
+    pub fn albedo(&self, intersection: &Intersection) -> Vec4
+    {
+        self.albedo.sample(intersection)
+    }
+
+    pub fn scatter(&self, ray: &Ray, intersection: &Intersection, sampler: &mut PixelSampler) -> (Option<Ray>, Vec4)
+    {
+        (Some(Self::scatter_lambertian(ray, intersection, sampler)), self.albedo.sample(intersection))
+    }
+
+    pub fn evaluate(&self, ray: &Ray, intersection: &Intersection, direction: Vec3) -> (Vec4, f32)
+    {
+        Self::evaluate_lambertian(self.albedo.sample(intersection), ray, intersection, direction)
+    }
+
+    fn facing_normal(ray: &Ray, intersection: &Intersection) -> Vec3
+    {
+        // Single sided geometry such as mesh triangles can be hit from behind.
+        if ray.direction().dot(intersection.normal) > 0.0
+        {
+            intersection.normal * -1.0
+        }
+        else
+        {
+            intersection.normal
+        }
+    }
+
+    pub fn scatter_lambertian(ray: &Ray, intersection: &Intersection, sampler: &mut PixelSampler) -> Ray
+    {
+        // Offsetting normal by point on unit sphere results in cosine weighted distribution.
+        let normal = Self::facing_normal(ray, intersection);
+        let scatter_direction = normal + Vec3::uniform_direction(sampler.next_2d());
+
+        if scatter_direction.is_zero()
+        {
+            return Ray::new(intersection.point, normal, ray.time());
+        }
+
+        Ray::new(intersection.point, scatter_direction.normalized(), ray.time())
+    }
+
+    pub fn evaluate_lambertian(albedo: Vec4, ray: &Ray, intersection: &Intersection, direction: Vec3) -> (Vec4, f32)
+    {
+        // Returns reflectance weighted by cosine term, along with probability density of scattering in given direction.
+        let cosine = Self::facing_normal(ray, intersection).dot(direction);
+
+        if cosine <= 0.0
+        {
+            return (Vec4::black(), 0.0);
+        }
 
-        (Some(scattered_ray), self.albedo)
+        let pdf = cosine / std::f32::consts::PI;
+        (albedo * pdf, pdf)
     }
 }
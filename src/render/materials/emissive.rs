@@ -0,0 +1,115 @@
+use std::path::Path;
+use std::hash::Hasher;
+use serde::{ Serialize, Deserialize };
+use super::math::Vec3;
+use super::math::Vec4;
+use super::math::Ray;
+use super::math::Intersection;
+use super::Material;
+use super::Diffuse;
+use super::textures::Texture;
+use super::samplers::PixelSampler;
+
+#[derive(Serialize, Deserialize)]
+pub struct Emissive
+{
+    #[serde(default = "Texture::black")]
+    albedo: Texture,
+    emission: Vec4,
+    intensity: f32
+}
+
+impl Default for Emissive
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            albedo: Texture::black(),
+            emission: Vec4::new(1.0, 1.0, 1.0, 1.0),
+            intensity: 1.0
+        }
+    }
+}
+
+impl Emissive
+{
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(emission: Vec4, intensity: f32) -> Material
+    {
+        Material::Emissive(Self
+        {
+            emission,
+            intensity,
+            ..Self::default()
+        })
+    }
+
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new_reflective<T: Into<Texture>>(albedo: T, emission: Vec4, intensity: f32) -> Material
+    {
+        Material::Emissive(Self
+        {
+            albedo: albedo.into(),
+            emission,
+            intensity
+        })
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when albedo texture of the emitter cannot be loaded.
+    pub fn load(&mut self, directory: &Path) -> std::io::Result<()>
+    {
+        self.albedo.load(directory)
+    }
+
+    pub fn find_unloaded_image(&self) -> Option<&Path>
+    {
+        self.albedo.find_unloaded_image()
+    }
+
+    pub fn hash_resources<H: Hasher>(&self, hasher: &mut H)
+    {
+        self.albedo.hash_resources(hasher);
+    }
+
+    pub fn albedo(&self, intersection: &Intersection) -> Vec4
+    {
+        self.albedo.sample(intersection)
+    }
+
+    pub fn emitted(&self) -> Vec4
+    {
+        // Emitted radiance has zero alpha, so it can be added on top of scattered light.
+        let mut radiance = self.emission * self.intensity;
+        radiance.set_a(0.0);
+        radiance
+    }
+
+    fn is_reflective(&self) -> bool
+    {
+        !self.albedo.is_black()
+    }
+
+    pub fn scatter(&self, ray: &Ray, intersection: &Intersection, sampler: &mut PixelSampler) -> (Option<Ray>, Vec4)
+    {
+        // Pure light sources with black albedo absorb everything that hits them.
+        if !self.is_reflective()
+        {
+            return (None, Vec4::black());
+        }
+
+        (Some(Diffuse::scatter_lambertian(ray, intersection, sampler)), self.albedo.sample(intersection))
+    }
+
+    pub fn evaluate(&self, ray: &Ray, intersection: &Intersection, direction: Vec3) -> Option<(Vec4, f32)>
+    {
+        if !self.is_reflective()
+        {
+            return None;
+        }
+
+        Some(Diffuse::evaluate_lambertian(self.albedo.sample(intersection), ray, intersection, direction))
+    }
+}
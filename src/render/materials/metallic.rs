@@ -1,14 +1,18 @@
+use std::path::Path;
+use std::hash::Hasher;
 use serde::{ Serialize, Deserialize };
 use super::math::Vec3;
 use super::math::Vec4;
 use super::math::Ray;
 use super::math::Intersection;
 use super::Material;
+use super::textures::Texture;
+use super::samplers::PixelSampler;
 
 #[derive(Serialize, Deserialize)]
 pub struct Metallic
 {
-    albedo: Vec4,
+    albedo: Texture,
     roughness: f32
 }
 
@@ -18,7 +22,7 @@ impl Default for Metallic
     {
         Self
         {
-            albedo: Vec4::new(1.0, 1.0, 1.0, 1.0),
+            albedo: Texture::from(Vec4::new(1.0, 1.0, 1.0, 1.0)),
             roughness: 0.0
         }
     }
@@ -27,21 +31,45 @@ impl Default for Metallic
 impl Metallic
 {
     #[allow(clippy::new_ret_no_self)]
-    pub fn new(albedo: Vec4, roughness: f32) -> Material
+    pub fn new<T: Into<Texture>>(albedo: T, roughness: f32) -> Material
     {
         Material::Metallic(Self
         {
-            albedo,
+            albedo: albedo.into(),
             roughness
         })
     }
 
-    pub fn scatter(&self, ray: &Ray, intersection: &Intersection) -> (Option<Ray>, Vec4)
+    /// # Errors
+    ///
+    /// Returns error when albedo texture cannot be loaded.
+    pub fn load(&mut self, directory: &Path) -> std::io::Result<()>
     {
-        let reflection_rougness = Vec3::random_in_unit_sphere() * self.roughness;
+        self.albedo.load(directory)
+    }
+
+    pub fn find_unloaded_image(&self) -> Option<&Path>
+    {
+        self.albedo.find_unloaded_image()
+    }
+
+    pub fn hash_resources<H: Hasher>(&self, hasher: &mut H)
+    {
+        self.albedo.hash_resources(hasher);
+    }
+
+    pub fn albedo(&self, intersection: &Intersection) -> Vec4
+    {
+        self.albedo.sample(intersection)
+    }
+
+    pub fn scatter(&self, ray: &Ray, intersection: &Intersection, sampler: &mut PixelSampler) -> (Option<Ray>, Vec4)
+    {
+        // Direction scaled by cube root of uniform value is uniformly distributed within unit sphere.
+        let reflection_rougness = Vec3::uniform_direction(sampler.next_2d()) * sampler.next_1d().cbrt() * self.roughness;
         let reflected_dir = (ray.direction().reflected(intersection.normal) + reflection_rougness).normalized();
         let scattered_ray = Ray::new(intersection.point, reflected_dir, ray.time());
 
-        (Some(scattered_ray), self.albedo)
+        (Some(scattered_ray), self.albedo.sample(intersection))
     }
 }
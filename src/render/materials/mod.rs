@@ -1,9 +1,15 @@
+use std::path::Path;
+use std::hash::Hasher;
 use serde::{ Serialize, Deserialize };
 
 use super::math;
+use super::math::Vec3;
 use super::math::Vec4;
 use super::math::Ray;
 use super::math::Intersection;
+use super::textures;
+use super::samplers;
+use super::samplers::PixelSampler;
 
 pub mod diffuse;
 pub use diffuse::Diffuse;
@@ -13,6 +19,8 @@ pub mod metallic;
 pub use metallic::Metallic;
 pub mod refractive;
 pub use refractive::Refractive;
+pub mod emissive;
+pub use emissive::Emissive;
 
 #[derive(Serialize, Deserialize)]
 pub enum Material
@@ -20,19 +28,115 @@ pub enum Material
     Diffuse(Diffuse),
     Normals(Normals),
     Metallic(Metallic),
-    Refractive(Refractive)
+    Refractive(Refractive),
+    Emissive(Emissive)
+}
+
+pub struct Scattering
+{
+    pub ray: Option<Ray>,
+    pub attenuation: Vec4,
+    pub emission: Vec4,
+    pub pdf: Option<f32>
 }
 
 impl Material
 {
-    pub fn scatter(&self, ray: &Ray, intersection: &Intersection, scatter_index: u16) -> (Option<Ray>, Vec4)
+    /// # Errors
+    ///
+    /// Returns error when any texture used by the material cannot be loaded.
+    pub fn load(&mut self, directory: &Path) -> std::io::Result<()>
     {
-        match &self
+        match self
         {
-            Self::Diffuse(diffuse) => diffuse.scatter(ray, intersection),
+            Self::Diffuse(diffuse) => diffuse.load(directory),
+            Self::Normals(_) => Ok(()),
+            Self::Metallic(metallic) => metallic.load(directory),
+            Self::Refractive(refractive) => refractive.load(directory),
+            Self::Emissive(emissive) => emissive.load(directory)
+        }
+    }
+
+    pub fn find_unloaded_image(&self) -> Option<&Path>
+    {
+        match self
+        {
+            Self::Diffuse(diffuse) => diffuse.find_unloaded_image(),
+            Self::Normals(_) => None,
+            Self::Metallic(metallic) => metallic.find_unloaded_image(),
+            Self::Refractive(refractive) => refractive.find_unloaded_image(),
+            Self::Emissive(emissive) => emissive.find_unloaded_image()
+        }
+    }
+
+    pub fn hash_resources<H: Hasher>(&self, hasher: &mut H)
+    {
+        match self
+        {
+            Self::Diffuse(diffuse) => diffuse.hash_resources(hasher),
+            Self::Normals(_) => (),
+            Self::Metallic(metallic) => metallic.hash_resources(hasher),
+            Self::Refractive(refractive) => refractive.hash_resources(hasher),
+            Self::Emissive(emissive) => emissive.hash_resources(hasher)
+        }
+    }
+
+    pub fn scatter(&self, ray: &Ray, intersection: &Intersection, scatter_index: u16, sampler: &mut PixelSampler) -> Scattering
+    {
+        let (scattered_ray, attenuation) = match &self
+        {
+            Self::Diffuse(diffuse) => diffuse.scatter(ray, intersection, sampler),
             Self::Normals(_normals) => Normals::scatter(intersection, scatter_index),
-            Self::Metallic(metallic) => metallic.scatter(ray, intersection),
-            Self::Refractive(refractive) => refractive.scatter(ray, intersection)
+            Self::Metallic(metallic) => metallic.scatter(ray, intersection, sampler),
+            Self::Refractive(refractive) => refractive.scatter(ray, intersection, sampler),
+            Self::Emissive(emissive) => emissive.scatter(ray, intersection, sampler)
+        };
+
+        // Probability density is only known for materials that are not perfectly specular.
+        let pdf = scattered_ray.and_then(|scattered_ray|
+        {
+            self.evaluate(ray, intersection, scattered_ray.direction()).map(|(_, pdf)| pdf)
+        });
+
+        Scattering
+        {
+            ray: scattered_ray,
+            attenuation,
+            emission: self.emitted(),
+            pdf
+        }
+    }
+
+    pub fn evaluate(&self, ray: &Ray, intersection: &Intersection, direction: Vec3) -> Option<(Vec4, f32)>
+    {
+        // Returns None for materials that scatter in a single direction, as lights cannot be sampled for them.
+        match &self
+        {
+            Self::Diffuse(diffuse) => Some(diffuse.evaluate(ray, intersection, direction)),
+            Self::Emissive(emissive) => emissive.evaluate(ray, intersection, direction),
+            _ => None
+        }
+    }
+
+    pub fn albedo(&self, intersection: &Intersection) -> Vec4
+    {
+        // Color of the surface itself, without any lighting.
+        match &self
+        {
+            Self::Diffuse(diffuse) => diffuse.albedo(intersection),
+            Self::Normals(_) => Normals::albedo(intersection),
+            Self::Metallic(metallic) => metallic.albedo(intersection),
+            Self::Refractive(refractive) => refractive.albedo(intersection),
+            Self::Emissive(emissive) => emissive.albedo(intersection)
+        }
+    }
+
+    pub fn emitted(&self) -> Vec4
+    {
+        match &self
+        {
+            Self::Emissive(emissive) => emissive.emitted(),
+            _ => Vec4::zero()
         }
     }
 }
@@ -20,15 +20,17 @@ impl Normals
     pub fn scatter(intersection: &Intersection, scatter_index: u16) -> (Option<Ray>, Vec4)
     {
         debug_assert!(scatter_index == 0, "Did not expect debug material for normals to scatter!");
+        (None, Self::albedo(intersection))
+    }
 
-        let normal_color = Vec4::new
+    pub fn albedo(intersection: &Intersection) -> Vec4
+    {
+        Vec4::new
         (
             (intersection.normal.get_x() + 1.0) * 0.5,
             (intersection.normal.get_y() + 1.0) * 0.5,
             (intersection.normal.get_z() + 1.0) * 0.5,
             1.0
-        );
-
-        (None, normal_color)
+        )
     }
 }
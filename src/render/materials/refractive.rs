@@ -1,13 +1,17 @@
+use std::path::Path;
+use std::hash::Hasher;
 use serde::{ Serialize, Deserialize };
 use super::math::Vec4;
 use super::math::Ray;
 use super::math::Intersection;
 use super::Material;
+use super::textures::Texture;
+use super::samplers::PixelSampler;
 
 #[derive(Serialize, Deserialize)]
 pub struct Refractive
 {
-    albedo: Vec4,
+    albedo: Texture,
     refractive_index: f32
 }
 
@@ -17,7 +21,7 @@ impl Default for Refractive
     {
         Self
         {
-            albedo: Vec4::new(1.0, 1.0, 1.0, 1.0),
+            albedo: Texture::from(Vec4::new(1.0, 1.0, 1.0, 1.0)),
             refractive_index: 0.0
         }
     }
@@ -26,16 +30,39 @@ impl Default for Refractive
 impl Refractive
 {
     #[allow(clippy::new_ret_no_self)]
-    pub fn new(albedo: Vec4, refractive_index: f32) -> Material
+    pub fn new<T: Into<Texture>>(albedo: T, refractive_index: f32) -> Material
     {
         Material::Refractive(Self
         {
-            albedo,
+            albedo: albedo.into(),
             refractive_index
         })
     }
 
-    pub fn scatter(&self, ray: &Ray, intersection: &Intersection) -> (Option<Ray>, Vec4)
+    /// # Errors
+    ///
+    /// Returns error when albedo texture cannot be loaded.
+    pub fn load(&mut self, directory: &Path) -> std::io::Result<()>
+    {
+        self.albedo.load(directory)
+    }
+
+    pub fn find_unloaded_image(&self) -> Option<&Path>
+    {
+        self.albedo.find_unloaded_image()
+    }
+
+    pub fn hash_resources<H: Hasher>(&self, hasher: &mut H)
+    {
+        self.albedo.hash_resources(hasher);
+    }
+
+    pub fn albedo(&self, intersection: &Intersection) -> Vec4
+    {
+        self.albedo.sample(intersection)
+    }
+
+    pub fn scatter(&self, ray: &Ray, intersection: &Intersection, sampler: &mut PixelSampler) -> (Option<Ray>, Vec4)
     {
         let outward_normal;
         let cosine;
@@ -65,13 +92,13 @@ impl Refractive
         {
             let reflection_propability = schlick(cosine, self.refractive_index);
 
-            if rand::random::<f32>() >= reflection_propability
+            if sampler.next_1d() >= reflection_propability
             {
-                return (Some(Ray::new(intersection.point, refracted, ray.time())), self.albedo);
+                return (Some(Ray::new(intersection.point, refracted, ray.time())), self.albedo.sample(intersection));
             }
         }
 
         let reflected = ray.direction().reflected(intersection.normal);
-        (Some(Ray::new(intersection.point, reflected, ray.time())), self.albedo)
+        (Some(Ray::new(intersection.point, reflected, ray.time())), self.albedo.sample(intersection))
     }
 }
@@ -3,15 +3,44 @@ use super::image;
 
 pub mod parameters;
 pub use parameters::Parameters;
-pub use parameters::DebugMode;
+pub use parameters::AdaptiveSampling;
 pub mod scene;
 pub use scene::Scene;
+pub mod environment;
+pub use environment::Environment;
 pub mod camera;
+pub mod samplers;
+pub use samplers::Sampler;
+pub mod textures;
+pub use textures::Texture;
 pub mod materials;
+pub mod lights;
+pub use lights::Light;
 pub mod objects;
 pub use objects::Object;
+pub mod integrators;
+pub use integrators::Integrator;
+pub use integrators::IntegratorKind;
 pub mod renderer;
 pub use renderer::Renderer;
+pub mod aovs;
+pub use aovs::Aov;
+pub mod film;
+pub use film::Film;
+pub use film::Filter;
+pub mod denoiser;
+pub use denoiser::Denoiser;
+pub mod tonemapping;
+pub use tonemapping::ToneMapping;
+pub use tonemapping::ToneCurve;
+pub mod tiles;
+pub use tiles::TileOrder;
+pub mod progress;
+pub use progress::Progress;
+pub use progress::CancellationToken;
+pub mod checkpoint;
+pub use checkpoint::Checkpoint;
+pub mod distributed;
 pub mod statistics;
 pub use statistics::Statistics;
 pub mod setup;
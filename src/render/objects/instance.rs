@@ -0,0 +1,20 @@
+use serde::{ Serialize, Deserialize };
+use super::ObjectKind;
+
+#[derive(Serialize, Deserialize)]
+pub struct Instance
+{
+    pub geometry: String
+}
+
+impl Instance
+{
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(geometry: &str) -> ObjectKind
+    {
+        ObjectKind::Instance(Self
+        {
+            geometry: geometry.to_owned()
+        })
+    }
+}
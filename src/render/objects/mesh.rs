@@ -0,0 +1,186 @@
+use std::sync::OnceLock;
+use serde::{ Serialize, Deserialize };
+use super::math::Vec2;
+use super::math::Vec3;
+use super::math::Ray;
+use super::math::Intersection;
+use super::math::Intersectable;
+use super::math::BoundingBox;
+use super::math::Hierarchy;
+use super::math::geometry;
+use super::materials::Material;
+use super::ObjectKind;
+
+#[derive(Debug)]
+pub enum Error
+{
+    IncompleteTriangle,
+    NormalCount,
+    UvCount,
+    InvalidIndex(usize)
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Mesh
+{
+    pub positions: Vec<Vec3>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub normals: Option<Vec<Vec3>>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub uvs: Option<Vec<Vec2>>,
+
+    pub indices: Vec<u32>,
+    pub material: Material,
+
+    #[serde(skip)]
+    hierarchy: OnceLock<Hierarchy>
+}
+
+impl Mesh
+{
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(positions: Vec<Vec3>, normals: Option<Vec<Vec3>>, uvs: Option<Vec<Vec2>>, indices: Vec<u32>, material: Material) -> ObjectKind
+    {
+        ObjectKind::Mesh(Self::from(positions, normals, uvs, indices, material))
+    }
+
+    pub fn from(positions: Vec<Vec3>, normals: Option<Vec<Vec3>>, uvs: Option<Vec<Vec2>>, indices: Vec<u32>, material: Material) -> Self
+    {
+        Self
+        {
+            positions,
+            normals,
+            uvs,
+            indices,
+            material,
+            hierarchy: OnceLock::new()
+        }
+    }
+
+    /// # Errors
+    ///
+    /// Returns error for the first index or attribute count that does not match mesh vertices.
+    pub fn validate(&self) -> Result<(), Error>
+    {
+        // Checked once before rendering, so triangles never read outside of vertex attributes.
+        if !self.indices.len().is_multiple_of(3)
+        {
+            return Err(Error::IncompleteTriangle);
+        }
+
+        if self.normals.as_ref().is_some_and(|normals| normals.len() != self.positions.len())
+        {
+            return Err(Error::NormalCount);
+        }
+
+        if self.uvs.as_ref().is_some_and(|uvs| uvs.len() != self.positions.len())
+        {
+            return Err(Error::UvCount);
+        }
+
+        match self.indices.iter().position(|index| *index as usize >= self.positions.len())
+        {
+            Some(position) => Err(Error::InvalidIndex(position)),
+            None => Ok(())
+        }
+    }
+
+    pub fn triangle_count(&self) -> usize
+    {
+        self.indices.len() / 3
+    }
+
+    fn vertex_indices(&self, triangle: usize) -> [usize; 3]
+    {
+        let first = triangle * 3;
+
+        [
+            self.indices[first] as usize,
+            self.indices[first + 1] as usize,
+            self.indices[first + 2] as usize
+        ]
+    }
+
+    pub fn triangle(&self, triangle: usize) -> geometry::Triangle
+    {
+        let [first, second, third] = self.vertex_indices(triangle);
+        geometry::Triangle::new(self.positions[first], self.positions[second], self.positions[third])
+    }
+
+    fn get_hierarchy(&self) -> &Hierarchy
+    {
+        self.hierarchy.get_or_init(||
+        {
+            let bounds: Vec<BoundingBox> = (0..self.triangle_count()).map(|triangle|
+            {
+                self.triangle(triangle).bounding_box()
+            }).collect();
+
+            Hierarchy::new(&bounds)
+        })
+    }
+
+    pub fn bounding_box(&self) -> BoundingBox
+    {
+        self.get_hierarchy().bounding_box()
+    }
+
+    #[allow(clippy::similar_names)]
+    fn intersect_triangle(&self, triangle: usize, ray: &Ray, min_length: f32, max_length: f32) -> Option<Intersection>
+    {
+        let (length, along_ab, along_ac) = self.triangle(triangle).intersect_barycentric(ray, min_length, max_length)?;
+
+        // Smooth shading interpolates per vertex normals, otherwise flat face normal is used.
+        let normal = match &self.normals
+        {
+            Some(normals) =>
+            {
+                let [first, second, third] = self.vertex_indices(triangle);
+                let interpolated = normals[first] * (1.0 - along_ab - along_ac) +
+                    normals[second] * along_ab + normals[third] * along_ac;
+
+                if interpolated.is_zero()
+                {
+                    self.triangle(triangle).normal()
+                }
+                else
+                {
+                    interpolated.normalized()
+                }
+            },
+            None => self.triangle(triangle).normal()
+        };
+
+        // Texture coordinates are interpolated the same way, with barycentric coordinates used when missing.
+        let uv = match &self.uvs
+        {
+            Some(uvs) =>
+            {
+                let [first, second, third] = self.vertex_indices(triangle);
+                uvs[first] * (1.0 - along_ab - along_ac) + uvs[second] * along_ab + uvs[third] * along_ac
+            },
+            None => Vec2::new(along_ab, along_ac)
+        };
+
+        Some(Intersection
+        {
+            point: ray.point_at(length),
+            normal,
+            uv,
+            length
+        })
+    }
+}
+
+impl Intersectable for Mesh
+{
+    fn intersect(&self, ray: &Ray, min_length: f32, max_length: f32) -> Option<Intersection>
+    {
+        self.get_hierarchy().intersect(ray, min_length, max_length, |triangle, min_length, max_length|
+        {
+            self.intersect_triangle(triangle, ray, min_length, max_length).map(|intersection| (intersection, ()))
+        }).map(|(intersection, ())| intersection)
+    }
+}
@@ -1,20 +1,152 @@
+use std::path::Path;
+use std::hash::Hasher;
+use std::sync::OnceLock;
+use std::collections::BTreeMap;
 use serde::{ Serialize, Deserialize };
 
 use super::math;
 use super::materials;
 use super::math::Vec3;
+use super::math::Mat4;
+use super::math::Transform;
 use super::math::Ray;
 use super::math::Intersection;
 use super::math::Intersectable;
+use super::math::BoundingBox;
 use super::materials::Material;
 
 pub mod sphere;
 pub use sphere::Sphere;
+pub mod mesh;
+pub use mesh::Mesh;
+pub mod model;
+pub use model::Model;
+pub mod instance;
+pub use instance::Instance;
+pub mod wavefront;
 
 #[derive(Serialize, Deserialize)]
 pub enum ObjectKind
 {
-    Sphere(Sphere)
+    Sphere(Sphere),
+    Mesh(Mesh),
+    Model(Model),
+    Instance(Instance)
+}
+
+pub type Geometry = BTreeMap<String, ObjectKind>;
+
+impl ObjectKind
+{
+    /// # Errors
+    ///
+    /// Returns error when model file cannot be loaded.
+    pub fn load(&mut self, directory: &Path) -> Result<(), wavefront::Error>
+    {
+        match self
+        {
+            Self::Model(model) => model.load(directory),
+            _ => Ok(())
+        }
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when texture of sphere or mesh material cannot be loaded.
+    pub fn load_textures(&mut self, directory: &Path) -> std::io::Result<()>
+    {
+        // Model materials are fully described by their material library files.
+        match self
+        {
+            Self::Sphere(sphere) => sphere.material.load(directory),
+            Self::Mesh(mesh) => mesh.material.load(directory),
+            _ => Ok(())
+        }
+    }
+
+    pub fn find_unloaded_image(&self) -> Option<&Path>
+    {
+        // Model materials are fully described by their material library files, so they never reference images.
+        match self
+        {
+            Self::Sphere(sphere) => sphere.material.find_unloaded_image(),
+            Self::Mesh(mesh) => mesh.material.find_unloaded_image(),
+            _ => None
+        }
+    }
+
+    pub fn find_unloaded_model(&self) -> Option<&Path>
+    {
+        match self
+        {
+            Self::Model(model) if !model.is_loaded() => Some(&model.path),
+            _ => None
+        }
+    }
+
+    pub fn hash_resources<H: Hasher>(&self, hasher: &mut H)
+    {
+        // Instances use resources of geometry they reference, which is hashed separately.
+        match self
+        {
+            Self::Sphere(sphere) => sphere.material.hash_resources(hasher),
+            Self::Mesh(mesh) => mesh.material.hash_resources(hasher),
+            Self::Model(model) => model.hash_resources(hasher),
+            Self::Instance(_) => ()
+        }
+    }
+
+    fn resolve<'a>(&'a self, geometry: &'a Geometry) -> Option<&'a Self>
+    {
+        // Instances cannot reference other instances, which also prevents reference cycles.
+        match self
+        {
+            Self::Instance(instance) => geometry.get(&instance.geometry)
+                .filter(|kind| !matches!(kind, Self::Instance(_))),
+            _ => Some(self)
+        }
+    }
+
+    fn intersect<'a>(&'a self, ray: &Ray, min_length: f32, max_length: f32, geometry: &'a Geometry) -> Option<(Intersection, &'a Material)>
+    {
+        match self.resolve(geometry)?
+        {
+            Self::Sphere(sphere) =>
+            {
+                sphere.shape.intersect(ray, min_length, max_length)
+                    .map(|intersection| (intersection, &sphere.material))
+            },
+            Self::Mesh(mesh) =>
+            {
+                mesh.intersect(ray, min_length, max_length)
+                    .map(|intersection| (intersection, &mesh.material))
+            },
+            Self::Model(model) => model.intersect(ray, min_length, max_length),
+            Self::Instance(_) => None
+        }
+    }
+
+    fn materials<'a>(&'a self, geometry: &'a Geometry) -> Vec<&'a Material>
+    {
+        match self.resolve(geometry)
+        {
+            Some(Self::Sphere(sphere)) => vec![&sphere.material],
+            Some(Self::Mesh(mesh)) => vec![&mesh.material],
+            Some(Self::Model(model)) => model.meshes().iter().map(|mesh| &mesh.material).collect(),
+            _ => Vec::new()
+        }
+    }
+
+    fn bounding_box(&self, geometry: &Geometry) -> BoundingBox
+    {
+        match self.resolve(geometry)
+        {
+            Some(Self::Sphere(sphere)) => sphere.shape.bounding_box(),
+            Some(Self::Mesh(mesh)) => mesh.bounding_box(),
+            Some(Self::Model(model)) => model.bounding_box(),
+            _ => BoundingBox::empty()
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -23,8 +155,14 @@ pub struct Object
     #[serde(flatten)]
     kind: ObjectKind,
 
+    #[serde(skip_serializing_if = "Transform::is_identity", default)]
+    transform: Transform,
+
     #[serde(skip_serializing_if = "Vec3::is_zero", default)] 
-    velocity: Vec3
+    velocity: Vec3,
+
+    #[serde(skip)]
+    matrices: OnceLock<(Mat4, Mat4)>
 }
 
 impl Object
@@ -34,7 +172,9 @@ impl Object
         Self
         {
             kind,
-            velocity: Vec3::zero()
+            transform: Transform::default(),
+            velocity: Vec3::zero(),
+            matrices: OnceLock::new()
         }
     }
 
@@ -42,11 +182,25 @@ impl Object
     {
         Self
         {
-            kind,
-            velocity
+            velocity,
+            ..Self::new(kind)
         }
     }
 
+    pub fn new_transformed(kind: ObjectKind, transform: Transform) -> Self
+    {
+        Self
+        {
+            transform,
+            ..Self::new(kind)
+        }
+    }
+
+    pub fn kind(&self) -> &ObjectKind
+    {
+        &self.kind
+    }
+
     pub fn velocity(&self) -> Vec3
     {
         self.velocity
@@ -57,22 +211,130 @@ impl Object
         self.velocity = velocity;
     }
 
-    pub fn intersect(&self, ray: &Ray, min_length: f32, max_length: f32) -> Option<Intersection>
+    pub fn transform(&self) -> &Transform
+    {
+        &self.transform
+    }
+
+    pub fn set_transform(&mut self, transform: Transform)
+    {
+        self.transform = transform;
+        self.matrices = OnceLock::new();
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when model file of the object cannot be loaded.
+    pub fn load(&mut self, directory: &Path) -> Result<(), wavefront::Error>
+    {
+        self.kind.load(directory)
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when texture of object material cannot be loaded.
+    pub fn load_textures(&mut self, directory: &Path) -> std::io::Result<()>
+    {
+        self.kind.load_textures(directory)
+    }
+
+    pub fn has_invertible_transform(&self) -> bool
+    {
+        // Transforms that collapse object, like zero scale, cannot move rays into object space.
+        self.transform.is_identity() || self.transform.matrix().inverse().is_some()
+    }
+
+    fn get_matrices(&self) -> &(Mat4, Mat4)
     {
-        match &self.kind
+        // Object to world matrix and its inverse are computed once on first use, after transform has been validated.
+        self.matrices.get_or_init(||
         {
-            ObjectKind::Sphere(sphere) =>
+            let matrix = self.transform.matrix();
+            let inverse = matrix.inverse().expect("Object transform cannot be inverted!");
+            (matrix, inverse)
+        })
+    }
+
+    pub fn intersect<'a>(&'a self, ray: &Ray, min_length: f32, max_length: f32, geometry: &'a Geometry) -> Option<(Intersection, &'a Material)>
+    {
+        // Instead of moving every vertex, ray is moved in the opposite direction.
+        let offset = self.velocity() * ray.time();
+        let moved_ray = Ray::new(ray.origin() - offset, ray.direction(), ray.time());
+
+        if self.transform.is_identity()
+        {
+            return self.kind.intersect(&moved_ray, min_length, max_length, geometry).map(|(intersection, material)|
             {
-                sphere.at_time(ray.time(), self.velocity()).intersect(ray, min_length, max_length)
-            }
+                (Intersection
+                {
+                    point: intersection.point + offset,
+                    ..intersection
+                }, material)
+            });
         }
+
+        // Ray is transformed to object space, where its direction needs to be normalized again.
+        // Lengths along the ray are scaled accordingly, so they remain comparable between objects.
+        let (matrix, inverse) = self.get_matrices();
+        let local_direction = inverse.transform_vector(moved_ray.direction());
+        let length_scale = local_direction.length();
+
+        let local_ray = Ray::new(inverse.transform_point(moved_ray.origin()), local_direction / length_scale, ray.time());
+
+        self.kind.intersect(&local_ray, min_length * length_scale, max_length * length_scale, geometry).map(|(intersection, material)|
+        {
+            // Normals are transformed by inverse transpose to remain perpendicular under non-uniform scale.
+            (Intersection
+            {
+                point: matrix.transform_point(intersection.point) + offset,
+                normal: inverse.transposed().transform_vector(intersection.normal).normalized(),
+                length: intersection.length / length_scale,
+                ..intersection
+            }, material)
+        })
     }
 
-    pub fn get_material(&self) -> &Material
+    pub fn materials<'a>(&'a self, geometry: &'a Geometry) -> Vec<&'a Material>
     {
-        match &self.kind
+        // Instances use materials of geometry they reference.
+        self.kind.materials(geometry)
+    }
+
+    pub fn bounding_box(&self, open_time: f32, close_time: f32, geometry: &Geometry) -> BoundingBox
+    {
+        let mut bounds = self.kind.bounding_box(geometry);
+
+        if bounds.is_empty()
+        {
+            return bounds;
+        }
+
+        if !self.transform.is_identity()
         {
-            ObjectKind::Sphere(sphere) => &sphere.material
+            // Transformed bounds enclose all corners of the object space bounds.
+            let (matrix, _) = self.get_matrices();
+            let mut transformed = BoundingBox::empty();
+
+            for corner in 0..8
+            {
+                let point = Vec3::new
+                (
+                    if corner & 1 == 0 { bounds.min.get_x() } else { bounds.max.get_x() },
+                    if corner & 2 == 0 { bounds.min.get_y() } else { bounds.max.get_y() },
+                    if corner & 4 == 0 { bounds.min.get_z() } else { bounds.max.get_z() }
+                );
+
+                transformed = transformed.merged(&BoundingBox::from_point(matrix.transform_point(point)));
+            }
+
+            bounds = transformed;
         }
+
+        // Moving objects are bounded by their swept extent over the whole shutter interval.
+        let open_offset = self.velocity() * open_time;
+        let close_offset = self.velocity() * close_time;
+
+        BoundingBox::new(bounds.min + open_offset, bounds.max + open_offset)
+            .merged(&BoundingBox::new(bounds.min + close_offset, bounds.max + close_offset))
     }
 }
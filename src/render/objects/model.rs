@@ -0,0 +1,87 @@
+use std::path::{ Path, PathBuf };
+use std::hash::Hasher;
+use serde::{ Serialize, Deserialize };
+use super::math::Ray;
+use super::math::Intersection;
+use super::math::Intersectable;
+use super::math::BoundingBox;
+use super::materials::Material;
+use super::wavefront;
+use super::Mesh;
+use super::ObjectKind;
+
+#[derive(Serialize, Deserialize)]
+pub struct Model
+{
+    pub path: PathBuf,
+
+    #[serde(skip)]
+    meshes: Option<Vec<Mesh>>
+}
+
+impl Model
+{
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new<P: AsRef<Path>>(path: P) -> ObjectKind
+    {
+        ObjectKind::Model(Self
+        {
+            path: PathBuf::from(path.as_ref()),
+            meshes: None
+        })
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when model file cannot be read or parsed.
+    pub fn load(&mut self, directory: &Path) -> Result<(), wavefront::Error>
+    {
+        // Relative paths are resolved against directory of the setup file that references the model.
+        self.meshes = Some(wavefront::load(directory.join(&self.path))?);
+        Ok(())
+    }
+
+    pub fn is_loaded(&self) -> bool
+    {
+        self.meshes.is_some()
+    }
+
+    /// # Panics
+    ///
+    /// Panics when mesh cannot be serialized.
+    pub fn hash_resources<H: Hasher>(&self, hasher: &mut H)
+    {
+        // Meshes are hashed together with their materials, which also covers material libraries of the model.
+        for mesh in self.meshes()
+        {
+            hasher.write(&serde_json::to_vec(mesh).expect("Failed to serialize mesh!"));
+        }
+    }
+
+    pub fn meshes(&self) -> &[Mesh]
+    {
+        self.meshes.as_deref().unwrap_or_default()
+    }
+
+    pub fn bounding_box(&self) -> BoundingBox
+    {
+        self.meshes().iter().fold(BoundingBox::empty(), |merged, mesh| merged.merged(&mesh.bounding_box()))
+    }
+
+    pub fn intersect(&self, ray: &Ray, min_length: f32, max_length: f32) -> Option<(Intersection, &Material)>
+    {
+        let mut closest_intersection: Option<(Intersection, &Material)> = None;
+        let mut closest_length = max_length;
+
+        for mesh in self.meshes()
+        {
+            if let Some(intersection) = mesh.intersect(ray, min_length, closest_length)
+            {
+                closest_length = intersection.length;
+                closest_intersection = Some((intersection, &mesh.material));
+            }
+        }
+
+        closest_intersection
+    }
+}
@@ -27,13 +27,4 @@ impl Sphere
             material
         })
     }
-
-    pub fn at_time(&self, time: f32, velocity: Vec3) -> geometry::Sphere
-    {
-        geometry::Sphere
-        {
-            center: self.shape.center + velocity * time,
-            radius: self.shape.radius
-        }
-    }
 }
@@ -0,0 +1,406 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs::OpenOptions;
+use std::io::{ BufRead, BufReader };
+use std::path::Path;
+use super::math::Vec2;
+use super::math::Vec3;
+use super::math::Vec4;
+use super::materials;
+use super::materials::Material;
+use super::Mesh;
+use super::mesh;
+
+// Loader for Wavefront OBJ geometry and MTL material libraries.
+// Specification: http://paulbourke.net/dataformats/obj/ and http://paulbourke.net/dataformats/mtl/
+
+#[derive(Debug)]
+pub enum Error
+{
+    OpeningFile,
+    ReadingFile,
+    InvalidStatement(usize),
+    InvalidIndex(usize),
+    InvalidMesh(mesh::Error)
+}
+
+#[derive(Copy, Clone)]
+struct MaterialDescription
+{
+    diffuse: Vec4,
+    specular: Vec4,
+    specular_exponent: f32,
+    refractive_index: f32,
+    dissolve: f32
+}
+
+impl Default for MaterialDescription
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            diffuse: Vec4::new(0.8, 0.8, 0.8, 1.0),
+            specular: Vec4::black(),
+            specular_exponent: 0.0,
+            refractive_index: 1.0,
+            dissolve: 1.0
+        }
+    }
+}
+
+impl MaterialDescription
+{
+    fn as_material(&self) -> Material
+    {
+        // Translucent materials become refractive, materials with specular color stronger
+        // than diffuse one become metallic and everything else is treated as diffuse.
+        let brightest = |color: Vec4| color.get_r().max(color.get_g()).max(color.get_b());
+
+        if self.dissolve < 1.0
+        {
+            // Index of refraction of one would make surface invisible, so assume glass instead.
+            let refractive_index = if self.refractive_index > 1.0 { self.refractive_index } else { 1.5 };
+            materials::Refractive::new(Vec4::white(), refractive_index)
+        }
+        else if brightest(self.specular) > brightest(self.diffuse)
+        {
+            // Approximate conversion from Phong exponent to roughness.
+            let roughness = (2.0 / (self.specular_exponent.max(0.0) + 2.0)).sqrt();
+            materials::Metallic::new(self.specular, roughness)
+        }
+        else
+        {
+            materials::Diffuse::new(self.diffuse)
+        }
+    }
+}
+
+#[derive(Default)]
+struct MeshBuilder
+{
+    positions: Vec<Vec3>,
+    normals: Vec<Vec3>,
+    uvs: Vec<Vec2>,
+    indices: Vec<u32>,
+    vertices: HashMap<(usize, Option<usize>, Option<usize>), u32>,
+    has_normals: bool,
+    has_uvs: bool
+}
+
+impl MeshBuilder
+{
+    fn new() -> Self
+    {
+        Self
+        {
+            has_normals: true,
+            has_uvs: true,
+            ..Self::default()
+        }
+    }
+
+    fn add_vertex(&mut self, key: (usize, Option<usize>, Option<usize>), positions: &[Vec3], uvs: &[Vec2], normals: &[Vec3]) -> Option<u32>
+    {
+        // OBJ indexes each attribute separately, so unique combinations are merged into shared vertices.
+        // Meshes are indexed with 32 bits, so vertices beyond that cannot be added.
+        let next_index = self.positions.len();
+        let index = *self.vertices.entry(key).or_insert(u32::try_from(next_index).ok()?);
+
+        if index as usize == next_index
+        {
+            let (position, uv, normal) = key;
+
+            self.positions.push(positions[position]);
+            self.uvs.push(uv.map_or(Vec2::default(), |uv| uvs[uv]));
+            self.normals.push(normal.map_or(Vec3::zero(), |normal| normals[normal]));
+
+            self.has_uvs &= uv.is_some();
+            self.has_normals &= normal.is_some();
+        }
+
+        Some(index)
+    }
+
+    fn build(self, material: Material) -> Result<Mesh, Error>
+    {
+        let normals = if self.has_normals { Some(self.normals) } else { None };
+        let uvs = if self.has_uvs { Some(self.uvs) } else { None };
+
+        let mesh = Mesh::from(self.positions, normals, uvs, self.indices, material);
+        mesh.validate().map_err(Error::InvalidMesh)?;
+        Ok(mesh)
+    }
+}
+
+fn parse_floats<const COUNT: usize>(arguments: &[&str], line: usize) -> Result<[f32; COUNT], Error>
+{
+    let mut values = [0.0; COUNT];
+
+    for (index, value) in values.iter_mut().enumerate()
+    {
+        let argument = arguments.get(index).ok_or(Error::InvalidStatement(line))?;
+        *value = argument.parse().or(Err(Error::InvalidStatement(line)))?;
+    }
+
+    Ok(values)
+}
+
+fn parse_index(argument: Option<&str>, count: usize, line: usize) -> Result<Option<usize>, Error>
+{
+    // Indices start from one and negative ones are relative to the end of the list.
+    match argument
+    {
+        None | Some("") => Ok(None),
+        Some(argument) =>
+        {
+            let index: isize = argument.parse().or(Err(Error::InvalidStatement(line)))?;
+
+            #[allow(clippy::cast_possible_wrap)]
+            let resolved = if index < 0 { count as isize + index } else { index - 1 };
+
+            if resolved < 0 || resolved.unsigned_abs() >= count
+            {
+                return Err(Error::InvalidIndex(line));
+            }
+
+            Ok(Some(resolved.unsigned_abs()))
+        }
+    }
+}
+
+fn y_up_to_z_up(vector: Vec3) -> Vec3
+{
+    // Wavefront files are conventionally Y up, while this renderer is Z up.
+    Vec3::new(vector.get_x(), -vector.get_z(), vector.get_y())
+}
+
+fn parse_materials<R: BufRead>(reader: R) -> Result<HashMap<String, MaterialDescription>, Error>
+{
+    let mut materials = HashMap::new();
+    let mut current: Option<(String, MaterialDescription)> = None;
+
+    for (line_index, line) in reader.lines().enumerate()
+    {
+        let line = line.or(Err(Error::ReadingFile))?;
+        let line_number = line_index + 1;
+        let arguments: Vec<&str> = line.split_whitespace().collect();
+
+        let (statement, arguments) = match arguments.split_first()
+        {
+            Some((statement, _)) if statement.starts_with('#') => continue,
+            Some((statement, arguments)) => (*statement, arguments),
+            None => continue
+        };
+
+        if statement == "newmtl"
+        {
+            if let Some((name, description)) = current.take()
+            {
+                materials.insert(name, description);
+            }
+
+            let name = arguments.join(" ");
+            current = Some((name, MaterialDescription::default()));
+            continue;
+        }
+
+        let Some((_, description)) = &mut current else
+        {
+            continue;
+        };
+
+        match statement
+        {
+            "Kd" =>
+            {
+                let [r, g, b] = parse_floats::<3>(arguments, line_number)?;
+                description.diffuse = Vec4::new(r, g, b, 1.0).clamped();
+            },
+            "Ks" =>
+            {
+                let [r, g, b] = parse_floats::<3>(arguments, line_number)?;
+                description.specular = Vec4::new(r, g, b, 1.0).clamped();
+            },
+            "Ns" => description.specular_exponent = parse_floats::<1>(arguments, line_number)?[0],
+            "Ni" => description.refractive_index = parse_floats::<1>(arguments, line_number)?[0],
+            "d" => description.dissolve = parse_floats::<1>(arguments, line_number)?[0],
+            "Tr" => description.dissolve = 1.0 - parse_floats::<1>(arguments, line_number)?[0],
+            _ => ()
+        }
+    }
+
+    if let Some((name, description)) = current.take()
+    {
+        materials.insert(name, description);
+    }
+
+    Ok(materials)
+}
+
+fn parse_meshes<R: BufRead>(reader: R, directory: &Path) -> Result<Vec<Mesh>, Error>
+{
+    let mut positions: Vec<Vec3> = Vec::new();
+    let mut normals: Vec<Vec3> = Vec::new();
+    let mut uvs: Vec<Vec2> = Vec::new();
+
+    // Faces are grouped into one mesh per material.
+    let mut materials: HashMap<String, MaterialDescription> = HashMap::new();
+    let mut builders: Vec<(String, MeshBuilder)> = Vec::new();
+    let mut current_builder: Option<usize> = None;
+    let mut current_material = String::new();
+
+    for (line_index, line) in reader.lines().enumerate()
+    {
+        let line = line.or(Err(Error::ReadingFile))?;
+        let line_number = line_index + 1;
+        let arguments: Vec<&str> = line.split_whitespace().collect();
+
+        let (statement, arguments) = match arguments.split_first()
+        {
+            Some((statement, _)) if statement.starts_with('#') => continue,
+            Some((statement, arguments)) => (*statement, arguments),
+            None => continue
+        };
+
+        match statement
+        {
+            "v" =>
+            {
+                let [x, y, z] = parse_floats::<3>(arguments, line_number)?;
+                positions.push(y_up_to_z_up(Vec3::new(x, y, z)));
+            },
+            "vn" =>
+            {
+                let [x, y, z] = parse_floats::<3>(arguments, line_number)?;
+                let normal = y_up_to_z_up(Vec3::new(x, y, z));
+                normals.push(if normal.is_zero() { normal } else { normal.normalized() });
+            },
+            "vt" =>
+            {
+                let [u, v] = parse_floats::<2>(arguments, line_number)?;
+                uvs.push(Vec2::new(u, v));
+            },
+            "mtllib" =>
+            {
+                let library_path = directory.join(arguments.join(" "));
+                let library_file = OpenOptions::new().read(true).open(library_path).or(Err(Error::OpeningFile))?;
+                materials.extend(parse_materials(BufReader::new(library_file))?);
+            },
+            "usemtl" =>
+            {
+                current_material = arguments.join(" ");
+                current_builder = None;
+            },
+            "f" =>
+            {
+                if arguments.len() < 3
+                {
+                    return Err(Error::InvalidStatement(line_number));
+                }
+
+                let builder_index = if let Some(index) = current_builder
+                {
+                    index
+                }
+                else
+                {
+                    let index = builders.iter().position(|(material, _)| *material == current_material).unwrap_or_else(||
+                    {
+                        builders.push((current_material.clone(), MeshBuilder::new()));
+                        builders.len() - 1
+                    });
+
+                    current_builder = Some(index);
+                    index
+                };
+
+                let builder = &mut builders[builder_index].1;
+                let mut face_indices = Vec::with_capacity(arguments.len());
+
+                for argument in arguments
+                {
+                    let mut parts = argument.split('/');
+
+                    let position = parse_index(parts.next(), positions.len(), line_number)?.ok_or(Error::InvalidStatement(line_number))?;
+                    let uv = parse_index(parts.next(), uvs.len(), line_number)?;
+                    let normal = parse_index(parts.next(), normals.len(), line_number)?;
+
+                    face_indices.push(builder.add_vertex((position, uv, normal), &positions, &uvs, &normals).ok_or(Error::InvalidIndex(line_number))?);
+                }
+
+                // Polygons are assumed to be convex and are split into a triangle fan.
+                for index in 1..face_indices.len() - 1
+                {
+                    builder.indices.extend(&[face_indices[0], face_indices[index], face_indices[index + 1]]);
+                }
+            },
+            _ => ()
+        }
+    }
+
+    builders.into_iter().map(|(material, builder)|
+    {
+        let description = materials.get(&material).copied().unwrap_or_default();
+        builder.build(description.as_material())
+    }).collect()
+}
+
+/// # Errors
+///
+/// Returns error when file or its material library cannot be read, or contains invalid data.
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Vec<Mesh>, Error>
+{
+    let path = path.as_ref();
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let file = OpenOptions::new().read(true).open(path).or(Err(Error::OpeningFile))?;
+    parse_meshes(BufReader::new(file), directory)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn parse_quad()
+    {
+        let source = "# Quad split into two triangles\n\
+            v -1.0 0.0 1.0\nv 1.0 0.0 1.0\nv 1.0 0.0 -1.0\nv -1.0 0.0 -1.0\n\
+            vt 0.0 0.0\nvt 1.0 0.0\nvt 1.0 1.0\nvt 0.0 1.0\n\
+            f 1/1 2/2 3/3 4/4\n";
+
+        let meshes = parse_meshes(source.as_bytes(), Path::new("")).unwrap();
+        assert_eq!(meshes.len(), 1);
+
+        let mesh = &meshes[0];
+        assert_eq!(mesh.positions.len(), 4);
+        assert_eq!(mesh.indices, vec![0, 1, 2, 0, 2, 3]);
+        assert_eq!(mesh.positions[0], Vec3::new(-1.0, -1.0, 0.0));
+        assert!(mesh.normals.is_none());
+        assert_eq!(mesh.uvs.as_ref().unwrap()[2], Vec2::new(1.0, 1.0));
+    }
+
+    #[test]
+    fn parse_bad_index()
+    {
+        let source = "v 0.0 0.0 0.0\nv 1.0 0.0 0.0\nf 1 2 3\n";
+        assert!(matches!(parse_meshes(source.as_bytes(), Path::new("")), Err(Error::InvalidIndex(3))));
+    }
+
+    #[test]
+    fn parse_materials()
+    {
+        let source = "newmtl Red\nKd 0.8 0.1 0.1\n\n\
+            newmtl Chrome\nKd 0.1 0.1 0.1\nKs 0.9 0.9 0.9\nNs 1000\n\n\
+            newmtl Glass\nKd 1.0 1.0 1.0\nNi 1.45\nd 0.1\n";
+
+        let materials = super::parse_materials(source.as_bytes()).unwrap();
+        assert_eq!(materials.len(), 3);
+
+        assert!(matches!(materials["Red"].as_material(), Material::Diffuse(_)));
+        assert!(matches!(materials["Chrome"].as_material(), Material::Metallic(_)));
+        assert!(matches!(materials["Glass"].as_material(), Material::Refractive(_)));
+    }
+}
@@ -1,10 +1,31 @@
 use serde::{ Serialize, Deserialize };
+use super::samplers::Sampler;
+use super::tiles::TileOrder;
+use super::aovs::Aov;
+use super::tonemapping::ToneMapping;
+use super::film::Filter;
+use super::denoiser::Denoiser;
+use super::integrators::IntegratorKind;
 
 #[derive(Serialize, Deserialize)]
-pub enum DebugMode
+pub struct AdaptiveSampling
 {
-    Diffuse,
-    Normals,
+    pub noise_threshold: f32,
+    pub min_samples: u16,
+    pub max_samples: u16
+}
+
+impl Default for AdaptiveSampling
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            noise_threshold: 0.01,
+            min_samples: 16,
+            max_samples: 1024
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -14,7 +35,50 @@ pub struct Parameters
     pub image_height: usize,
     pub antialias_samples: u16,
     pub scatter_limit: u16,
-    pub debug_mode: Option<DebugMode>
+
+    // Paths are terminated by Russian roulette once they scatter this many times.
+    #[serde(default = "default_roulette_depth")]
+    pub roulette_depth: u16,
+
+    #[serde(default)]
+    pub integrator: IntegratorKind,
+
+    #[serde(default)]
+    pub seed: u64,
+
+    #[serde(default)]
+    pub sampler: Sampler,
+
+    #[serde(default)]
+    pub adaptive_sampling: Option<AdaptiveSampling>,
+
+    #[serde(default = "default_tile_size")]
+    pub tile_size: usize,
+
+    #[serde(default)]
+    pub tile_order: TileOrder,
+
+    #[serde(default)]
+    pub filter: Filter,
+
+    #[serde(default)]
+    pub aovs: Vec<Aov>,
+
+    #[serde(default)]
+    pub tone_mapping: ToneMapping,
+
+    #[serde(default)]
+    pub denoiser: Option<Denoiser>
+}
+
+fn default_tile_size() -> usize
+{
+    32
+}
+
+fn default_roulette_depth() -> u16
+{
+    3
 }
 
 impl Default for Parameters
@@ -26,8 +90,107 @@ impl Default for Parameters
             image_width: 1024,
             image_height: 576,
             antialias_samples: 4,
-            scatter_limit: 8,
-            debug_mode: None
+            scatter_limit: 32,
+            roulette_depth: default_roulette_depth(),
+            integrator: IntegratorKind::default(),
+            seed: 0,
+            sampler: Sampler::default(),
+            adaptive_sampling: None,
+            tile_size: default_tile_size(),
+            tile_order: TileOrder::default(),
+            filter: Filter::default(),
+            aovs: Vec::new(),
+            tone_mapping: ToneMapping::default(),
+            denoiser: None
+        }
+    }
+}
+
+impl Parameters
+{
+    pub fn find_invalid_setting(&self) -> Option<&'static str>
+    {
+        // Settings come from setup files, so values that renderer cannot work with are reported instead of asserted.
+        if self.image_width == 0 || self.image_height == 0
+        {
+            return Some("Pixel count cannot be zero!");
         }
+
+        if self.tile_size == 0
+        {
+            return Some("Tile size cannot be zero!");
+        }
+
+        if !(0.5..=Filter::MAX_RADIUS).contains(&self.filter.radius())
+        {
+            return Some("Filter radius must be between half a pixel and sixteen pixels!");
+        }
+
+        if let Some(adaptive_sampling) = &self.adaptive_sampling
+        {
+            if adaptive_sampling.min_samples < 1
+            {
+                return Some("Minimum sample count must equal one or higher!");
+            }
+
+            if adaptive_sampling.min_samples > adaptive_sampling.max_samples
+            {
+                return Some("Minimum sample count cannot exceed maximum!");
+            }
+        }
+        else if self.antialias_samples < 1
+        {
+            return Some("Antialias samples must equal one or higher!");
+        }
+
+        if let IntegratorKind::AmbientOcclusion { distance } = self.integrator
+        {
+            if distance.is_nan() || distance <= 0.0
+            {
+                return Some("Ambient occlusion distance must be positive!");
+            }
+        }
+
+        None
+    }
+
+    pub fn rendered_aovs(&self) -> Vec<Aov>
+    {
+        // Denoiser is guided by output variables of the same render, which are added after requested ones.
+        let guides = self.denoiser.iter().flat_map(|_| Denoiser::GUIDES.iter());
+
+        self.aovs.iter().chain(guides).fold(Vec::new(), |mut aovs, aov|
+        {
+            if !aovs.contains(aov)
+            {
+                aovs.push(*aov);
+            }
+
+            aovs
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn invalid_settings()
+    {
+        assert!(Parameters::default().find_invalid_setting().is_none());
+
+        let empty_tiles = Parameters { tile_size: 0, ..Parameters::default() };
+        assert_eq!(empty_tiles.find_invalid_setting(), Some("Tile size cannot be zero!"));
+
+        // Radius sizes margin of every film tile, so values that are not finite or too large are rejected.
+        for radius in &[0.25, f32::NAN, f32::INFINITY, 1.0e9]
+        {
+            let filtered = Parameters { filter: Filter::Tent { radius: *radius }, ..Parameters::default() };
+            assert!(filtered.find_invalid_setting().is_some());
+        }
+
+        assert!(Parameters { filter: Filter::Lanczos { radius: 3.0 }, ..Parameters::default() }.find_invalid_setting().is_none());
     }
 }
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+use std::sync::atomic::{ AtomicBool, Ordering };
+use std::time::Duration;
+
+pub struct Progress
+{
+    pub pass_index: usize,
+    pub tiles_done: usize,
+    pub tile_count: usize,
+    pub elapsed: Duration
+}
+
+impl Progress
+{
+    pub fn fraction(&self) -> f32
+    {
+        self.tiles_done as f32 / self.tile_count as f32
+    }
+
+    pub fn estimated_remaining(&self) -> Option<Duration>
+    {
+        // Estimate assumes remaining tiles of the pass take as long as those already done.
+        if self.tiles_done == 0
+        {
+            return None;
+        }
+
+        let remaining_tiles = (self.tile_count - self.tiles_done) as f64;
+        Some(self.elapsed.mul_f64(remaining_tiles / self.tiles_done as f64))
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct CancellationToken
+{
+    is_cancelled: Arc<AtomicBool>
+}
+
+impl CancellationToken
+{
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    pub fn cancel(&self)
+    {
+        self.is_cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool
+    {
+        self.is_cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn estimate()
+    {
+        let progress = Progress
+        {
+            pass_index: 1,
+            tiles_done: 4,
+            tile_count: 10,
+            elapsed: Duration::from_secs(8)
+        };
+
+        assert!((progress.fraction() - 0.4).abs() < 0.0001);
+        assert_eq!(progress.estimated_remaining(), Some(Duration::from_secs(12)));
+    }
+
+    #[test]
+    fn cancel()
+    {
+        let token = CancellationToken::new();
+        let shared = token.clone();
+
+        assert!(!token.is_cancelled());
+        shared.cancel();
+        assert!(token.is_cancelled());
+    }
+}
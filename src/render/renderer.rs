@@ -1,12 +1,35 @@
-use rayon::prelude::*;
+use std::path::{ Path, PathBuf };
+use std::sync::Mutex;
+use std::sync::atomic::{ AtomicUsize, Ordering };
+use std::time::{ Duration, Instant };
 use super::math::Vec4;
-use super::math::Ray;
+use super::math::Intersection;
 use super::image;
 use super::parameters::Parameters;
-use super::parameters::DebugMode;
+use super::aovs::Aov;
 use super::statistics::Statistics;
 use super::scene::Scene;
-use super::materials;
+use super::setup;
+use super::camera;
+use super::integrators::{ Integrator, IntegratorKind, FirstHit };
+use super::samplers::PixelSampler;
+use super::tiles;
+use super::tiles::Tile;
+use super::film::{ Film, FilmTile, FilmPixel };
+use super::progress::Progress;
+use super::progress::CancellationToken;
+use super::checkpoint::Checkpoint;
+use super::checkpoint::PixelCheckpoint;
+
+#[derive(Debug)]
+pub enum Error
+{
+    InvalidSetup(setup::Error),
+    CheckpointMismatch,
+    InvalidTile
+}
+
+type ProgressCallback<'a> = Box<dyn Fn(&Progress) + Send + Sync + 'a>;
 
 pub struct Renderer<'a>
 {
@@ -14,9 +37,11 @@ pub struct Renderer<'a>
     scene: Option<&'a Scene>,
 
     thread_pool: rayon::ThreadPool,
-
-    debug_diffuse_material: materials::Material,
-    debug_normals_material: materials::Material
+    progress_callback: Option<ProgressCallback<'a>>,
+    cancellation_token: CancellationToken,
+    checkpoint_path: Option<PathBuf>,
+    checkpoint_interval: Duration,
+    resume_checkpoint: Option<&'a Checkpoint>
 }
 
 impl<'a> Default for Renderer<'a>
@@ -32,9 +57,11 @@ impl<'a> Default for Renderer<'a>
             scene: None,
 
             thread_pool,
-
-            debug_diffuse_material: materials::Diffuse::new(Vec4::new(0.5, 0.5, 0.5, 1.0)),
-            debug_normals_material: materials::Normals::new()
+            progress_callback: None,
+            cancellation_token: CancellationToken::new(),
+            checkpoint_path: None,
+            checkpoint_interval: Duration::from_mins(10),
+            resume_checkpoint: None
         }
     }
 }
@@ -58,15 +85,216 @@ impl<'a> Renderer<'a>
         self
     }
 
-    pub fn render(&self) -> image::Surface
+    /// # Panics
+    ///
+    /// Panics when thread pool cannot be created.
+    #[must_use]
+    pub fn set_thread_count(mut self, thread_count: usize) -> Self
+    {
+        // Zero lets thread pool pick count based on available cores.
+        self.thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(thread_count)
+            .build().expect("Failed to create a thread pool!");
+
+        self
+    }
+
+    #[must_use]
+    pub fn set_progress_callback<F>(mut self, callback: F) -> Self
+        where F: Fn(&Progress) + Send + Sync + 'a
+    {
+        // Callback is invoked from render threads after each finished tile.
+        self.progress_callback = Some(Box::new(callback));
+        self
+    }
+
+    #[must_use]
+    pub fn set_cancellation_token(mut self, cancellation_token: CancellationToken) -> Self
+    {
+        self.cancellation_token = cancellation_token;
+        self
+    }
+
+    #[must_use]
+    pub fn set_checkpoint<P: AsRef<Path>>(mut self, path: P, interval: Duration) -> Self
+    {
+        // Checkpoint is written after first pass that ends once interval has passed, and when render is cancelled.
+        self.checkpoint_path = Some(PathBuf::from(path.as_ref()));
+        self.checkpoint_interval = interval;
+        self
+    }
+
+    #[must_use]
+    pub fn set_resume_checkpoint(mut self, checkpoint: &'a Checkpoint) -> Self
+    {
+        self.resume_checkpoint = Some(checkpoint);
+        self
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when setup is invalid or resumed checkpoint does not match it.
+    pub fn render(&self) -> Result<image::Surface, Error>
+    {
+        // Regular rendering is a single pass that takes all samples at once.
+        self.render_progressive(usize::MAX, |_, _| {})
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when setup is invalid or tile lies outside of the image.
+    ///
+    /// # Panics
+    ///
+    /// Panics when parameters or scene have not been set.
+    pub fn render_tile(&self, tile: Tile) -> Result<FilmTile, Error>
+    {
+        // Renders all samples of single tile on calling thread, which lets distributed workers share the image.
+        // Returned film tile is merged the same way as in whole image, so tiles can be combined into identical result.
+        let parameters = self.parameters.expect("Cannot render image without parameters!");
+        let scene = self.scene.expect("Cannot render image without scene!");
+
+        setup::validate(parameters, scene).map_err(Error::InvalidSetup)?;
+
+        // Tiles can come from other machines, so they are checked to lie within the image.
+        let is_inside = |position: usize, size: usize, image_size: usize| size > 0 && position.checked_add(size).is_some_and(|end| end <= image_size);
+
+        if !is_inside(tile.x, tile.width, parameters.image_width) || !is_inside(tile.y, tile.height, parameters.image_height)
+        {
+            return Err(Error::InvalidTile);
+        }
+
+        let context = self.create_context(usize::MAX);
+
+        let mut tile_state = TileState::new(tile, parameters, &context);
+        self.render_tile_pass(&context, &mut tile_state, &mut Statistics::new());
+
+        // Heatmap replaces filtered colors of tile pixels, without reaching into neighbouring tiles.
+        if parameters.integrator == IntegratorKind::SampleCount
+        {
+            tile_state.film.clear();
+
+            for (index, state) in tile_state.pixels.iter().enumerate()
+            {
+                let (x, y) = tile.pixel_position(index);
+                tile_state.film.set_pixel(x, y, FilmPixel { color: heatmap_pixel(state, &context), weight: 1.0 });
+            }
+        }
+
+        Ok(tile_state.film)
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when setup is invalid or resumed checkpoint does not match it.
+    pub fn render_with_aovs(&self) -> Result<(image::Surface, Vec<image::Surface>), Error>
+    {
+        self.render_progressive_with_aovs(usize::MAX, |_, _| {})
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when setup is invalid or resumed checkpoint does not match it.
+    pub fn render_progressive<F>(&self, samples_per_pass: usize, callback: F) -> Result<image::Surface, Error>
+        where F: FnMut(usize, &image::Surface)
+    {
+        self.render_progressive_with_aovs(samples_per_pass, callback).map(|(image, _)| image)
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when setup is invalid or resumed checkpoint does not match it.
+    ///
+    /// # Panics
+    ///
+    /// Panics when parameters or scene have not been set.
+    pub fn render_progressive_with_aovs<F>(&self, samples_per_pass: usize, mut callback: F) -> Result<(image::Surface, Vec<image::Surface>), Error>
+        where F: FnMut(usize, &image::Surface)
     {
         // Start measuring render time.
-        let begin_time = std::time::Instant::now();
+        let begin_time = Instant::now();
 
         // Acquire required parameters.
         let parameters = self.parameters.expect("Cannot render image without parameters!");
         let scene = self.scene.expect("Cannot render image without scene!");
 
+        // Scenes built in code are not checked by setup loading, so they are checked here before any thread starts.
+        setup::validate(parameters, scene).map_err(Error::InvalidSetup)?;
+
+        let context = self.create_context(samples_per_pass);
+
+        // Resumed render must use exactly the same setup, otherwise accumulated samples would not match the image.
+        if self.resume_checkpoint.is_some_and(|checkpoint| !checkpoint.matches(parameters, scene))
+        {
+            return Err(Error::CheckpointMismatch);
+        }
+
+        // Split image into tiles that keep state of their pixels between passes.
+        // Each pixel has its own sampler state, so result does not depend on which thread renders it or how many passes are used.
+        let tiles: Vec<Mutex<TileState>> = tiles::generate(parameters.image_width, parameters.image_height, parameters.tile_size, parameters.tile_order)
+            .into_iter().enumerate().map(|(index, tile)|
+            {
+                let mut tile_state = TileState::new(tile, parameters, &context);
+
+                if let Some(checkpoint) = self.resume_checkpoint
+                {
+                    tile_state.restore(checkpoint, index, parameters.image_width, &context.aovs)?;
+                }
+
+                Ok(Mutex::new(tile_state))
+            }).collect::<Result<_, Error>>()?;
+
+        let mut accumulated_stats = Statistics::new();
+        let mut pass_index = self.resume_checkpoint.map_or(0, |checkpoint| checkpoint.pass_count);
+        let mut checkpoint_time = Instant::now();
+
+        loop
+        {
+            pass_index += 1;
+
+            accumulated_stats = accumulated_stats.accumulated(&self.render_pass(&context, &tiles, pass_index));
+
+            let (image, sample_counts) = self.resolve(&tiles, &context);
+            callback(pass_index, &image);
+
+            // Cancelled render returns partial image, with pixels that have not been sampled yet left black.
+            let is_cancelled = self.cancellation_token.is_cancelled();
+            let is_finished = tiles.iter().all(|tile_state|
+            {
+                tile_state.lock().expect("Tile state has been poisoned!").pixels.iter().all(|state| state.is_finished)
+            });
+
+            if self.checkpoint_path.is_some() && (is_cancelled || checkpoint_time.elapsed() >= self.checkpoint_interval)
+            {
+                self.save_checkpoint(&tiles, pass_index);
+                checkpoint_time = Instant::now();
+            }
+
+            if is_finished || is_cancelled
+            {
+                accumulated_stats.set_region_samples(&sample_counts, parameters.image_width, parameters.image_height);
+
+                // Pixels restored from checkpoint may not be sampled again, but are still part of rendered image.
+                if self.resume_checkpoint.is_some()
+                {
+                    accumulated_stats.pixels = sample_counts.iter().filter(|count| **count > 0).count();
+                }
+
+                // Print render statistics.
+                println!("Rendered image in {} seconds.", begin_time.elapsed().as_secs_f32());
+                accumulated_stats.print();
+
+                // Return image with rendered pixel data, along with output variables listed by parameters.
+                return Ok((image, self.resolve_aovs(&tiles, &context)));
+            }
+        }
+    }
+
+    fn create_context(&self, samples_per_pass: usize) -> RenderContext
+    {
+        let parameters = self.parameters.expect("Cannot render image without parameters!");
+        let scene = self.scene.expect("Cannot render image without scene!");
+
         // Build camera that will be used to calculate initial raycasts.
         let camera = scene.camera.build(parameters.image_width as f32 / parameters.image_height as f32)
             .expect("Failed to build compiled camera structure!");
@@ -74,156 +302,489 @@ impl<'a> Renderer<'a>
         // Calculate image constants.
         let image_width_inv = 1.0 / parameters.image_width as f32;
         let image_height_inv = 1.0 / parameters.image_height as f32;
-        let image_pixel_count = parameters.image_width * parameters.image_height;
-        assert!(image_pixel_count > 0, "Pixel count cannot be zero!");
+        assert!(parameters.image_width * parameters.image_height > 0, "Pixel count cannot be zero!");
+        assert!(samples_per_pass >= 1, "Samples per pass must equal one or higher!");
+        assert!(parameters.filter.radius() >= 0.5, "Filter radius must equal half a pixel or higher!");
+
+        // Calculate range of subpixel samples, with their positions provided by sampler.
+        // Adaptive sampling keeps adding samples to each pixel until its estimated noise falls below threshold.
+        let (min_subpixel_count, max_subpixel_count, noise_threshold) = if let Some(adaptive_sampling) = &parameters.adaptive_sampling
+        {
+            (usize::from(adaptive_sampling.min_samples), usize::from(adaptive_sampling.max_samples), Some(adaptive_sampling.noise_threshold))
+        }
+        else
+        {
+            assert!(parameters.antialias_samples >= 1, "Antialias samples must equal one or higher!");
+            let antialias_subpixel_count = usize::from(parameters.antialias_samples).pow(2);
+            (antialias_subpixel_count, antialias_subpixel_count, None)
+        };
 
-        // Create antialiasing kernel.
-        assert!(parameters.antialias_samples >= 1, "Antialias samples must equal one or higher!");
-        let antialias_subpixel_count = parameters.antialias_samples.pow(2) as usize;
-        let antialias_subpixel_step = 1.0 / f32::from(parameters.antialias_samples);
+        assert!(min_subpixel_count >= 1, "Minimum sample count must equal one or higher!");
+        assert!(min_subpixel_count <= max_subpixel_count, "Minimum sample count cannot exceed maximum!");
 
-        let antialias_kernel: Vec<(f32, f32)> = 
+        RenderContext
         {
-            let mut kernel = Vec::with_capacity(antialias_subpixel_count);
+            camera,
+            image_width_inv,
+            image_height_inv,
+            samples_per_pass,
+            min_subpixel_count,
+            max_subpixel_count,
+            noise_threshold,
+            aovs: parameters.rendered_aovs(),
+            integrator: parameters.integrator.build(parameters)
+        }
+    }
+
+    fn render_pass(&self, context: &RenderContext, tiles: &[Mutex<TileState>], pass_index: usize) -> Statistics
+    {
+        let pass_begin_time = Instant::now();
+        let next_tile_index = AtomicUsize::new(0);
+        let tiles_done = AtomicUsize::new(0);
+        let rendered_stats = Mutex::new(Statistics::new());
+
+        // Render tiles in parallel and collect stats.
+        // Threads take tiles from shared counter, so they are started in requested order.
+        self.thread_pool.scope(|scope|
+        {
+            for _ in 0..self.thread_pool.current_num_threads()
+            {
+                scope.spawn(|_|
+                {
+                    let mut thread_stats = Statistics::new();
+    
+                    while !self.cancellation_token.is_cancelled()
+                    {
+                        let Some(tile_state) = tiles.get(next_tile_index.fetch_add(1, Ordering::Relaxed)) else
+                        {
+                            break;
+                        };
+    
+                        self.render_tile_pass(context, &mut tile_state.lock().expect("Tile state has been poisoned!"), &mut thread_stats);
+    
+                        if let Some(progress_callback) = &self.progress_callback
+                        {
+                            progress_callback(&Progress
+                            {
+                                pass_index,
+                                tiles_done: tiles_done.fetch_add(1, Ordering::Relaxed) + 1,
+                                tile_count: tiles.len(),
+                                elapsed: pass_begin_time.elapsed()
+                            });
+                        }
+                    }
+    
+                    let mut rendered_stats = rendered_stats.lock().expect("Statistics have been poisoned!");
+                    *rendered_stats = rendered_stats.accumulated(&thread_stats);
+                });
+            }
+        });
+
+        rendered_stats.into_inner().expect("Statistics have been poisoned!")
+    }
+
+    fn render_tile_pass(&self, context: &RenderContext, tile_state: &mut TileState, stats: &mut Statistics)
+    {
+        let scene = self.scene.expect("Cannot render image without scene!");
+        let TileState { tile, pixels, film } = tile_state;
+
+        // Samples are taken in rounds over all unfinished pixels, so wide filters splat them into shared pixels
+        // in the same order regardless of how samples are split into passes.
+        for _ in 0..context.samples_per_pass
+        {
+            let mut is_sampled = false;
 
-            for subpixel_x in 0..parameters.antialias_samples
+            for (index, state) in pixels.iter_mut().enumerate().filter(|(_, state)| !state.is_finished)
             {
-                for subpixel_y in 0..parameters.antialias_samples
+                if state.convergence.count >= context.max_subpixel_count
                 {
-                    let offset_u = f32::from(subpixel_x) * antialias_subpixel_step;
-                    let offset_v = f32::from(subpixel_y) * antialias_subpixel_step;
+                    state.is_finished = true;
+                    continue;
+                }
 
-                    kernel.push((offset_u, offset_v));
+                if state.convergence.count == 0
+                {
+                    stats.pixels += 1;
                 }
-            }
 
-            kernel
-        };
+                let (x, y) = tile.pixel_position(index);
 
-        debug_assert!(antialias_kernel.len() == antialias_subpixel_count);
+                let sampler = &mut state.sampler;
+                sampler.begin_sample(state.convergence.count);
+                let offset = sampler.next_2d();
 
-        // Render pixels in parallel and collect stats.
-        let mut image_pixels: Vec<Vec4> = Vec::with_capacity(image_pixel_count);
-        image_pixels.resize(image_pixel_count, Vec4::new(0.0, 0.0, 0.0, 0.0));
+                let u = (x as f32 + offset.x) * context.image_width_inv;
+                let v = (y as f32 + offset.y) * context.image_height_inv;
+                
+                let ray = context.camera.calculate_ray(u, v, sampler);
 
-        /* chunk rendering
-        let per_thread_chunk_size = 1; //image_pixel_count / rayon::current_num_threads();
-        let accumulated_stats: Statistics = image_pixels.par_chunk_mut(per_thread_chunk_size).enumerate().map(|(chunk_index, chunk)|
-        */
+                let mut first_hit = None;
+                let mut sample = context.integrator.sample(scene, ray, sampler, stats, &mut first_hit);
 
-        let accumulated_stats: Statistics = self.thread_pool.install(||
-        {
-            image_pixels.par_iter_mut().enumerate().map(|(pixel_index, pixel)|
-            {
-                /* chunk rendering
-                let mut chunk_stats = Statistics::new();
-                chunk.iter_mut().enumerate().for_each(|(pixel_index, pixel)|
+                // Every camera sample covers its pixel completely, whatever alpha radiance math leaves behind.
+                sample.set_a(1.0);
+                debug_assert!(sample.is_finite());
+
+                if !state.aovs.is_empty()
                 {
-                */
+                    self.sample_aovs(context, first_hit.as_ref(), state.convergence.count == 0, &mut state.aovs);
+                }
 
-                    let mut pixel_stats = Statistics::new_pixel();
+                film.add_sample(x, y, offset, sample);
+                state.convergence.add(sample.luminance());
+                stats.subpixels += 1;
+                is_sampled = true;
 
-                    let x = (/* per_thread_chunk_size * chunk_index + */ pixel_index) % parameters.image_width as usize;
-                    let y = (/* per_thread_chunk_size * chunk_index + */ pixel_index) / parameters.image_width as usize;
+                let is_converged = context.noise_threshold.is_some_and(|noise_threshold|
+                {
+                    state.convergence.count >= context.min_subpixel_count && state.convergence.is_converged(noise_threshold)
+                });
 
-                    let mut accumulated_color = Vec4::new(0.0, 0.0, 0.0, 0.0);
+                if is_converged || state.convergence.count >= context.max_subpixel_count
+                {
+                    state.is_finished = true;
+                }
+            }
 
-                    for (offset_u, offset_v) in &antialias_kernel
-                    {
-                        let u = (x as f32 + offset_u) * image_width_inv as f32;
-                        let v = (y as f32 + offset_v) * image_height_inv as f32;
-                        
-                        let sample = self.sample(camera.calculate_ray(u, v), 0, &mut pixel_stats);
-                        debug_assert!(sample.is_valid());
+            if !is_sampled
+            {
+                break;
+            }
+        }
+    }
 
-                        accumulated_color += sample;
-                    }
+    fn save_checkpoint(&self, tiles: &[Mutex<TileState>], pass_count: usize)
+    {
+        let parameters = self.parameters.expect("Cannot render image without parameters!");
+        let scene = self.scene.expect("Cannot render image without scene!");
+        let checkpoint_path = self.checkpoint_path.as_ref().expect("Cannot save checkpoint without path!");
+
+        let mut pixels: Vec<Option<PixelCheckpoint>> = (0..parameters.image_width * parameters.image_height).map(|_| None).collect();
+        let mut film_tiles = Vec::with_capacity(tiles.len());
+
+        for tile_state in tiles
+        {
+            let tile_state = tile_state.lock().expect("Tile state has been poisoned!");
+            film_tiles.push(tile_state.film.pixels.clone());
 
-                    *pixel = accumulated_color / antialias_subpixel_count as f32;
-                    
-                    pixel_stats.subpixels += antialias_subpixel_count;
+            for (index, state) in tile_state.pixels.iter().enumerate()
+            {
+                let (x, y) = tile_state.tile.pixel_position(index);
 
-                /* chunk rendering
-                    chunk_stats = chunk_stats.accumulated(&pixel_stats);
+                pixels[y * parameters.image_width + x] = Some(PixelCheckpoint
+                {
+                    aovs: state.aovs.clone(),
+                    sample_count: state.convergence.count,
+                    mean: state.convergence.mean,
+                    squared_deviations: state.convergence.squared_deviations,
+                    is_finished: state.is_finished,
+                    random: state.sampler.get_random()
                 });
+            }
+        }
 
-                chunk_stats
-                */
+        let checkpoint = Checkpoint
+        {
+            setup_hash: setup::hash(parameters, scene),
+            image_width: parameters.image_width,
+            image_height: parameters.image_height,
+            pass_count,
+            aovs: parameters.rendered_aovs(),
+            pixels: pixels.into_iter().map(|pixel| pixel.expect("Tiles do not cover whole image!")).collect(),
+            film_tiles
+        };
 
-                pixel_stats
-            }).sum()
-        });
+        // Failing to write checkpoint should not stop the render itself.
+        println!("Writing checkpoint after pass {pass_count}.");
+
+        if let Err(error) = checkpoint.save(checkpoint_path)
+        {
+            println!("Checkpoint saving error: {error}");
+        }
+    }
+
+    fn resolve(&self, tiles: &[Mutex<TileState>], context: &RenderContext) -> (image::Surface, Vec<usize>)
+    {
+        let parameters = self.parameters.expect("Cannot render image without parameters!");
 
-        // Perform gamma correction on color values.
-        let gamma_correction = 1.0 / 2.2;
+        // Tile films overlap where filter reaches across their edges, and are merged in fixed order to keep result deterministic.
+        let mut film = Film::new(parameters.image_width, parameters.image_height);
+        let mut heatmap_pixels = Vec::new();
+        let mut sample_counts = vec![0; parameters.image_width * parameters.image_height];
 
-        image_pixels.par_iter_mut().for_each(|pixel|
+        for tile_state in tiles
         {
-            pixel.set_r(pixel.get_r().powf(gamma_correction));
-            pixel.set_g(pixel.get_g().powf(gamma_correction));
-            pixel.set_b(pixel.get_b().powf(gamma_correction));
-            
-            debug_assert!(pixel.is_valid());
-            debug_assert!((pixel.get_a() - 1.0).abs() < std::f32::EPSILON);
-        });
+            let tile_state = tile_state.lock().expect("Tile state has been poisoned!");
+            film.merge(&tile_state.film);
+
+            for (index, state) in tile_state.pixels.iter().enumerate().filter(|(_, state)| state.convergence.count > 0)
+            {
+                let (x, y) = tile_state.tile.pixel_position(index);
+                sample_counts[y * parameters.image_width + x] = state.convergence.count;
+
+                if parameters.integrator == IntegratorKind::SampleCount
+                {
+                    heatmap_pixels.push((x, y, heatmap_pixel(state, context)));
+                }
+            }
+        }
 
-        // Print render statistics.
-        println!("Rendered image in {} seconds.", begin_time.elapsed().as_secs_f32());
-        accumulated_stats.print();
+        // Averaged color values are kept linear, with their full range left for tone mapping.
+        let mut image = film.resolve();
+
+        // Replace image with heatmap of sample counts.
+        for (x, y, color) in heatmap_pixels
+        {
+            image.set_pixel(x, y, color);
+        }
 
-        // Return image with rendered pixel data.
-        image::Surface::from(parameters.image_width, parameters.image_height, image_pixels)
+        (image, sample_counts)
     }
 
-    fn sample(&self, ray: Ray, scatter_index: u16, stats: &mut Statistics) -> Vec4
+    fn resolve_aovs(&self, tiles: &[Mutex<TileState>], context: &RenderContext) -> Vec<image::Surface>
     {
         let parameters = self.parameters.expect("Cannot render image without parameters!");
-        let scene = self.scene.expect("Cannot render image without scene!");
 
-        if scatter_index > parameters.scatter_limit
+        let image_pixel_count = parameters.image_width * parameters.image_height;
+        let mut layers = vec![vec![Vec4::zero(); image_pixel_count]; context.aovs.len()];
+
+        for tile_state in tiles
         {
-            return Vec4::black();
+            let tile_state = tile_state.lock().expect("Tile state has been poisoned!");
+
+            for (index, state) in tile_state.pixels.iter().enumerate().filter(|(_, state)| state.convergence.count > 0)
+            {
+                let (x, y) = tile_state.tile.pixel_position(index);
+                let pixel_index = y * parameters.image_width + x;
+
+                for ((aov, value), layer) in context.aovs.iter().zip(&state.aovs).zip(&mut layers)
+                {
+                    layer[pixel_index] = match aov
+                    {
+                        Aov::SampleCount => scalar(state.convergence.count as f32),
+                        _ if aov.is_averaged() => *value / state.convergence.count as f32,
+                        _ => *value
+                    };
+                }
+            }
         }
 
-        stats.samples += 1;
-        
-        if let Some((intersection, object)) = scene.intersect(&ray, 0.0001, std::f32::MAX)
+        layers.into_iter().map(|layer| image::Surface::from(parameters.image_width, parameters.image_height, layer)).collect()
+    }
+
+    fn sample_aovs(&self, context: &RenderContext, first_hit: Option<&FirstHit>, is_first_sample: bool, values: &mut [Vec4])
+    {
+        let scene = self.scene.expect("Cannot render image without scene!");
+
+        for (aov, value) in context.aovs.iter().zip(values)
         {
-            stats.intersections += 1;
-            
-            let material = match parameters.debug_mode
+            if !aov.is_averaged() && !is_first_sample
             {
-                None => object.get_material(),
-                Some(DebugMode::Diffuse) => &self.debug_diffuse_material,
-                Some(DebugMode::Normals) => &self.debug_normals_material
-            };
-            
-            let (scattered_ray, attenuation) = material.scatter(&ray, &intersection, scatter_index);
+                continue;
+            }
 
-            if let Some(scattered_ray) = scattered_ray
+            // Rays that miss the scene leave averaged variables at zero, so their alpha holds pixel coverage.
+            let sample = match (aov, first_hit)
             {
-                stats.scatters += 1;
-                stats.max_scatters = std::cmp::max(stats.max_scatters, scatter_index);
+                (Aov::Depth, Some(hit)) => scalar(context.camera.depth(hit.intersection.point)),
+                (Aov::Depth, None) => scalar(f32::INFINITY),
+                (Aov::Normal, Some(hit)) => Vec4::new(hit.intersection.normal.get_x(), hit.intersection.normal.get_y(), hit.intersection.normal.get_z(), 1.0),
+                (Aov::Albedo, Some(hit)) => hit.material.albedo(&hit.intersection),
+                (Aov::ObjectIndex, Some(hit)) => scalar(hit.object_index as f32),
+                (Aov::MaterialIndex, Some(hit)) => scalar(scene.material_index(hit.object_index, hit.material) as f32),
+                (Aov::ObjectIndex | Aov::MaterialIndex, None) => scalar(-1.0),
+                (Aov::MotionVector, Some(hit)) => self.motion_vector(context, &hit.intersection, hit.object_index),
+                _ => Vec4::zero()
+            };
 
-                self.sample(scattered_ray, scatter_index + 1, stats) * attenuation
+            if aov.is_averaged()
+            {
+                *value += sample;
             }
             else
             {
-                attenuation
+                *value = sample;
             }
         }
-        else
+    }
+
+    fn motion_vector(&self, context: &RenderContext, intersection: &Intersection, object_index: usize) -> Vec4
+    {
+        // Movement of the point across image in pixels per unit of time, based on velocity of the object.
+        let parameters = self.parameters.expect("Cannot render image without parameters!");
+        let scene = self.scene.expect("Cannot render image without scene!");
+
+        let velocity = scene.objects()[object_index].velocity();
+
+        if velocity.is_zero()
+        {
+            return Vec4::new(0.0, 0.0, 0.0, 1.0);
+        }
+
+        match (context.camera.project(intersection.point), context.camera.project(intersection.point + velocity))
         {
-            match parameters.debug_mode
+            (Some(position), Some(moved_position)) =>
             {
-                None =>
-                {
-                    let alpha = (ray.direction().get_z() + 1.0) * 0.5;
-                    Vec4::new(1.0, 1.0, 1.0, 1.0) * (1.0 - alpha) + Vec4::new(0.5, 0.7, 1.0, 1.0) * alpha
-                },
-                Some(DebugMode::Diffuse) => Vec4::new(0.5, 0.5, 0.5, 1.0),
-                Some(DebugMode::Normals) => Vec4::new(0.5, 0.0, 0.5, 1.0)
+                let motion = moved_position - position;
+                Vec4::new(motion.x * parameters.image_width as f32, motion.y * parameters.image_height as f32, 0.0, 1.0)
+            },
+            _ => Vec4::new(0.0, 0.0, 0.0, 1.0)
+        }
+    }
+}
+
+fn scalar(value: f32) -> Vec4
+{
+    Vec4::new(value, value, value, 1.0)
+}
+
+fn heatmap_pixel(state: &PixelState, context: &RenderContext) -> Vec4
+{
+    // Sample counts are shown relative to their allowed range.
+    let sample_count_range = (context.max_subpixel_count - context.min_subpixel_count).max(1) as f32;
+    heatmap(state.convergence.count.saturating_sub(context.min_subpixel_count) as f32 / sample_count_range)
+}
+
+#[allow(clippy::cast_sign_loss)]
+#[allow(clippy::cast_possible_truncation)]
+fn heatmap(value: f32) -> Vec4
+{
+    // Maps value between zero and one onto gradient going from blue through green to red.
+    let colors =
+    [
+        Vec4::new(0.0, 0.0, 1.0, 1.0),
+        Vec4::new(0.0, 1.0, 1.0, 1.0),
+        Vec4::new(0.0, 1.0, 0.0, 1.0),
+        Vec4::new(1.0, 1.0, 0.0, 1.0),
+        Vec4::new(1.0, 0.0, 0.0, 1.0)
+    ];
+
+    let position = value.clamp(0.0, 1.0) * (colors.len() - 1) as f32;
+    let index = (position.floor() as usize).min(colors.len() - 2);
+    let alpha = position - index as f32;
+
+    colors[index] * (1.0 - alpha) + colors[index + 1] * alpha
+}
+
+struct RenderContext
+{
+    camera: camera::Compiled,
+    image_width_inv: f32,
+    image_height_inv: f32,
+    samples_per_pass: usize,
+    min_subpixel_count: usize,
+    max_subpixel_count: usize,
+    noise_threshold: Option<f32>,
+    aovs: Vec<Aov>,
+    integrator: Box<dyn Integrator>
+}
+
+struct TileState
+{
+    tile: Tile,
+    pixels: Vec<PixelState>,
+    film: FilmTile
+}
+
+impl TileState
+{
+    fn new(tile: Tile, parameters: &Parameters, context: &RenderContext) -> Self
+    {
+        let pixels = (0..tile.pixel_count()).map(|index|
+        {
+            let (x, y) = tile.pixel_position(index);
+
+            PixelState
+            {
+                sampler: parameters.sampler.begin_pixel(x, y, parameters.seed, context.min_subpixel_count),
+                aovs: vec![Vec4::zero(); context.aovs.len()],
+                convergence: Convergence::default(),
+                is_finished: false
             }
+        }).collect();
+
+        Self
+        {
+            tile,
+            pixels,
+            film: FilmTile::new(&tile, parameters.filter, parameters.image_width, parameters.image_height)
+        }
+    }
+
+    fn restore(&mut self, checkpoint: &Checkpoint, tile_index: usize, image_width: usize, aovs: &[Aov]) -> Result<(), Error>
+    {
+        // Restore accumulated samples and random state of pixels, along with splatted colors of tile.
+        for (index, state) in self.pixels.iter_mut().enumerate()
+        {
+            let (x, y) = self.tile.pixel_position(index);
+            let pixel = &checkpoint.pixels[y * image_width + x];
+
+            state.sampler.set_random(pixel.random);
+            // Checkpoint can store more output variables than needed, which are picked by their kind.
+            state.aovs = aovs.iter().map(|aov|
+            {
+                checkpoint.aovs.iter().position(|other| other == aov).and_then(|position| pixel.aovs.get(position)).copied()
+                    .ok_or(Error::CheckpointMismatch)
+            }).collect::<Result<_, Error>>()?;
+
+            state.convergence = Convergence
+            {
+                count: pixel.sample_count,
+                mean: pixel.mean,
+                squared_deviations: pixel.squared_deviations
+            };
+            state.is_finished = pixel.is_finished;
+        }
+
+        let Some(film_pixels) = checkpoint.film_tiles.get(tile_index).filter(|film_pixels| film_pixels.len() == self.film.pixels.len()) else
+        {
+            return Err(Error::CheckpointMismatch);
+        };
+
+        self.film.pixels.clone_from(film_pixels);
+        Ok(())
+    }
+}
+
+struct PixelState
+{
+    sampler: PixelSampler,
+    aovs: Vec<Vec4>,
+    convergence: Convergence,
+    is_finished: bool
+}
+
+#[derive(Default)]
+struct Convergence
+{
+    count: usize,
+    mean: f32,
+    squared_deviations: f32
+}
+
+impl Convergence
+{
+    fn add(&mut self, value: f32)
+    {
+        // Running mean and variance, see: https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance#Welford's_online_algorithm
+        self.count += 1;
+
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f32;
+        self.squared_deviations += delta * (value - self.mean);
+    }
+
+    fn is_converged(&self, noise_threshold: f32) -> bool
+    {
+        // Compares standard error of the mean relative to its value, which is clamped so very dark pixels can converge.
+        if self.count < 2
+        {
+            return false;
         }
+
+        let variance = self.squared_deviations / (self.count - 1) as f32;
+        let standard_error = (variance / self.count as f32).sqrt();
+
+        standard_error <= noise_threshold * self.mean.max(0.01)
     }
 }
@@ -0,0 +1,186 @@
+use std::sync::OnceLock;
+use super::math::Vec2;
+use super::math::Random;
+use super::{ hash_combine, shifted, sobol };
+
+// Blue noise dithered sampling, where all pixels share one sequence offset by values from blue noise texture.
+// Remaining error is then distributed as high frequency noise, which is less visible at low sample counts.
+// Implementation based on: https://dl.acm.org/doi/10.1145/2897839.2927430
+
+const SIZE: usize = 64;
+
+static TEXTURE: OnceLock<Vec<u16>> = OnceLock::new();
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn generate() -> Vec<u16>
+{
+    // Void and cluster method, see: https://cv.ulichney.com/papers/1993-void-cluster.pdf
+    let count = SIZE * SIZE;
+    let sigma: f32 = 1.5;
+
+    // Gaussian energy of each offset on a torus, so texture tiles seamlessly.
+    let kernel: Vec<f32> = (0..count).map(|index|
+    {
+        let distance = |offset: usize| offset.min(SIZE - offset) as f32;
+        let distance_x = distance(index % SIZE);
+        let distance_y = distance(index / SIZE);
+
+        (-(distance_x * distance_x + distance_y * distance_y) / (2.0 * sigma * sigma)).exp()
+    }).collect();
+
+    let mut pattern = vec![false; count];
+    let mut energy = vec![0.0_f32; count];
+
+    let toggle = |pattern: &mut Vec<bool>, energy: &mut Vec<f32>, index: usize|
+    {
+        pattern[index] = !pattern[index];
+        let sign = if pattern[index] { 1.0 } else { -1.0 };
+
+        for (other, value) in energy.iter_mut().enumerate()
+        {
+            let offset_x = (other % SIZE + SIZE - index % SIZE) % SIZE;
+            let offset_y = (other / SIZE + SIZE - index / SIZE) % SIZE;
+            *value += sign * kernel[offset_y * SIZE + offset_x];
+        }
+    };
+
+    // Tightest cluster is the set pixel with highest energy, largest void is the empty pixel with lowest.
+    let find = |pattern: &[bool], energy: &[f32], set: bool, highest: bool| -> usize
+    {
+        let candidates = (0..count).filter(|index| pattern[*index] == set);
+
+        if highest
+        {
+            candidates.max_by(|a, b| energy[*a].partial_cmp(&energy[*b]).unwrap_or(std::cmp::Ordering::Equal))
+        }
+        else
+        {
+            candidates.min_by(|a, b| energy[*a].partial_cmp(&energy[*b]).unwrap_or(std::cmp::Ordering::Equal))
+        }.expect("Pattern cannot be empty or full!")
+    };
+
+    // Start with random pattern covering tenth of pixels and spread it out evenly.
+    let mut random = Random::new(0, 0);
+    let initial_count = count / 10;
+    let mut placed = 0;
+
+    while placed < initial_count
+    {
+        let index = random.next_u32() as usize % count;
+
+        if !pattern[index]
+        {
+            toggle(&mut pattern, &mut energy, index);
+            placed += 1;
+        }
+    }
+
+    for _ in 0..count
+    {
+        let cluster = find(&pattern, &energy, true, true);
+        toggle(&mut pattern, &mut energy, cluster);
+
+        let void = find(&pattern, &energy, false, false);
+        toggle(&mut pattern, &mut energy, void);
+
+        if void == cluster
+        {
+            break;
+        }
+    }
+
+    let mut ranks = vec![0_u16; count];
+
+    // Ranks below initial count are assigned by removing tightest clusters from a copy of the pattern.
+    {
+        let mut pattern = pattern.clone();
+        let mut energy = energy.clone();
+
+        for rank in (0..initial_count).rev()
+        {
+            let cluster = find(&pattern, &energy, true, true);
+            toggle(&mut pattern, &mut energy, cluster);
+            ranks[cluster] = rank as u16;
+        }
+    }
+
+    // Remaining ranks are assigned by filling largest voids, which simplifies the original third phase.
+    for rank in initial_count..count
+    {
+        let void = find(&pattern, &energy, false, false);
+        toggle(&mut pattern, &mut energy, void);
+        ranks[void] = rank as u16;
+    }
+
+    ranks
+}
+
+pub fn texture() -> &'static [u16]
+{
+    TEXTURE.get_or_init(generate)
+}
+
+fn noise(x: u32, y: u32, seed: u32, channel: u32) -> f32
+{
+    // Each channel reads texture at different offset, so values of different dimensions are not correlated.
+    let offset = hash_combine(seed, channel);
+    let texture_x = (x.wrapping_add(offset) as usize) % SIZE;
+    let texture_y = (y.wrapping_add(offset >> 16) as usize) % SIZE;
+
+    (f32::from(texture()[texture_y * SIZE + texture_x]) + 0.5) / (SIZE * SIZE) as f32
+}
+
+pub fn sample_1d(x: u32, y: u32, seed: u32, index: u32, dimension: u32) -> f32
+{
+    shifted(sobol::sample_1d(seed, index, dimension), noise(x, y, seed, dimension))
+}
+
+pub fn sample_2d(x: u32, y: u32, seed: u32, index: u32, dimension: u32) -> Vec2
+{
+    let value = sobol::sample_2d(seed, index, dimension);
+
+    Vec2::new
+    (
+        shifted(value.x, noise(x, y, seed, dimension)),
+        shifted(value.y, noise(x, y, seed, dimension + 1))
+    )
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn ranks()
+    {
+        let mut ranks = texture().to_vec();
+        ranks.sort_unstable();
+
+        assert_eq!(ranks, (0..(SIZE * SIZE) as u16).collect::<Vec<u16>>());
+    }
+
+    #[test]
+    fn spectrum()
+    {
+        // Neighbouring pixels of blue noise should differ more than those of white noise would on average.
+        let texture = texture();
+        let mut difference = 0.0;
+
+        for y in 0..SIZE
+        {
+            for x in 0..SIZE
+            {
+                let value = f32::from(texture[y * SIZE + x]);
+                let right = f32::from(texture[y * SIZE + (x + 1) % SIZE]);
+                let bottom = f32::from(texture[((y + 1) % SIZE) * SIZE + x]);
+
+                difference += (value - right).abs() + (value - bottom).abs();
+            }
+        }
+
+        let average = difference / (2.0 * (SIZE * SIZE) as f32) / (SIZE * SIZE) as f32;
+        assert!(average > 0.38, "Average difference {} is too low!", average);
+    }
+}
@@ -0,0 +1,68 @@
+use super::math::Vec2;
+use super::ONE_MINUS_EPSILON;
+use super::{ hash_combine, to_unit, shifted };
+
+// Each dimension uses radical inverse in different prime base.
+const PRIMES: [u32; 32] =
+[
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53,
+    59, 61, 67, 71, 73, 79, 83, 89, 97, 101, 103, 107, 109, 113, 127, 131
+];
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn radical_inverse(base: u32, mut index: u32) -> f32
+{
+    // Mirrors digits of index around decimal point, see: https://www.pbr-book.org/3ed-2018/Sampling_and_Reconstruction/The_Halton_Sampler
+    let base_inv = 1.0 / f64::from(base);
+    let mut reversed: u64 = 0;
+    let mut factor = 1.0;
+
+    while index > 0
+    {
+        let next = index / base;
+        reversed = reversed * u64::from(base) + u64::from(index - next * base);
+        factor *= base_inv;
+        index = next;
+    }
+
+    ((reversed as f64 * factor) as f32).min(ONE_MINUS_EPSILON)
+}
+
+pub fn sample_1d(seed: u32, index: u32, dimension: u32) -> Option<f32>
+{
+    // Each pixel shifts the sequence by random offset, so neighbouring pixels are not correlated.
+    let base = *PRIMES.get(dimension as usize)?;
+    let shift = to_unit(hash_combine(seed, dimension));
+
+    Some(shifted(radical_inverse(base, index), shift))
+}
+
+pub fn sample_2d(seed: u32, index: u32, dimension: u32) -> Option<Vec2>
+{
+    Some(Vec2::new(sample_1d(seed, index, dimension)?, sample_1d(seed, index, dimension + 1)?))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn inverse()
+    {
+        assert_eq!(radical_inverse(2, 0), 0.0);
+        assert_eq!(radical_inverse(2, 1), 0.5);
+        assert_eq!(radical_inverse(2, 6), 0.375);
+        assert_eq!(radical_inverse(3, 1), 1.0 / 3.0);
+        assert_eq!(radical_inverse(3, 5), 7.0 / 9.0);
+    }
+
+    #[test]
+    fn dimensions()
+    {
+        assert!(sample_2d(0, 0, 30).is_some());
+        assert!(sample_2d(0, 0, 31).is_none());
+        assert!(sample_1d(0, 0, 32).is_none());
+    }
+}
@@ -0,0 +1,209 @@
+use serde::{ Serialize, Deserialize };
+
+use super::math;
+use super::math::Vec2;
+use super::math::Random;
+
+pub mod stratified;
+pub mod halton;
+pub mod sobol;
+pub mod blue_noise;
+
+// Largest value below one that can be represented, which sample values are clamped to.
+pub const ONE_MINUS_EPSILON: f32 = 1.0 - f32::EPSILON / 2.0;
+
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum Sampler
+{
+    #[default]
+    Stratified,
+    Halton,
+    Sobol,
+    BlueNoise
+}
+
+impl Sampler
+{
+    /// # Panics
+    ///
+    /// Panics when sample count is zero.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn begin_pixel(self, x: usize, y: usize, seed: u64, sample_count: usize) -> PixelSampler
+    {
+        assert!(sample_count >= 1, "Sample count must equal one or higher!");
+
+        let x = x as u32;
+        let y = y as u32;
+        let seed = hash_combine(hash(seed as u32), (seed >> 32) as u32);
+
+        PixelSampler
+        {
+            sampler: self,
+            x,
+            y,
+            seed,
+            pixel_seed: hash_combine(hash_combine(seed, x), y),
+            sample_count: sample_count as u32,
+            sample_index: 0,
+            dimension: 0,
+            random: Random::new(u64::from(seed), u64::from(y) << 32 | u64::from(x))
+        }
+    }
+}
+
+pub struct PixelSampler
+{
+    sampler: Sampler,
+    x: u32,
+    y: u32,
+    seed: u32,
+    pixel_seed: u32,
+    sample_count: u32,
+    sample_index: u32,
+    dimension: u32,
+    random: Random
+}
+
+impl PixelSampler
+{
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn begin_sample(&mut self, sample_index: usize)
+    {
+        // Every sample consumes dimensions in the same order, starting with pixel position.
+        self.sample_index = sample_index as u32;
+        self.dimension = 0;
+    }
+
+    pub fn get_random(&self) -> Random
+    {
+        self.random
+    }
+
+    pub fn set_random(&mut self, random: Random)
+    {
+        // Random state is the only part of sampler that changes between samples, so it is enough to resume rendering.
+        self.random = random;
+    }
+
+    pub fn next_1d(&mut self) -> f32
+    {
+        let dimension = self.dimension;
+        self.dimension += 1;
+
+        let value = match self.sampler
+        {
+            Sampler::Stratified =>
+            {
+                let jitter = self.random.next_f32();
+                Some(stratified::sample_1d(hash_combine(self.pixel_seed, dimension), self.sample_index, self.sample_count, jitter))
+            },
+            Sampler::Halton => halton::sample_1d(self.pixel_seed, self.sample_index, dimension),
+            Sampler::Sobol => Some(sobol::sample_1d(self.pixel_seed, self.sample_index, dimension)),
+            Sampler::BlueNoise => Some(blue_noise::sample_1d(self.x, self.y, self.seed, self.sample_index, dimension))
+        };
+
+        // Sequences with limited number of dimensions fall back to random values for deep paths.
+        value.unwrap_or_else(|| self.random.next_f32())
+    }
+
+    pub fn next_2d(&mut self) -> Vec2
+    {
+        let dimension = self.dimension;
+        self.dimension += 2;
+
+        let value = match self.sampler
+        {
+            Sampler::Stratified =>
+            {
+                let jitter = Vec2::new(self.random.next_f32(), self.random.next_f32());
+                Some(stratified::sample_2d(hash_combine(self.pixel_seed, dimension), self.sample_index, self.sample_count, jitter))
+            },
+            Sampler::Halton => halton::sample_2d(self.pixel_seed, self.sample_index, dimension),
+            Sampler::Sobol => Some(sobol::sample_2d(self.pixel_seed, self.sample_index, dimension)),
+            Sampler::BlueNoise => Some(blue_noise::sample_2d(self.x, self.y, self.seed, self.sample_index, dimension))
+        };
+
+        value.unwrap_or_else(|| Vec2::new(self.random.next_f32(), self.random.next_f32()))
+    }
+}
+
+pub fn hash(mut value: u32) -> u32
+{
+    // Integer hash with low bias, see: https://nullprogram.com/blog/2018/07/31/
+    value ^= value >> 16;
+    value = value.wrapping_mul(0x7feb_352d);
+    value ^= value >> 15;
+    value = value.wrapping_mul(0x846c_a68b);
+    value ^= value >> 16;
+    value
+}
+
+pub fn hash_combine(seed: u32, value: u32) -> u32
+{
+    hash(seed ^ value.wrapping_add(0x9e37_79b9).wrapping_add(seed << 6).wrapping_add(seed >> 2))
+}
+
+pub fn to_unit(value: u32) -> f32
+{
+    // Uses upper 24 bits, so result is always below one.
+    (value >> 8) as f32 * (1.0 / 16_777_216.0)
+}
+
+pub fn shifted(value: f32, shift: f32) -> f32
+{
+    // Toroidal shift that keeps distribution of sequence while decorrelating pixels.
+    let result = value + shift;
+    let result = if result >= 1.0 { result - 1.0 } else { result };
+    result.min(ONE_MINUS_EPSILON)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn range()
+    {
+        for sampler in &[Sampler::Stratified, Sampler::Halton, Sampler::Sobol, Sampler::BlueNoise]
+        {
+            let mut pixel_sampler = sampler.begin_pixel(3, 5, 42, 16);
+
+            for sample_index in 0..32
+            {
+                pixel_sampler.begin_sample(sample_index);
+
+                for _ in 0..40
+                {
+                    let value = pixel_sampler.next_1d();
+                    let pair = pixel_sampler.next_2d();
+
+                    assert!((0.0..1.0).contains(&value));
+                    assert!((0.0..1.0).contains(&pair.x) && (0.0..1.0).contains(&pair.y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn pixel_stratification()
+    {
+        // Sequences without random shifts should place exactly one of sixteen samples in each cell of 4x4 grid.
+        for sampler in &[Sampler::Stratified, Sampler::Sobol]
+        {
+            let mut pixel_sampler = sampler.begin_pixel(7, 2, 1, 16);
+            let mut cells = [0; 16];
+
+            for sample_index in 0..16
+            {
+                pixel_sampler.begin_sample(sample_index);
+
+                let position = pixel_sampler.next_2d();
+                cells[(position.y * 4.0) as usize * 4 + (position.x * 4.0) as usize] += 1;
+            }
+
+            assert_eq!(cells, [1; 16], "{sampler:?} sampler is not stratified!");
+        }
+    }
+}
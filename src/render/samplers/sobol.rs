@@ -0,0 +1,90 @@
+use super::math::Vec2;
+use super::{ hash_combine, to_unit };
+
+// Owen-scrambled Sobol sequence with padding between pairs of dimensions.
+// Implementation based on: https://www.jcgt.org/published/0009/04/01/
+
+fn sobol(mut index: u32, dimension: u32) -> u32
+{
+    // First dimension is van der Corput sequence, second one is generated by Pascal matrix.
+    if dimension == 0
+    {
+        return index.reverse_bits();
+    }
+
+    let mut result = 0;
+    let mut direction = 1 << 31;
+
+    while index != 0
+    {
+        if index & 1 != 0
+        {
+            result ^= direction;
+        }
+
+        index >>= 1;
+        direction ^= direction >> 1;
+    }
+
+    result
+}
+
+fn laine_karras_permutation(mut value: u32, seed: u32) -> u32
+{
+    value = value.wrapping_add(seed);
+    value ^= value.wrapping_mul(0x6c50_b47c);
+    value ^= value.wrapping_mul(0xb82f_1e52);
+    value ^= value.wrapping_mul(0xc7af_e638);
+    value ^= value.wrapping_mul(0x8d22_f6e6);
+    value
+}
+
+fn nested_uniform_scramble(value: u32, seed: u32) -> u32
+{
+    laine_karras_permutation(value.reverse_bits(), seed).reverse_bits()
+}
+
+pub fn sample_1d(seed: u32, index: u32, dimension: u32) -> f32
+{
+    let seed = hash_combine(seed, dimension);
+    let index = nested_uniform_scramble(index, seed);
+
+    to_unit(nested_uniform_scramble(sobol(index, 0), hash_combine(seed, 0)))
+}
+
+pub fn sample_2d(seed: u32, index: u32, dimension: u32) -> Vec2
+{
+    // Every pair of dimensions shuffles indices differently, which keeps them decorrelated.
+    let seed = hash_combine(seed, dimension);
+    let index = nested_uniform_scramble(index, seed);
+
+    Vec2::new
+    (
+        to_unit(nested_uniform_scramble(sobol(index, 0), hash_combine(seed, 0))),
+        to_unit(nested_uniform_scramble(sobol(index, 1), hash_combine(seed, 1)))
+    )
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn sequence()
+    {
+        let values: Vec<(u32, u32)> = (0..4).map(|index| (sobol(index, 0) >> 30, sobol(index, 1) >> 30)).collect();
+        assert_eq!(values, vec![(0, 0), (2, 2), (1, 3), (3, 1)]);
+    }
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn scramble()
+    {
+        // Scrambling must keep stratification of every power of two prefix.
+        let mut strata: Vec<u32> = (0..8).map(|index| (sample_1d(99, index, 3) * 8.0) as u32).collect();
+        strata.sort_unstable();
+
+        assert_eq!(strata, (0..8).collect::<Vec<u32>>());
+    }
+}
@@ -0,0 +1,110 @@
+use super::math::Vec2;
+use super::ONE_MINUS_EPSILON;
+use super::hash_combine;
+
+fn permute(mut index: u32, length: u32, seed: u32) -> u32
+{
+    // Hashed permutation that does not require storage, based on: https://graphics.pixar.com/library/MultiJitteredSampling/paper.pdf
+    let mut mask = length - 1;
+    mask |= mask >> 1;
+    mask |= mask >> 2;
+    mask |= mask >> 4;
+    mask |= mask >> 8;
+    mask |= mask >> 16;
+
+    loop
+    {
+        index ^= seed;
+        index = index.wrapping_mul(0xe170_893d);
+        index ^= seed >> 16;
+        index ^= (index & mask) >> 4;
+        index ^= seed >> 8;
+        index = index.wrapping_mul(0x0929_eb3f);
+        index ^= seed >> 23;
+        index ^= (index & mask) >> 1;
+        index = index.wrapping_mul(1 | seed >> 27);
+        index = index.wrapping_mul(0x6935_fa69);
+        index ^= (index & mask) >> 11;
+        index = index.wrapping_mul(0x74dc_b303);
+        index ^= (index & mask) >> 2;
+        index = index.wrapping_mul(0x9e50_1cc3);
+        index ^= (index & mask) >> 2;
+        index = index.wrapping_mul(0xc860_a3df);
+        index &= mask;
+        index ^= index >> 5;
+
+        // Values outside of range are cycled through permutation again until they land inside it.
+        if index < length
+        {
+            break;
+        }
+    }
+
+    index.wrapping_add(seed) % length
+}
+
+fn stratum(seed: u32, index: u32, count: u32) -> u32
+{
+    // Strata are visited in shuffled order, with new order for every full round of samples.
+    let round = index / count;
+    permute(index % count, count, hash_combine(seed, round))
+}
+
+pub fn sample_1d(seed: u32, index: u32, count: u32, jitter: f32) -> f32
+{
+    ((stratum(seed, index, count) as f32 + jitter) / count as f32).min(ONE_MINUS_EPSILON)
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn sample_2d(seed: u32, index: u32, count: u32, jitter: Vec2) -> Vec2
+{
+    let width = (count as f32).sqrt() as u32;
+
+    // Counts that do not form a square grid are stratified along each axis separately instead.
+    if width * width != count
+    {
+        return Vec2::new
+        (
+            sample_1d(seed, index, count, jitter.x),
+            sample_1d(hash_combine(seed, 1), index, count, jitter.y)
+        );
+    }
+
+    let stratum = stratum(seed, index, count);
+
+    Vec2::new
+    (
+        (((stratum % width) as f32 + jitter.x) / width as f32).min(ONE_MINUS_EPSILON),
+        (((stratum / width) as f32 + jitter.y) / width as f32).min(ONE_MINUS_EPSILON)
+    )
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn permutation()
+    {
+        for length in &[1, 2, 7, 16, 100]
+        {
+            let mut values: Vec<u32> = (0..*length).map(|index| permute(index, *length, 1234)).collect();
+            values.sort_unstable();
+
+            assert_eq!(values, (0..*length).collect::<Vec<u32>>());
+        }
+    }
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn rounds()
+    {
+        // Each round of samples should cover every stratum exactly once.
+        let mut strata: Vec<u32> = (0..10).map(|index| (sample_1d(7, index, 5, 0.5) * 5.0) as u32).collect();
+        strata[..5].sort_unstable();
+        strata[5..].sort_unstable();
+
+        assert_eq!(strata, vec![0, 1, 2, 3, 4, 0, 1, 2, 3, 4]);
+    }
+}
@@ -1,14 +1,42 @@
+use std::path::Path;
+use std::hash::Hasher;
+use std::sync::OnceLock;
 use serde::{ Serialize, Deserialize };
 use crate::math::Ray;
 use crate::math::Intersection;
+use crate::math::Hierarchy;
+use crate::math::BoundingBox;
 use super::camera;
+use super::environment::Environment;
 use super::objects::Object;
+use super::objects::ObjectKind;
+use super::objects::Geometry;
+use super::objects::wavefront;
+use super::objects::mesh;
+use super::materials::Material;
+use super::lights::Light;
 
 #[derive(Default, Serialize, Deserialize)]
 pub struct Scene
 {
     pub camera: camera::Parameters,
-    objects: Vec<Object>
+
+    #[serde(default)]
+    pub environment: Environment,
+
+    #[serde(skip_serializing_if = "Geometry::is_empty", default)]
+    geometry: Geometry,
+
+    objects: Vec<Object>,
+
+    #[serde(default)]
+    lights: Vec<Light>,
+
+    #[serde(skip)]
+    hierarchy: OnceLock<Hierarchy>,
+
+    #[serde(skip)]
+    material_offsets: OnceLock<Vec<usize>>
 }
 
 impl Scene
@@ -20,32 +48,208 @@ impl Scene
 
     pub fn set_camera(mut self, camera: camera::Parameters) -> Self
     {
+        // Bounds of moving objects depend on camera shutter times.
         self.camera = camera;
+        self.hierarchy = OnceLock::new();
+        self
+    }
+
+    #[must_use]
+    pub fn set_environment(mut self, environment: Environment) -> Self
+    {
+        self.environment = environment;
+        self
+    }
+
+    #[must_use]
+    pub fn add_geometry(mut self, name: &str, kind: ObjectKind) -> Self
+    {
+        // Shared geometry is not rendered by itself, only through instance objects that reference it by name.
+        self.geometry.insert(name.to_owned(), kind);
+        self.hierarchy = OnceLock::new();
+        self.material_offsets = OnceLock::new();
         self
     }
 
     pub fn add_object(mut self, object: Object) -> Self
     {
         self.objects.push(object);
+        self.hierarchy = OnceLock::new();
+        self.material_offsets = OnceLock::new();
         self
     }
 
-    pub fn intersect(&self, ray: &Ray, min_length: f32, max_length: f32) -> Option<(Intersection, &Object)>
+    #[must_use]
+    pub fn add_light(mut self, light: Light) -> Self
     {
-        let mut closest_intersection: Option<(Intersection, &Object)> = None;
-        let mut closest_length = max_length;
+        self.lights.push(light);
+        self
+    }
+
+    pub fn objects(&self) -> &[Object]
+    {
+        &self.objects
+    }
+
+    pub fn lights(&self) -> &[Light]
+    {
+        &self.lights
+    }
 
-        for object in &self.objects
+    /// # Errors
+    ///
+    /// Returns error for the first model file that cannot be loaded.
+    pub fn load_models(&mut self, directory: &Path) -> Result<(), wavefront::Error>
+    {
+        for kind in self.geometry.values_mut()
         {
-            if let Some(intersection) = object.intersect(ray, min_length, closest_length)
-            {
-                debug_assert!(intersection.length <= closest_length);
+            kind.load(directory)?;
+        }
+
+        for object in &mut self.objects
+        {
+            object.load(directory)?;
+        }
+
+        self.hierarchy = OnceLock::new();
+        self.material_offsets = OnceLock::new();
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// Returns error for the first texture that cannot be loaded.
+    pub fn load_textures(&mut self, directory: &Path) -> std::io::Result<()>
+    {
+        for kind in self.geometry.values_mut()
+        {
+            kind.load_textures(directory)?;
+        }
+
+        for object in &mut self.objects
+        {
+            object.load_textures(directory)?;
+        }
+
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when environment image cannot be loaded.
+    pub fn load_environment(&mut self, directory: &Path) -> std::io::Result<()>
+    {
+        self.environment.load(directory)
+    }
+
+    pub fn hash_resources<H: Hasher>(&self, hasher: &mut H)
+    {
+        // Setup only stores paths of models and images, so their loaded contents are hashed separately.
+        self.environment.hash_resources(hasher);
 
-                closest_length = intersection.length;
-                closest_intersection = Some((intersection, &object));
-            }
+        for kind in self.geometry.values().chain(self.objects.iter().map(Object::kind))
+        {
+            kind.hash_resources(hasher);
         }
+    }
+
+    pub fn find_missing_geometry(&self) -> Option<&str>
+    {
+        // Instances can only reference shared geometry that exists and is not an instance itself.
+        let is_missing = |name: &String|
+        {
+            self.geometry.get(name).is_none_or(|kind| matches!(kind, ObjectKind::Instance(_)))
+        };
+
+        let object_kinds = self.objects.iter().map(Object::kind);
+
+        self.geometry.values().chain(object_kinds).find_map(|kind| match kind
+        {
+            ObjectKind::Instance(instance) if is_missing(&instance.geometry) => Some(instance.geometry.as_str()),
+            _ => None
+        })
+    }
+
+    pub fn find_invalid_mesh(&self) -> Option<mesh::Error>
+    {
+        // Meshes of models are checked by loader, which leaves meshes given directly by setup or code.
+        self.geometry.values().chain(self.objects.iter().map(Object::kind)).find_map(|kind| match kind
+        {
+            ObjectKind::Mesh(mesh) => mesh.validate().err(),
+            _ => None
+        })
+    }
+
+    pub fn find_unloaded_image(&self) -> Option<&Path>
+    {
+        // Images are only loaded together with setup, so scenes built in code need to load them explicitly.
+        let object_kinds = self.objects.iter().map(Object::kind);
+
+        self.environment.find_unloaded_image()
+            .or_else(|| self.geometry.values().chain(object_kinds).find_map(ObjectKind::find_unloaded_image))
+    }
+
+    pub fn find_unloaded_model(&self) -> Option<&Path>
+    {
+        // Models are only loaded together with setup as well, otherwise they would render as nothing.
+        let object_kinds = self.objects.iter().map(Object::kind);
+        self.geometry.values().chain(object_kinds).find_map(ObjectKind::find_unloaded_model)
+    }
+
+    pub fn find_singular_transform(&self) -> Option<usize>
+    {
+        self.objects.iter().position(|object| !object.has_invertible_transform())
+    }
+
+    fn get_hierarchy(&self) -> &Hierarchy
+    {
+        // Hierarchy is built lazily on first use, so scenes can be freely modified or deserialized before rendering.
+        self.hierarchy.get_or_init(||
+        {
+            let bounds: Vec<BoundingBox> = self.objects.iter().map(|object|
+            {
+                object.bounding_box(self.camera.shutter_open_time, self.camera.shutter_close_time, &self.geometry)
+            }).collect();
+
+            Hierarchy::new(&bounds)
+        })
+    }
+
+    pub fn intersect(&self, ray: &Ray, min_length: f32, max_length: f32) -> Option<(Intersection, &Material)>
+    {
+        self.get_hierarchy().intersect(ray, min_length, max_length, |index, min_length, max_length|
+        {
+            self.objects[index].intersect(ray, min_length, max_length, &self.geometry)
+        })
+    }
+
+    pub fn intersect_object(&self, ray: &Ray, min_length: f32, max_length: f32) -> Option<(Intersection, &Material, usize)>
+    {
+        // Same as regular intersection, but also returns index of the object that was hit.
+        self.get_hierarchy().intersect(ray, min_length, max_length, |index, min_length, max_length|
+        {
+            self.objects[index].intersect(ray, min_length, max_length, &self.geometry)
+                .map(|(intersection, material)| (intersection, (material, index)))
+        }).map(|(intersection, (material, index))| (intersection, material, index))
+    }
+
+    pub fn material_index(&self, object_index: usize, material: &Material) -> usize
+    {
+        // Materials are numbered in order of objects, with each object counting all materials it uses.
+        let offsets = self.material_offsets.get_or_init(||
+        {
+            self.objects.iter().scan(0, |offset, object|
+            {
+                let object_offset = *offset;
+                *offset += object.materials(&self.geometry).len();
+                Some(object_offset)
+            }).collect()
+        });
+
+        let position = self.objects[object_index].materials(&self.geometry).iter()
+            .position(|object_material| std::ptr::eq(*object_material, material))
+            .unwrap_or_default();
 
-        closest_intersection
+        offsets[object_index] + position
     }
 }
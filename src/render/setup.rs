@@ -1,6 +1,7 @@
 use std::path::Path;
 use std::fs::OpenOptions;
-use std::io::{ BufWriter, BufReader };
+use std::io::{ BufWriter, BufReader, Read };
+use std::hash::Hasher;
 use serde::{ Serialize, Deserialize };
 use super::parameters::Parameters;
 use super::scene::Scene;
@@ -12,6 +13,15 @@ pub enum Error
     CreatingFile,
     Serializing,
     Deserializing,
+    LoadingModel,
+    LoadingTexture,
+    MissingGeometry,
+    LoadingEnvironment,
+    InvalidParameters,
+    InvalidMesh,
+    InvalidTransform,
+    UnloadedImage,
+    UnloadedModel
 }
 
 #[derive(Default, Serialize, Deserialize)]
@@ -21,6 +31,84 @@ pub struct Setup
     pub scene: Scene
 }
 
+// FNV-1a, which unlike standard hasher is stable between program runs and versions.
+struct Fnv(u64);
+
+impl Hasher for Fnv
+{
+    fn finish(&self) -> u64
+    {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8])
+    {
+        self.0 = bytes.iter().fold(self.0, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3));
+    }
+}
+
+/// # Panics
+///
+/// Panics when parameters or scene cannot be serialized.
+pub fn hash(parameters: &Parameters, scene: &Scene) -> u64
+{
+    // Hash of everything that affects rendered samples, used to check that checkpoint belongs to the setup.
+    // Settings applied to finished image are left out, so they can be changed when render is resumed.
+    let mut sampled_parameters = serde_json::to_value(parameters).expect("Failed to serialize parameters!");
+
+    if let Some(fields) = sampled_parameters.as_object_mut()
+    {
+        for name in &["aovs", "tone_mapping", "denoiser"]
+        {
+            fields.remove(*name);
+        }
+    }
+
+    let mut hasher = Fnv(0xcbf2_9ce4_8422_2325);
+    hasher.write(&serde_json::to_vec(&(sampled_parameters, scene)).expect("Failed to serialize setup!"));
+    scene.hash_resources(&mut hasher);
+    hasher.finish()
+}
+
+/// # Errors
+///
+/// Returns error when parameters are invalid, scene has broken mesh or transform, or its images and models are not loaded.
+pub fn validate(parameters: &Parameters, scene: &Scene) -> Result<(), Error>
+{
+    // Problems that would otherwise only show up in the middle of rendering are reported before it starts.
+    if let Some(message) = parameters.find_invalid_setting()
+    {
+        println!("Invalid parameters: {message}");
+        return Err(Error::InvalidParameters);
+    }
+
+    if let Some(error) = scene.find_invalid_mesh()
+    {
+        println!("Invalid mesh: {error:?}");
+        return Err(Error::InvalidMesh);
+    }
+
+    if let Some(index) = scene.find_singular_transform()
+    {
+        println!("Transform of object {index} cannot be inverted.");
+        return Err(Error::InvalidTransform);
+    }
+
+    if let Some(path) = scene.find_unloaded_image()
+    {
+        println!("Image has not been loaded: {}", path.display());
+        return Err(Error::UnloadedImage);
+    }
+
+    if let Some(path) = scene.find_unloaded_model()
+    {
+        println!("Model has not been loaded: {}", path.display());
+        return Err(Error::UnloadedModel);
+    }
+
+    Ok(())
+}
+
 impl Setup
 {
     pub fn new() -> Self
@@ -28,20 +116,62 @@ impl Setup
         Self::default()
     }
 
+    pub fn hash(&self) -> u64
+    {
+        hash(&self.parameters, &self.scene)
+    }
+
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error>
     {
-        let scene_file = OpenOptions::new().read(true).open(path).or(Err(Error::OpeningFile))?;
+        let scene_file = OpenOptions::new().read(true).open(&path).or(Err(Error::OpeningFile))?;
         let file_reader = BufReader::new(scene_file);
 
-        match serde_json::from_reader(file_reader)
+        // Models, textures and environment images referenced by the setup are stored in separate files next to it.
+        let directory = path.as_ref().parent().unwrap_or_else(|| Path::new(""));
+        Self::from_reader(file_reader, directory)
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when setup cannot be parsed or its resources cannot be loaded.
+    pub fn from_reader<R: Read>(reader: R, directory: &Path) -> Result<Self, Error>
+    {
+        let mut setup: Self = match serde_json::from_reader(reader)
         {
-            Ok(setup) => Ok(setup),
+            Ok(setup) => setup,
             Err(error) =>
             {
                 println!("Deserialization error: {}", error);
-                Err(Error::Deserializing)
+                return Err(Error::Deserializing);
             }
+        };
+
+        if let Some(name) = setup.scene.find_missing_geometry()
+        {
+            println!("Missing geometry: {name}");
+            return Err(Error::MissingGeometry);
         }
+
+        if let Err(error) = setup.scene.load_models(directory)
+        {
+            println!("Model loading error: {error:?}");
+            return Err(Error::LoadingModel);
+        }
+
+        if let Err(error) = setup.scene.load_textures(directory)
+        {
+            println!("Texture loading error: {error}");
+            return Err(Error::LoadingTexture);
+        }
+
+        if let Err(error) = setup.scene.load_environment(directory)
+        {
+            println!("Environment loading error: {error}");
+            return Err(Error::LoadingEnvironment);
+        }
+
+        validate(&setup.parameters, &setup.scene)?;
+        Ok(setup)
     }
 
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error>
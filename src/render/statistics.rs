@@ -1,5 +1,8 @@
 use std::iter;
 
+// Image is split into a grid of regions, for which average sample counts are reported.
+const REGION_GRID_SIZE: usize = 8;
+
 pub struct Statistics
 {
     pub pixels: usize,
@@ -7,7 +10,10 @@ pub struct Statistics
     pub samples: usize,
     pub intersections: usize,
     pub scatters: usize,
-    pub max_scatters: u16
+    pub shadows: usize,
+    pub max_scatters: u16,
+    pub roulette_terminations: usize,
+    pub region_samples: Vec<f32>
 }
 
 impl Default for Statistics
@@ -21,7 +27,10 @@ impl Default for Statistics
             samples: 0,
             intersections: 0,
             scatters: 0,
-            max_scatters: 0
+            shadows: 0,
+            max_scatters: 0,
+            roulette_terminations: 0,
+            region_samples: Vec::new()
         }
     }
 }
@@ -42,7 +51,10 @@ impl Statistics
             samples: 0,
             intersections: 0,
             scatters: 0,
-            max_scatters: 0
+            shadows: 0,
+            max_scatters: 0,
+            roulette_terminations: 0,
+            region_samples: Vec::new()
         }
     }
 
@@ -55,18 +67,62 @@ impl Statistics
             samples: self.samples + other.samples,
             intersections: self.intersections + other.intersections,
             scatters: self.scatters + other.scatters,
-            max_scatters: self.max_scatters.max(other.max_scatters)
+            shadows: self.shadows + other.shadows,
+            max_scatters: self.max_scatters.max(other.max_scatters),
+            roulette_terminations: self.roulette_terminations + other.roulette_terminations,
+
+            // Region samples are calculated once for the whole image, so at most one side is expected to have them.
+            region_samples: if self.region_samples.is_empty() { other.region_samples.clone() } else { self.region_samples.clone() }
         }
     }
 
+    pub fn set_region_samples(&mut self, sample_counts: &[usize], width: usize, height: usize)
+    {
+        debug_assert_eq!(sample_counts.len(), width * height);
+
+        let mut totals = vec![(0, 0); REGION_GRID_SIZE * REGION_GRID_SIZE];
+
+        for (index, sample_count) in sample_counts.iter().enumerate()
+        {
+            let region_x = (index % width) * REGION_GRID_SIZE / width;
+            let region_y = (index / width) * REGION_GRID_SIZE / height;
+
+            let total = &mut totals[region_y * REGION_GRID_SIZE + region_x];
+            total.0 += sample_count;
+            total.1 += 1;
+        }
+
+        self.region_samples = totals.iter().map(|(samples, pixels)|
+        {
+            if *pixels == 0 { 0.0 } else { *samples as f32 / *pixels as f32 }
+        }).collect();
+    }
+
     pub fn print(&self)
     {
+        // Render cancelled before any pixel was sampled has no pixels to average over.
+        let pixels = self.pixels.max(1);
+
         println!("Printing render statistics:");
         println!("  Pixels:        {}", self.pixels);
-        println!("  Subpixels:     {} ({} per pixel)", self.subpixels, self.subpixels / self.pixels);
-        println!("  Samples:       {} ({:.2} per pixel)", self.samples, self.samples as f32 / self.pixels as f32);
-        println!("  Intersections: {} ({:.2} per pixel)", self.intersections, self.intersections as f32 / self.pixels as f32);
+        println!("  Subpixels:     {} ({} per pixel)", self.subpixels, self.subpixels / pixels);
+        println!("  Samples:       {} ({:.2} per pixel)", self.samples, self.samples as f32 / pixels as f32);
+        println!("  Intersections: {} ({:.2} per pixel)", self.intersections, self.intersections as f32 / pixels as f32);
         println!("  Scatters:      {} ({} max)", self.scatters, self.max_scatters);
+        println!("  Roulette:      {} ({:.2} per pixel)", self.roulette_terminations, self.roulette_terminations as f32 / pixels as f32);
+        println!("  Shadows:       {} ({:.2} per pixel)", self.shadows, self.shadows as f32 / pixels as f32);
+
+        if !self.region_samples.is_empty()
+        {
+            // Image rows are stored from bottom to top, so regions are printed in reverse order.
+            println!("  Subpixels per pixel in each region:");
+
+            for row in self.region_samples.chunks(REGION_GRID_SIZE).rev()
+            {
+                let columns: Vec<String> = row.iter().map(|samples| format!("{samples:8.1}")).collect();
+                println!("   {}", columns.concat());
+            }
+        }
     }
 }
 
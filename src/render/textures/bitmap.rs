@@ -0,0 +1,158 @@
+use std::path::{ Path, PathBuf };
+use std::hash::{ Hash, Hasher };
+use serde::{ Serialize, Deserialize };
+use super::image;
+use super::math::Vec2;
+use super::math::Vec4;
+use super::Texture;
+use super::Pattern;
+
+#[derive(Copy, Clone, Default, Serialize, Deserialize)]
+pub enum Wrap
+{
+    #[default]
+    Repeat,
+    Mirror,
+    Clamp
+}
+
+impl Wrap
+{
+    #[allow(clippy::cast_sign_loss)]
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_precision_loss)]
+    fn apply(self, index: f32, size: usize) -> usize
+    {
+        // Maps pixel index that can lie outside of image into a valid one.
+        let size = size as f32;
+
+        let wrapped = match self
+        {
+            Self::Repeat => index.rem_euclid(size),
+            Self::Mirror =>
+            {
+                let period = index.rem_euclid(2.0 * size);
+
+                if period < size
+                {
+                    period
+                }
+                else
+                {
+                    2.0 * size - 1.0 - period
+                }
+            },
+            Self::Clamp => index.clamp(0.0, size - 1.0)
+        };
+
+        (wrapped as usize).min(size as usize - 1)
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub fn sample_bilinear(surface: &image::Surface, uv: Vec2, wrap_u: Wrap, wrap_v: Wrap) -> Vec4
+{
+    // Pixel centers lie in the middle between integer coordinates.
+    let x = uv.x * surface.get_width() as f32 - 0.5;
+    let y = uv.y * surface.get_height() as f32 - 0.5;
+
+    let x_floor = x.floor();
+    let y_floor = y.floor();
+    let x_weight = x - x_floor;
+    let y_weight = y - y_floor;
+
+    let x0 = wrap_u.apply(x_floor, surface.get_width());
+    let x1 = wrap_u.apply(x_floor + 1.0, surface.get_width());
+    let y0 = wrap_v.apply(y_floor, surface.get_height());
+    let y1 = wrap_v.apply(y_floor + 1.0, surface.get_height());
+
+    let bottom = surface.get_pixel(x0, y0) * (1.0 - x_weight) + surface.get_pixel(x1, y0) * x_weight;
+    let top = surface.get_pixel(x0, y1) * (1.0 - x_weight) + surface.get_pixel(x1, y1) * x_weight;
+
+    bottom * (1.0 - y_weight) + top * y_weight
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Bitmap
+{
+    pub path: PathBuf,
+
+    #[serde(default)]
+    pub wrap: Wrap,
+
+    #[serde(skip)]
+    surface: Option<image::Surface>
+}
+
+impl Bitmap
+{
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new<P: AsRef<Path>>(path: P, wrap: Wrap) -> Texture
+    {
+        Texture::Pattern(Pattern::Bitmap(Self
+        {
+            path: PathBuf::from(path.as_ref()),
+            wrap,
+            surface: None
+        }))
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when image file cannot be read in any supported format.
+    pub fn load(&mut self, directory: &Path) -> std::io::Result<()>
+    {
+        // Colors stored in sRGB are converted to linear values used during rendering.
+        self.surface = Some(image::Reader::new().input(directory.join(&self.path)).load()?);
+        Ok(())
+    }
+
+    pub fn is_loaded(&self) -> bool
+    {
+        self.surface.is_some()
+    }
+
+    pub fn hash_resources<H: Hasher>(&self, hasher: &mut H)
+    {
+        self.surface.hash(hasher);
+    }
+
+    /// # Panics
+    ///
+    /// Panics when image has not been loaded, which setup validation reports before rendering.
+    pub fn sample(&self, uv: Vec2) -> Vec4
+    {
+        let surface = self.surface.as_ref().expect("Texture image has not been loaded!");
+        sample_bilinear(surface, uv, self.wrap, self.wrap)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn wrap()
+    {
+        assert_eq!(Wrap::Repeat.apply(-1.0, 4), 3);
+        assert_eq!(Wrap::Repeat.apply(5.0, 4), 1);
+        assert_eq!(Wrap::Mirror.apply(-1.0, 4), 0);
+        assert_eq!(Wrap::Mirror.apply(5.0, 4), 2);
+        assert_eq!(Wrap::Clamp.apply(-1.0, 4), 0);
+        assert_eq!(Wrap::Clamp.apply(5.0, 4), 3);
+    }
+
+    #[test]
+    fn sample()
+    {
+        let mut surface = image::Surface::new(2, 1);
+        surface.set_pixel(0, 0, Vec4::black());
+        surface.set_pixel(1, 0, Vec4::white());
+
+        assert_eq!(sample_bilinear(&surface, Vec2::new(0.25, 0.5), Wrap::Clamp, Wrap::Clamp), Vec4::black());
+        assert_eq!(sample_bilinear(&surface, Vec2::new(0.5, 0.5), Wrap::Clamp, Wrap::Clamp), Vec4::new(0.5, 0.5, 0.5, 1.0));
+        assert_eq!(sample_bilinear(&surface, Vec2::new(0.0, 0.5), Wrap::Repeat, Wrap::Clamp), Vec4::new(0.5, 0.5, 0.5, 1.0));
+        assert_eq!(sample_bilinear(&surface, Vec2::new(0.0, 0.5), Wrap::Clamp, Wrap::Clamp), Vec4::black());
+    }
+}
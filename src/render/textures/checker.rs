@@ -0,0 +1,64 @@
+use serde::{ Serialize, Deserialize };
+use super::math::Vec2;
+use super::math::Vec4;
+use super::Texture;
+use super::Pattern;
+
+#[derive(Serialize, Deserialize)]
+pub struct Checker
+{
+    even: Vec4,
+    odd: Vec4,
+    scale: f32
+}
+
+impl Checker
+{
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(even: Vec4, odd: Vec4, scale: f32) -> Texture
+    {
+        Texture::Pattern(Pattern::Checker(Self
+        {
+            even,
+            odd,
+            scale
+        }))
+    }
+
+    pub fn sample(&self, uv: Vec2) -> Vec4
+    {
+        // Scale specifies number of squares along each texture coordinate axis.
+        let square = (uv.x * self.scale).floor() + (uv.y * self.scale).floor();
+
+        if square.rem_euclid(2.0) < 1.0
+        {
+            self.even
+        }
+        else
+        {
+            self.odd
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn sample()
+    {
+        let checker = Checker
+        {
+            even: Vec4::white(),
+            odd: Vec4::black(),
+            scale: 2.0
+        };
+
+        assert_eq!(checker.sample(Vec2::new(0.25, 0.25)), Vec4::white());
+        assert_eq!(checker.sample(Vec2::new(0.75, 0.25)), Vec4::black());
+        assert_eq!(checker.sample(Vec2::new(0.75, 0.75)), Vec4::white());
+        assert_eq!(checker.sample(Vec2::new(-0.25, 0.25)), Vec4::black());
+    }
+}
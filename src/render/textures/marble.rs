@@ -0,0 +1,52 @@
+use serde::{ Serialize, Deserialize };
+use super::math::Vec3;
+use super::math::Vec4;
+use super::noise;
+use super::Texture;
+use super::Pattern;
+
+#[derive(Serialize, Deserialize)]
+pub struct Marble
+{
+    seed: u32,
+    scale: f32,
+
+    #[serde(default = "super::default_octaves")]
+    octaves: u32,
+
+    #[serde(default = "default_distortion")]
+    distortion: f32,
+
+    #[serde(default = "Vec4::white")]
+    color: Vec4
+}
+
+fn default_distortion() -> f32
+{
+    10.0
+}
+
+impl Marble
+{
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(seed: u32, scale: f32, color: Vec4) -> Texture
+    {
+        Texture::Pattern(Pattern::Marble(Self
+        {
+            seed,
+            scale,
+            octaves: super::default_octaves(),
+            distortion: default_distortion(),
+            color
+        }))
+    }
+
+    pub fn sample(&self, point: Vec3) -> Vec4
+    {
+        // Stripes along up axis have their phase distorted by turbulence.
+        let turbulence = noise::turbulence(point, self.seed, self.octaves);
+        let value = 0.5 * (1.0 + (self.scale * point.get_z() + self.distortion * turbulence).sin());
+
+        super::scaled(self.color, value)
+    }
+}
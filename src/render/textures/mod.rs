@@ -0,0 +1,120 @@
+use std::path::Path;
+use std::hash::Hasher;
+use serde::{ Serialize, Deserialize };
+
+use super::math;
+use super::image;
+use super::math::Vec4;
+use super::math::Intersection;
+
+pub mod checker;
+pub use checker::Checker;
+pub mod bitmap;
+pub use bitmap::Bitmap;
+pub use bitmap::Wrap;
+pub mod noise;
+pub mod perlin;
+pub use perlin::Perlin;
+pub mod turbulence;
+pub use turbulence::Turbulence;
+pub mod marble;
+pub use marble::Marble;
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Texture
+{
+    Constant(Vec4),
+    Pattern(Pattern)
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum Pattern
+{
+    Checker(Checker),
+    Bitmap(Bitmap),
+    Perlin(Perlin),
+    Turbulence(Turbulence),
+    Marble(Marble)
+}
+
+impl From<Vec4> for Texture
+{
+    fn from(color: Vec4) -> Self
+    {
+        Self::Constant(color)
+    }
+}
+
+impl Texture
+{
+    pub fn black() -> Self
+    {
+        Self::Constant(Vec4::black())
+    }
+
+    pub fn is_black(&self) -> bool
+    {
+        match &self
+        {
+            Self::Constant(color) => color.get_r() <= 0.0 && color.get_g() <= 0.0 && color.get_b() <= 0.0,
+            Self::Pattern(_) => false
+        }
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when bitmap image cannot be loaded.
+    pub fn load(&mut self, directory: &Path) -> std::io::Result<()>
+    {
+        match self
+        {
+            Self::Pattern(Pattern::Bitmap(bitmap)) => bitmap.load(directory),
+            _ => Ok(())
+        }
+    }
+
+    pub fn find_unloaded_image(&self) -> Option<&Path>
+    {
+        match self
+        {
+            Self::Pattern(Pattern::Bitmap(bitmap)) if !bitmap.is_loaded() => Some(&bitmap.path),
+            _ => None
+        }
+    }
+
+    pub fn hash_resources<H: Hasher>(&self, hasher: &mut H)
+    {
+        // Only bitmaps are loaded from files, while other patterns are fully described by setup.
+        if let Self::Pattern(Pattern::Bitmap(bitmap)) = self
+        {
+            bitmap.hash_resources(hasher);
+        }
+    }
+
+    pub fn sample(&self, intersection: &Intersection) -> Vec4
+    {
+        match &self
+        {
+            Self::Constant(color) => *color,
+            Self::Pattern(Pattern::Checker(checker)) => checker.sample(intersection.uv),
+            Self::Pattern(Pattern::Bitmap(bitmap)) => bitmap.sample(intersection.uv),
+            Self::Pattern(Pattern::Perlin(perlin)) => perlin.sample(intersection.point),
+            Self::Pattern(Pattern::Turbulence(turbulence)) => turbulence.sample(intersection.point),
+            Self::Pattern(Pattern::Marble(marble)) => marble.sample(intersection.point)
+        }
+    }
+}
+
+fn default_octaves() -> u32
+{
+    7
+}
+
+fn scaled(color: Vec4, value: f32) -> Vec4
+{
+    // Procedural patterns modulate color intensity while keeping its alpha.
+    let mut scaled = color * value;
+    scaled.set_a(color.get_a());
+    scaled
+}
@@ -0,0 +1,124 @@
+use super::math::Vec3;
+
+// Improved Perlin noise, see: https://mrl.cs.nyu.edu/~perlin/noise/
+// Instead of a permutation table, lattice points are hashed together with seed,
+// so differently seeded noise does not need any precomputed state.
+
+fn hash(x: i32, y: i32, z: i32, seed: u32) -> u32
+{
+    #[allow(clippy::cast_sign_loss)]
+    let mut hash = seed ^ (x as u32).wrapping_mul(0x8da6_b343) ^ (y as u32).wrapping_mul(0xd816_3841) ^ (z as u32).wrapping_mul(0xcb1a_b31f);
+
+    // Finalizer from MurmurHash3 mixes all input bits into the result.
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2_ae35);
+    hash ^ (hash >> 16)
+}
+
+fn gradient(hash: u32, offset: Vec3) -> f32
+{
+    // Selects one of twelve gradient directions pointing to cube edges.
+    let index = hash & 15;
+    let first = if index < 8 { offset.get_x() } else { offset.get_y() };
+    let second = if index < 4 { offset.get_y() } else if index == 12 || index == 14 { offset.get_x() } else { offset.get_z() };
+
+    (if index & 1 == 0 { first } else { -first }) + (if index & 2 == 0 { second } else { -second })
+}
+
+fn fade(t: f32) -> f32
+{
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f32, a: f32, b: f32) -> f32
+{
+    a + t * (b - a)
+}
+
+#[allow(clippy::cast_possible_truncation)]
+pub fn perlin(point: Vec3, seed: u32) -> f32
+{
+    // Returns value roughly in range from minus one to one, which is zero at every lattice point.
+    let floor = Vec3::new(point.get_x().floor(), point.get_y().floor(), point.get_z().floor());
+    let local = point - floor;
+
+    let lattice = (floor.get_x() as i32, floor.get_y() as i32, floor.get_z() as i32);
+    let weight = (fade(local.get_x()), fade(local.get_y()), fade(local.get_z()));
+
+    let corner = |dx: i32, dy: i32, dz: i32|
+    {
+        #[allow(clippy::cast_precision_loss)]
+        let offset = local - Vec3::new(dx as f32, dy as f32, dz as f32);
+        gradient(hash(lattice.0 + dx, lattice.1 + dy, lattice.2 + dz, seed), offset)
+    };
+
+    lerp(weight.2,
+        lerp(weight.1, lerp(weight.0, corner(0, 0, 0), corner(1, 0, 0)), lerp(weight.0, corner(0, 1, 0), corner(1, 1, 0))),
+        lerp(weight.1, lerp(weight.0, corner(0, 0, 1), corner(1, 0, 1)), lerp(weight.0, corner(0, 1, 1), corner(1, 1, 1))))
+}
+
+pub fn turbulence(point: Vec3, seed: u32, octaves: u32) -> f32
+{
+    // Fractal sum of noise octaves, each with doubled frequency and halved amplitude.
+    let mut sum = 0.0;
+    let mut frequency = 1.0;
+    let mut amplitude = 1.0;
+
+    for octave in 0..octaves
+    {
+        sum += amplitude * perlin(point * frequency, seed.wrapping_add(octave)).abs();
+        frequency *= 2.0;
+        amplitude *= 0.5;
+    }
+
+    sum
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub fn normalized_turbulence(point: Vec3, seed: u32, octaves: u32) -> f32
+{
+    // Turbulence relative to total amplitude of its octaves, which keeps it between zero and one.
+    // Single octave of noise can slightly exceed one, so the result is clamped as well.
+    let total_amplitude = 2.0 - 2.0 * 0.5_f32.powf(octaves as f32);
+
+    if total_amplitude <= 0.0
+    {
+        return 0.0;
+    }
+
+    (turbulence(point, seed, octaves) / total_amplitude).min(1.0)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn perlin_range()
+    {
+        for index in 0..1000
+        {
+            #[allow(clippy::cast_precision_loss)]
+            let point = Vec3::new(index as f32 * 0.37, index as f32 * -0.13, index as f32 * 0.71);
+            let value = perlin(point, 7);
+
+            assert!((-1.1..=1.1).contains(&value));
+            assert!(turbulence(point, 7, 5) >= 0.0);
+            assert!((0.0..=1.0).contains(&normalized_turbulence(point, 7, 5)));
+        }
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn perlin_seed()
+    {
+        let point = Vec3::new(0.5, 1.25, -3.75);
+
+        assert_eq!(perlin(Vec3::new(1.0, 2.0, -3.0), 1), 0.0);
+        assert_eq!(perlin(point, 1), perlin(point, 1));
+        assert_ne!(perlin(point, 1), perlin(point, 2));
+    }
+}
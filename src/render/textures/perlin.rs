@@ -0,0 +1,36 @@
+use serde::{ Serialize, Deserialize };
+use super::math::Vec3;
+use super::math::Vec4;
+use super::noise;
+use super::Texture;
+use super::Pattern;
+
+#[derive(Serialize, Deserialize)]
+pub struct Perlin
+{
+    seed: u32,
+    scale: f32,
+
+    #[serde(default = "Vec4::white")]
+    color: Vec4
+}
+
+impl Perlin
+{
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(seed: u32, scale: f32, color: Vec4) -> Texture
+    {
+        Texture::Pattern(Pattern::Perlin(Self
+        {
+            seed,
+            scale,
+            color
+        }))
+    }
+
+    pub fn sample(&self, point: Vec3) -> Vec4
+    {
+        let value = 0.5 * (1.0 + noise::perlin(point * self.scale, self.seed));
+        super::scaled(self.color, value)
+    }
+}
@@ -0,0 +1,41 @@
+use serde::{ Serialize, Deserialize };
+use super::math::Vec3;
+use super::math::Vec4;
+use super::noise;
+use super::Texture;
+use super::Pattern;
+
+#[derive(Serialize, Deserialize)]
+pub struct Turbulence
+{
+    seed: u32,
+    scale: f32,
+
+    #[serde(default = "super::default_octaves")]
+    octaves: u32,
+
+    #[serde(default = "Vec4::white")]
+    color: Vec4
+}
+
+impl Turbulence
+{
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(seed: u32, scale: f32, octaves: u32, color: Vec4) -> Texture
+    {
+        Texture::Pattern(Pattern::Turbulence(Self
+        {
+            seed,
+            scale,
+            octaves,
+            color
+        }))
+    }
+
+    pub fn sample(&self, point: Vec3) -> Vec4
+    {
+        // Normalized value keeps albedo within given color, so materials never reflect more light than they receive.
+        let value = noise::normalized_turbulence(point * self.scale, self.seed, self.octaves);
+        super::scaled(self.color, value)
+    }
+}
@@ -0,0 +1,165 @@
+use serde::{ Serialize, Deserialize };
+
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum TileOrder
+{
+    #[default]
+    Spiral,
+    Hilbert
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tile
+{
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize
+}
+
+impl Tile
+{
+    pub fn pixel_count(&self) -> usize
+    {
+        self.width * self.height
+    }
+
+    pub fn pixel_position(&self, index: usize) -> (usize, usize)
+    {
+        (self.x + index % self.width, self.y + index / self.width)
+    }
+}
+
+fn hilbert_index(size: usize, mut x: usize, mut y: usize) -> usize
+{
+    // Position along Hilbert curve filling square grid of power of two size.
+    // Implementation based on: https://en.wikipedia.org/wiki/Hilbert_curve#Applications_and_mapping_algorithms
+    let mut index = 0;
+    let mut step = size / 2;
+
+    while step > 0
+    {
+        let rotate_x = usize::from(x & step > 0);
+        let rotate_y = usize::from(y & step > 0);
+        index += step * step * ((3 * rotate_x) ^ rotate_y);
+
+        if rotate_y == 0
+        {
+            if rotate_x == 1
+            {
+                x = size - 1 - x;
+                y = size - 1 - y;
+            }
+
+            std::mem::swap(&mut x, &mut y);
+        }
+
+        step /= 2;
+    }
+
+    index
+}
+
+/// # Panics
+///
+/// Panics when tile size is zero, which parameter validation reports before rendering.
+pub fn generate(image_width: usize, image_height: usize, tile_size: usize, order: TileOrder) -> Vec<Tile>
+{
+    assert!(tile_size > 0, "Tile size cannot be zero!");
+
+    let columns = image_width.div_ceil(tile_size);
+    let rows = image_height.div_ceil(tile_size);
+
+    let mut tiles: Vec<(usize, usize)> = (0..rows).flat_map(|row| (0..columns).map(move |column| (column, row))).collect();
+
+    match order
+    {
+        TileOrder::Spiral =>
+        {
+            // Tiles are visited ring by ring going outwards from image center, which is usually the most interesting part.
+            let center_x = (columns as f32 - 1.0) * 0.5;
+            let center_y = (rows as f32 - 1.0) * 0.5;
+
+            let key = |(column, row): &(usize, usize)|
+            {
+                let offset_x = *column as f32 - center_x;
+                let offset_y = *row as f32 - center_y;
+                (offset_x.abs().max(offset_y.abs()).round(), offset_y.atan2(offset_x))
+            };
+
+            tiles.sort_by(|a, b| key(a).partial_cmp(&key(b)).unwrap_or(std::cmp::Ordering::Equal));
+        },
+        TileOrder::Hilbert =>
+        {
+            let size = columns.max(rows).next_power_of_two();
+            tiles.sort_by_key(|(column, row)| hilbert_index(size, *column, *row));
+        }
+    }
+
+    tiles.iter().map(|(column, row)|
+    {
+        let x = column * tile_size;
+        let y = row * tile_size;
+
+        Tile
+        {
+            x,
+            y,
+            width: tile_size.min(image_width - x),
+            height: tile_size.min(image_height - y)
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn assert_coverage(tiles: &[Tile], width: usize, height: usize)
+    {
+        let mut coverage = vec![0; width * height];
+
+        for tile in tiles
+        {
+            for index in 0..tile.pixel_count()
+            {
+                let (x, y) = tile.pixel_position(index);
+                coverage[y * width + x] += 1;
+            }
+        }
+
+        assert!(coverage.iter().all(|count| *count == 1));
+    }
+
+    #[test]
+    fn spiral()
+    {
+        let tiles = generate(100, 70, 16, TileOrder::Spiral);
+
+        assert_eq!(tiles.len(), 7 * 5);
+        assert_eq!(tiles[0], Tile { x: 48, y: 32, width: 16, height: 16 });
+        assert_coverage(&tiles, 100, 70);
+    }
+
+    #[test]
+    fn hilbert()
+    {
+        assert_eq!(hilbert_index(2, 0, 0), 0);
+        assert_eq!(hilbert_index(2, 0, 1), 1);
+        assert_eq!(hilbert_index(2, 1, 1), 2);
+        assert_eq!(hilbert_index(2, 1, 0), 3);
+
+        let tiles = generate(100, 70, 16, TileOrder::Hilbert);
+        assert_coverage(&tiles, 100, 70);
+
+        // Consecutive tiles along Hilbert curve are always neighbours.
+        let grid = generate(64, 64, 8, TileOrder::Hilbert);
+
+        for pair in grid.windows(2)
+        {
+            let distance = pair[0].x.abs_diff(pair[1].x) + pair[0].y.abs_diff(pair[1].y);
+            assert_eq!(distance, 8);
+        }
+    }
+}
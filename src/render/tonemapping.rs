@@ -0,0 +1,95 @@
+use serde::{ Serialize, Deserialize };
+use super::math::Vec4;
+use super::image;
+
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum ToneCurve
+{
+    #[default]
+    Clamp,
+    Reinhard,
+    Aces
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ToneMapping
+{
+    // Exposure adjustment in stops, where each one doubles brightness.
+    #[serde(default)]
+    pub exposure: f32,
+
+    #[serde(default)]
+    pub curve: ToneCurve
+}
+
+impl ToneCurve
+{
+    fn apply(self, value: f32) -> f32
+    {
+        match self
+        {
+            Self::Clamp => value,
+            Self::Reinhard => value / (1.0 + value),
+            Self::Aces =>
+            {
+                // Curve fitted to ACES reference rendering transform.
+                // Implementation based on: https://knarkowicz.wordpress.com/2016/01/06/aces-filmic-tone-mapping-curve/
+                (value * (2.51 * value + 0.03)) / (value * (2.43 * value + 0.59) + 0.14)
+            }
+        }.clamp(0.0, 1.0)
+    }
+}
+
+impl ToneMapping
+{
+    pub fn new(exposure: f32, curve: ToneCurve) -> Self
+    {
+        Self
+        {
+            exposure,
+            curve
+        }
+    }
+
+    pub fn map(&self, color: Vec4) -> Vec4
+    {
+        // Linear color is exposed and compressed into displayable range, then encoded in sRGB.
+        let scale = 2.0_f32.powf(self.exposure);
+        let curve = |value: f32| self.curve.apply((value * scale).max(0.0));
+
+        let mapped = Vec4::new(curve(color.get_r()), curve(color.get_g()), curve(color.get_b()), color.get_a().clamp(0.0, 1.0));
+        image::color::linear_to_srgb(mapped).clamped()
+    }
+
+    pub fn apply(&self, surface: &image::Surface) -> image::Surface
+    {
+        let pixels = surface.as_pixel_slice().iter().map(|pixel| self.map(*pixel)).collect();
+        image::Surface::from(surface.get_width(), surface.get_height(), pixels)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn map()
+    {
+        let bright = Vec4::new(4.0, 1.0, 0.0, 1.0);
+
+        assert_eq!(ToneMapping::default().map(bright).as_quantized_u8_array(), [255, 255, 0, 255]);
+        assert_eq!(ToneMapping::default().map(Vec4::new(-1.0, 0.0, 0.0, 1.0)), Vec4::black());
+
+        // Curves keep bright values apart instead of clipping them.
+        for curve in &[ToneCurve::Reinhard, ToneCurve::Aces]
+        {
+            let mapped = ToneMapping::new(0.0, *curve).map(bright);
+            assert!(mapped.get_r() > mapped.get_g() && mapped.get_r() < 1.0);
+        }
+
+        // Each stop of exposure doubles linear value before encoding.
+        let exposed = ToneMapping::new(1.0, ToneCurve::Clamp).map(Vec4::new(0.25, 0.25, 0.25, 1.0));
+        assert!((image::color::srgb_to_linear(exposed).get_r() - 0.5).abs() < 0.0001);
+    }
+}
@@ -0,0 +1,169 @@
+mod distributed
+{
+    use std::io::BufReader;
+    use std::net::{ TcpListener, TcpStream };
+    use std::time::Duration;
+    use raytracer::math::Vec3;
+    use raytracer::math::Vec4;
+    use raytracer::render;
+    use render::Object;
+    use render::objects;
+    use render::materials;
+    use render::lights;
+    use render::distributed::{ Coordinator, Worker, Message, read_message, write_message };
+
+    fn create_setup() -> render::Setup
+    {
+        let parameters = render::Parameters
+        {
+            image_width: 64,
+            image_height: 36,
+            antialias_samples: 2,
+            scatter_limit: 8,
+            tile_size: 8,
+            filter: render::Filter::Mitchell { radius: 2.0 },
+            ..render::Parameters::default()
+        };
+
+        let scene = render::Scene::new()
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -100.5), 100.0, materials::Diffuse::new(Vec4::new(0.8, 0.8, 0.8, 1.0)))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -0.1), 0.4, materials::Metallic::new(Vec4::new(0.9, 0.6, 0.3, 1.0), 0.3))))
+            .add_light(lights::Point::new(Vec3::new(1.0, 0.0, 1.0), Vec4::new(1.0, 1.0, 1.0, 1.0), 2.0));
+
+        render::Setup
+        {
+            parameters,
+            scene
+        }
+    }
+
+    fn render_locally(setup: &render::Setup) -> Vec<Vec4>
+    {
+        render::Renderer::new()
+            .set_parameters(&setup.parameters)
+            .set_scene(&setup.scene)
+            .render().expect("Rendering failed!").as_pixel_slice().to_vec()
+    }
+
+    #[test]
+    fn render()
+    {
+        let setup = create_setup();
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Binding listener failed!");
+        let address = listener.local_addr().unwrap();
+
+        let workers: Vec<_> = (0..2).map(|_| std::thread::spawn(move ||
+        {
+            Worker::new().set_thread_count(2).run(address).expect("Worker failed!")
+        })).collect();
+
+        let image = Coordinator::new().set_setup(&setup).render(&listener).expect("Distributed render failed!");
+        let tile_count: usize = workers.into_iter().map(|worker| worker.join().unwrap()).sum();
+
+        // Tiles merged from workers must form the same image as the one rendered locally.
+        assert_eq!(tile_count, 8 * 5);
+        assert!(image.as_pixel_slice() == render_locally(&setup).as_slice(), "Distributed image differs from local one!");
+
+        // Workers do not send output variables, so setup that needs them is rejected before any worker connects.
+        let mut aov_setup = create_setup();
+        aov_setup.parameters.aovs = vec![render::Aov::Depth];
+        assert!(Coordinator::new().set_setup(&aov_setup).render(&listener).is_err());
+    }
+
+    #[test]
+    fn reassign()
+    {
+        let setup = create_setup();
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Binding listener failed!");
+        let address = listener.local_addr().unwrap();
+
+        let workers = std::thread::spawn(move ||
+        {
+            // First worker takes several tiles and disconnects without rendering any of them.
+            let stream = TcpStream::connect(address).expect("Connecting failed!");
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut writer = stream;
+
+            write_message(&mut writer, &Message::Hello { thread_count: 4 }).unwrap();
+            assert!(matches!(read_message(&mut reader).unwrap(), Message::Setup { .. }));
+
+            for _ in 0..4
+            {
+                assert!(matches!(read_message(&mut reader).unwrap(), Message::Tile { .. }));
+            }
+
+            drop(reader);
+            drop(writer);
+
+            Worker::new().set_thread_count(2).run(address).expect("Worker failed!")
+        });
+
+        let image = Coordinator::new().set_setup(&setup).render(&listener).expect("Distributed render failed!");
+
+        // Remaining worker must render all tiles, including those abandoned by the first one.
+        assert_eq!(workers.join().unwrap(), 8 * 5);
+        assert!(image.as_pixel_slice() == render_locally(&setup).as_slice(), "Distributed image differs from local one!");
+    }
+
+    #[test]
+    fn timeout()
+    {
+        let setup = create_setup();
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Binding listener failed!");
+        let address = listener.local_addr().unwrap();
+
+        let workers = std::thread::spawn(move ||
+        {
+            // First worker takes several tiles and stops responding, while keeping its connection open.
+            let stream = TcpStream::connect(address).expect("Connecting failed!");
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut writer = stream;
+
+            write_message(&mut writer, &Message::Hello { thread_count: 4 }).unwrap();
+            assert!(matches!(read_message(&mut reader).unwrap(), Message::Setup { .. }));
+
+            for _ in 0..4
+            {
+                assert!(matches!(read_message(&mut reader).unwrap(), Message::Tile { .. }));
+            }
+
+            let worker = std::thread::spawn(move || Worker::new().set_thread_count(2).run(address).expect("Worker failed!"));
+
+            // Coordinator closes connection once the worker times out.
+            assert!(read_message(&mut reader).is_err());
+            worker.join().unwrap()
+        });
+
+        let image = Coordinator::new().set_setup(&setup).set_timeout(Duration::from_millis(500)).render(&listener).expect("Distributed render failed!");
+
+        // Tiles of unresponsive worker must be rendered by the other one.
+        assert_eq!(workers.join().unwrap(), 8 * 5);
+        assert!(image.as_pixel_slice() == render_locally(&setup).as_slice(), "Distributed image differs from local one!");
+    }
+
+    #[test]
+    fn failed_tile()
+    {
+        let setup = create_setup();
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Binding listener failed!");
+        let address = listener.local_addr().unwrap();
+
+        let worker = std::thread::spawn(move || Worker::new().set_thread_count(2).run(address));
+
+        // Coordinator assigns tile outside of the image, which worker cannot render.
+        let (stream, _) = listener.accept().expect("Accepting worker failed!");
+        stream.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut writer = stream;
+
+        assert!(matches!(read_message(&mut reader).unwrap(), Message::Hello { thread_count: 2 }));
+        write_message(&mut writer, &Message::Setup { setup: serde_json::to_string(&setup).unwrap(), directory: std::path::PathBuf::new() }).unwrap();
+        write_message(&mut writer, &Message::Tile { index: 0, tile: render::tiles::Tile { x: 60, y: 0, width: 8, height: 8 } }).unwrap();
+
+        // Worker closes connection right away, instead of leaving coordinator to wait for timeout.
+        let error = read_message(&mut reader).err().expect("Worker kept connection open!");
+        assert_ne!(error.kind(), std::io::ErrorKind::WouldBlock);
+        assert!(worker.join().unwrap().is_err());
+    }
+}
@@ -1,5 +1,6 @@
 mod examples
 {
+    use raytracer::math;
     use raytracer::math::Vec3;
     use raytracer::math::Vec4;
     use raytracer::image;
@@ -7,6 +8,8 @@ mod examples
     use render::Object;
     use render::objects;
     use render::materials;
+    use render::lights;
+    use render::textures;
 
     fn save_and_test_example(name: &str, parameters: render::Parameters, scene: render::Scene)
     {
@@ -20,10 +23,13 @@ mod examples
             scene
         };
 
-        setup.save(save_path).expect("Saving setup file failed!");
+        setup.save(&save_path).expect("Saving setup file failed!");
 
         let _ = std::fs::remove_dir_all(render_dir);
 
+        // Render setup loaded back from file, so serialization and referenced resources are tested too.
+        let setup = render::Setup::from_file(&save_path).expect("Loading setup file failed!");
+
         let test_parameters = render::Parameters
         {
             image_width: setup.parameters.image_width / 16,
@@ -36,10 +42,19 @@ mod examples
         let image = render::Renderer::new()
             .set_parameters(&test_parameters)
             .set_scene(&setup.scene)
-            .render();
+            .render().expect("Rendering failed!");
+
+        // Rendering must be reproducible regardless of how work is split between threads.
+        let single_threaded_image = render::Renderer::new()
+            .set_parameters(&test_parameters)
+            .set_scene(&setup.scene)
+            .set_thread_count(1)
+            .render().expect("Rendering failed!");
+
+        assert!(image.as_pixel_slice() == single_threaded_image.as_pixel_slice(), "Rendered image depends on thread count!");
 
         image::Writer::new(image::FormatPNG::new())
-            .input(&image).output(render_path)
+            .input(&test_parameters.tone_mapping.apply(&image)).output(render_path)
             .save().expect("Failed to save rendered image!");
     }
 
@@ -174,6 +189,855 @@ mod examples
         save_and_test_example("velocity", parameters, scene);
     }
 
+    #[test]
+    fn mesh()
+    {
+        let parameters = render::Parameters
+        {
+            image_width: 1024,
+            image_height: 576,
+            antialias_samples: 16,
+            scatter_limit: 16,
+            ..render::Parameters::default()
+        };
+
+        let camera = render::camera::Parameters::new()
+            .set_origin(Vec3::new(1.5, -2.5, 1.0))
+            .set_look_at(Some(Vec3::new(0.0, 0.0, 0.2)))
+            .set_field_of_view(45.0);
+
+        let positions = vec!
+        [
+            Vec3::new(-0.6, -0.6, -0.5),
+            Vec3::new(0.6, -0.6, -0.5),
+            Vec3::new(0.6, 0.6, -0.5),
+            Vec3::new(-0.6, 0.6, -0.5),
+            Vec3::new(0.0, 0.0, 0.9)
+        ];
+
+        let indices = vec!
+        [
+            0, 1, 4,
+            1, 2, 4,
+            2, 3, 4,
+            3, 0, 4,
+            0, 2, 1,
+            0, 3, 2
+        ];
+
+        let scene = render::Scene::new()
+            .set_camera(camera)
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -100.5), 100.0, materials::Diffuse::new(Vec4::new(0.8, 0.8, 0.0, 1.0)))))
+            .add_object(Object::new(objects::Mesh::new(positions, None, None, indices, materials::Diffuse::new(Vec4::new(0.3, 0.3, 0.8, 1.0)))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(-1.2, 0.6, -0.1), 0.4, materials::Metallic::new(Vec4::new(0.8, 0.8, 0.8, 1.0), 0.1))));
+
+        save_and_test_example("mesh", parameters, scene);
+    }
+
+    #[test]
+    fn model()
+    {
+        let parameters = render::Parameters
+        {
+            image_width: 1024,
+            image_height: 576,
+            antialias_samples: 16,
+            scatter_limit: 16,
+            ..render::Parameters::default()
+        };
+
+        let camera = render::camera::Parameters::new()
+            .set_origin(Vec3::new(1.6, -2.4, 1.2))
+            .set_look_at(Some(Vec3::new(0.0, 0.0, 0.0)))
+            .set_field_of_view(45.0);
+
+        let scene = render::Scene::new()
+            .set_camera(camera)
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -100.5), 100.0, materials::Diffuse::new(Vec4::new(0.8, 0.8, 0.0, 1.0)))))
+            .add_object(Object::new(objects::Model::new("models/cube.obj")));
+
+        save_and_test_example("model", parameters, scene);
+    }
+
+    #[test]
+    fn emissive()
+    {
+        let parameters = render::Parameters
+        {
+            image_width: 1024,
+            image_height: 576,
+            antialias_samples: 16,
+            scatter_limit: 16,
+            ..render::Parameters::default()
+        };
+
+        let camera = render::camera::Parameters::new()
+            .set_origin(Vec3::new(0.0, -0.6, 0.0))
+            .set_look_at(Some(Vec3::new(0.0, 1.0, -0.2)))
+            .set_field_of_view(55.0);
+
+        let scene = render::Scene::new()
+            .set_camera(camera)
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.4, 0.0), 0.5, materials::Emissive::new(Vec4::new(1.0, 0.6, 0.2, 1.0), 4.0))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.8, 1.0, -0.1), 0.4, materials::Metallic::new(Vec4::new(0.8, 0.8, 0.8, 1.0), 0.0))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(-0.8, 1.0, -0.1), 0.4, materials::Emissive::new_reflective(Vec4::new(0.3, 0.3, 0.8, 1.0), Vec4::new(0.2, 0.2, 1.0, 1.0), 0.5))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -100.5), 100.0, materials::Diffuse::new(Vec4::new(0.5, 0.5, 0.5, 1.0)))));
+
+        save_and_test_example("emissive", parameters, scene);
+    }
+
+    #[test]
+    fn environment()
+    {
+        let parameters = render::Parameters
+        {
+            image_width: 1024,
+            image_height: 576,
+            antialias_samples: 16,
+            scatter_limit: 16,
+            ..render::Parameters::default()
+        };
+
+        let camera = render::camera::Parameters::new()
+            .set_origin(Vec3::new(0.0, -0.6, 0.0))
+            .set_look_at(Some(Vec3::new(0.0, 1.0, -0.2)))
+            .set_field_of_view(55.0);
+
+        let scene = render::Scene::new()
+            .set_camera(camera)
+            .set_environment(render::Environment::Image(render::environment::Image::new("environments/sky.hdr", 90.0, 1.0)))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -0.1), 0.4, materials::Metallic::new(Vec4::new(0.9, 0.9, 0.9, 1.0), 0.0))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.8, 1.0, -0.1), 0.4, materials::Diffuse::new(Vec4::new(0.8, 0.3, 0.3, 1.0)))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(-0.8, 1.0, -0.1), 0.4, materials::Refractive::new(Vec4::new(1.0, 1.0, 1.0, 1.0), 1.5))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -100.5), 100.0, materials::Diffuse::new(Vec4::new(0.5, 0.5, 0.5, 1.0)))));
+
+        save_and_test_example("environment", parameters, scene);
+    }
+
+    #[test]
+    fn tone_mapping()
+    {
+        // Bright emitters and sky are compressed by filmic curve, instead of being clipped at white.
+        let parameters = render::Parameters
+        {
+            image_width: 1024,
+            image_height: 576,
+            antialias_samples: 16,
+            scatter_limit: 16,
+            tone_mapping: render::ToneMapping::new(0.0, render::ToneCurve::Aces),
+            ..render::Parameters::default()
+        };
+
+        let camera = render::camera::Parameters::new()
+            .set_origin(Vec3::new(0.0, -0.6, 0.0))
+            .set_look_at(Some(Vec3::new(0.0, 1.0, -0.2)))
+            .set_field_of_view(55.0);
+
+        let scene = render::Scene::new()
+            .set_camera(camera)
+            .set_environment(render::Environment::Image(render::environment::Image::new("environments/sky.hdr", 90.0, 1.0)))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -0.1), 0.4, materials::Emissive::new(Vec4::new(1.0, 0.6, 0.2, 1.0), 4.0))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.8, 1.0, -0.1), 0.4, materials::Metallic::new(Vec4::new(0.9, 0.9, 0.9, 1.0), 0.0))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(-0.8, 1.0, -0.1), 0.4, materials::Diffuse::new(Vec4::new(0.8, 0.3, 0.3, 1.0)))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -100.5), 100.0, materials::Diffuse::new(Vec4::new(0.5, 0.5, 0.5, 1.0)))));
+
+        save_and_test_example("tone_mapping", parameters, scene);
+    }
+
+    #[test]
+    fn lights()
+    {
+        let parameters = render::Parameters
+        {
+            image_width: 1024,
+            image_height: 576,
+            antialias_samples: 16,
+            scatter_limit: 16,
+            ..render::Parameters::default()
+        };
+
+        let camera = render::camera::Parameters::new()
+            .set_origin(Vec3::new(0.0, -0.6, 0.0))
+            .set_look_at(Some(Vec3::new(0.0, 1.0, -0.2)))
+            .set_field_of_view(55.0);
+
+        let scene = render::Scene::new()
+            .set_camera(camera)
+            .set_environment(render::Environment::Color(Vec4::new(0.02, 0.02, 0.03, 1.0)))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -0.1), 0.4, materials::Diffuse::new(Vec4::new(0.8, 0.8, 0.8, 1.0)))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.8, 1.0, -0.1), 0.4, materials::Metallic::new(Vec4::new(0.8, 0.6, 0.2, 1.0), 0.1))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(-0.8, 1.0, -0.1), 0.4, materials::Diffuse::new(Vec4::new(0.3, 0.3, 0.8, 1.0)))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -100.5), 100.0, materials::Diffuse::new(Vec4::new(0.5, 0.5, 0.5, 1.0)))))
+            .add_light(lights::Sphere::new(Vec3::new(0.0, 0.4, 0.6), 0.05, Vec4::new(1.0, 0.9, 0.8, 1.0), 200.0))
+            .add_light(lights::Spot::new(Vec3::new(-1.2, 0.6, 1.0), Vec3::new(0.4, 0.4, -1.0), Vec4::new(0.2, 0.4, 1.0, 1.0), 3.0, 15.0, 30.0))
+            .add_light(lights::Point::new(Vec3::new(1.4, 1.6, 0.4), Vec4::new(1.0, 0.5, 0.2, 1.0), 0.8))
+            .add_light(lights::Directional::new(Vec3::new(-1.0, 1.0, -1.0), Vec4::new(0.6, 0.6, 1.0, 1.0), 0.1));
+
+        save_and_test_example("lights", parameters, scene);
+    }
+
+    #[test]
+    fn instances()
+    {
+        let parameters = render::Parameters
+        {
+            image_width: 1024,
+            image_height: 576,
+            antialias_samples: 16,
+            scatter_limit: 16,
+            ..render::Parameters::default()
+        };
+
+        let camera = render::camera::Parameters::new()
+            .set_origin(Vec3::new(0.0, -3.0, 1.2))
+            .set_look_at(Some(Vec3::new(0.0, 0.0, -0.2)))
+            .set_field_of_view(50.0);
+
+        let mut scene = render::Scene::new()
+            .set_camera(camera)
+            .add_geometry("cube", objects::Model::new("models/cube.obj"))
+            .add_geometry("ball", objects::Sphere::new(Vec3::zero(), 0.5, materials::Metallic::new(Vec4::new(0.8, 0.8, 0.8, 1.0), 0.05)))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 0.0, -100.5), 100.0, materials::Diffuse::new(Vec4::new(0.5, 0.5, 0.5, 1.0)))))
+            .add_object(Object::new_transformed(objects::Instance::new("ball"), math::Transform::new()
+                .set_translation(Vec3::new(0.0, 0.0, -0.1))
+                .set_rotation(Vec3::new(0.0, 0.0, 30.0))
+                .set_scale(Vec3::new(1.6, 0.8, 0.8))));
+
+        for index in 0..24
+        {
+            let angle = index as f32 * 15.0;
+            let position = Vec3::new(angle.to_radians().cos(), angle.to_radians().sin(), 0.0) * 1.5 + Vec3::new(0.0, 0.0, -0.35);
+
+            scene = scene.add_object(Object::new_transformed(objects::Instance::new("cube"), math::Transform::new()
+                .set_translation(position)
+                .set_rotation(Vec3::new(0.0, index as f32 * 10.0, angle))
+                .set_scale(Vec3::one() * 0.25)));
+        }
+
+        save_and_test_example("instances", parameters, scene);
+    }
+
+    #[test]
+    fn textures()
+    {
+        let parameters = render::Parameters
+        {
+            image_width: 1024,
+            image_height: 576,
+            antialias_samples: 16,
+            scatter_limit: 16,
+            ..render::Parameters::default()
+        };
+
+        let camera = render::camera::Parameters::new()
+            .set_origin(Vec3::new(0.0, -0.6, 0.0))
+            .set_look_at(Some(Vec3::new(0.0, 1.0, -0.2)))
+            .set_field_of_view(55.0);
+
+        let ground_positions = vec!
+        [
+            Vec3::new(-8.0, -8.0, -0.5),
+            Vec3::new(8.0, -8.0, -0.5),
+            Vec3::new(8.0, 8.0, -0.5),
+            Vec3::new(-8.0, 8.0, -0.5)
+        ];
+
+        let ground_uvs = vec!
+        [
+            math::Vec2::new(0.0, 0.0),
+            math::Vec2::new(16.0, 0.0),
+            math::Vec2::new(16.0, 16.0),
+            math::Vec2::new(0.0, 16.0)
+        ];
+
+        let ground_material = materials::Diffuse::new(textures::Checker::new(Vec4::new(0.8, 0.8, 0.8, 1.0), Vec4::new(0.2, 0.2, 0.2, 1.0), 1.0));
+
+        let scene = render::Scene::new()
+            .set_camera(camera)
+            .add_object(Object::new(objects::Mesh::new(ground_positions, None, Some(ground_uvs), vec![0, 1, 2, 0, 2, 3], ground_material)))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -0.1), 0.4, materials::Diffuse::new(textures::Bitmap::new("textures/grid.png", textures::Wrap::Repeat)))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.8, 1.0, -0.1), 0.4, materials::Metallic::new(Vec4::new(0.8, 0.8, 0.8, 1.0), 0.0))))
+            .add_object(Object::new_transformed(objects::Sphere::new(Vec3::zero(), 0.4, materials::Diffuse::new(textures::Bitmap::new("textures/bricks.ppm", textures::Wrap::Mirror))),
+                math::Transform::new().set_translation(Vec3::new(-0.8, 1.0, -0.1)).set_rotation(Vec3::new(0.0, 0.0, 45.0))));
+
+        save_and_test_example("textures", parameters, scene);
+    }
+
+    #[test]
+    fn noise()
+    {
+        let parameters = render::Parameters
+        {
+            image_width: 1024,
+            image_height: 576,
+            antialias_samples: 16,
+            scatter_limit: 16,
+            ..render::Parameters::default()
+        };
+
+        let camera = render::camera::Parameters::new()
+            .set_origin(Vec3::new(0.0, -0.6, 0.0))
+            .set_look_at(Some(Vec3::new(0.0, 1.0, -0.2)))
+            .set_field_of_view(55.0);
+
+        let scene = render::Scene::new()
+            .set_camera(camera)
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -100.5), 100.0, materials::Diffuse::new(textures::Perlin::new(1, 8.0, Vec4::new(0.8, 0.8, 0.8, 1.0))))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(-0.8, 1.0, -0.1), 0.4, materials::Diffuse::new(textures::Turbulence::new(2, 4.0, 7, Vec4::new(0.9, 0.5, 0.3, 1.0))))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -0.1), 0.4, materials::Diffuse::new(textures::Marble::new(3, 12.0, Vec4::new(0.9, 0.9, 0.9, 1.0))))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.8, 1.0, -0.1), 0.4, materials::Metallic::new(textures::Marble::new(4, 20.0, Vec4::new(0.9, 0.8, 0.4, 1.0)), 0.1))));
+
+        save_and_test_example("noise", parameters, scene);
+    }
+
+    #[test]
+    fn sampling()
+    {
+        let parameters = render::Parameters
+        {
+            image_width: 1024,
+            image_height: 576,
+            antialias_samples: 4,
+            scatter_limit: 16,
+            sampler: render::Sampler::Sobol,
+            ..render::Parameters::default()
+        };
+
+        let camera = render::camera::Parameters::new()
+            .set_origin(Vec3::new(0.0, -0.6, 0.0))
+            .set_look_at(Some(Vec3::new(0.0, 1.0, -0.2)))
+            .set_field_of_view(55.0)
+            .set_focus_on_look_at(1.0)
+            .set_aperture_size(0.02);
+
+        // Fine checker pattern on the ground aliases badly with regular subpixel grid.
+        let ground_positions = vec!
+        [
+            Vec3::new(-20.0, -20.0, -0.5),
+            Vec3::new(20.0, -20.0, -0.5),
+            Vec3::new(20.0, 20.0, -0.5),
+            Vec3::new(-20.0, 20.0, -0.5)
+        ];
+
+        let ground_uvs = vec!
+        [
+            math::Vec2::new(0.0, 0.0),
+            math::Vec2::new(1.0, 0.0),
+            math::Vec2::new(1.0, 1.0),
+            math::Vec2::new(0.0, 1.0)
+        ];
+
+        let ground_material = materials::Diffuse::new(textures::Checker::new(Vec4::new(0.9, 0.9, 0.9, 1.0), Vec4::new(0.1, 0.1, 0.1, 1.0), 160.0));
+
+        let scene = render::Scene::new()
+            .set_camera(camera)
+            .add_object(Object::new(objects::Mesh::new(ground_positions, None, Some(ground_uvs), vec![0, 1, 2, 0, 2, 3], ground_material)))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(-0.8, 1.0, -0.1), 0.4, materials::Metallic::new(Vec4::new(0.8, 0.6, 0.2, 1.0), 0.3))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -0.1), 0.4, materials::Refractive::new(Vec4::new(1.0, 1.0, 1.0, 1.0), 1.5))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.8, 1.0, -0.1), 0.4, materials::Diffuse::new(Vec4::new(0.8, 0.3, 0.3, 1.0)))))
+            .add_light(lights::Sphere::new(Vec3::new(0.0, 0.2, 1.5), 0.3, Vec4::new(1.0, 0.9, 0.8, 1.0), 4.0));
+
+        save_and_test_example("sampling", parameters, scene);
+    }
+
+    #[test]
+    fn progressive()
+    {
+        let parameters = render::Parameters
+        {
+            image_width: 64,
+            image_height: 36,
+            antialias_samples: 4,
+            scatter_limit: 16,
+            ..render::Parameters::default()
+        };
+
+        let camera = render::camera::Parameters::new()
+            .set_origin(Vec3::new(0.0, -0.6, 0.0))
+            .set_look_at(Some(Vec3::new(0.0, 1.0, -0.2)))
+            .set_field_of_view(55.0);
+
+        let scene = render::Scene::new()
+            .set_camera(camera)
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -100.5), 100.0, materials::Diffuse::new(Vec4::new(0.8, 0.8, 0.8, 1.0)))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -0.1), 0.4, materials::Refractive::new(Vec4::new(1.0, 1.0, 1.0, 1.0), 1.5))))
+            .add_light(lights::Point::new(Vec3::new(1.0, 0.0, 1.0), Vec4::new(1.0, 1.0, 1.0, 1.0), 2.0));
+
+        let renderer = render::Renderer::new()
+            .set_parameters(&parameters)
+            .set_scene(&scene);
+
+        // Splitting samples into passes must produce the same image as rendering all of them at once.
+        let mut pass_count = 0;
+
+        let progressive_image = renderer.render_progressive(3, |pass_index, image|
+        {
+            pass_count += 1;
+            assert_eq!(pass_index, pass_count);
+            assert_eq!(image.get_pixel_count(), 64 * 36);
+        }).expect("Rendering failed!");
+
+        assert_eq!(pass_count, 6);
+        assert!(progressive_image.as_pixel_slice() == renderer.render().expect("Rendering failed!").as_pixel_slice(), "Progressive image differs from regular one!");
+    }
+
+    #[test]
+    fn resume()
+    {
+        let parameters = render::Parameters
+        {
+            image_width: 64,
+            image_height: 36,
+            antialias_samples: 3,
+            scatter_limit: 16,
+            sampler: render::Sampler::Stratified,
+            filter: render::Filter::Gaussian { radius: 1.5 },
+            ..render::Parameters::default()
+        };
+
+        let scene = render::Scene::new()
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -100.5), 100.0, materials::Diffuse::new(Vec4::new(0.8, 0.8, 0.8, 1.0)))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -0.1), 0.4, materials::Metallic::new(Vec4::new(0.9, 0.6, 0.3, 1.0), 0.3))))
+            .add_light(lights::Point::new(Vec3::new(1.0, 0.0, 1.0), Vec4::new(1.0, 1.0, 1.0, 1.0), 2.0));
+
+        let checkpoint_dir = "target/tests/checkpoint";
+        let checkpoint_path = format!("{}/resume.checkpoint", checkpoint_dir);
+        std::fs::create_dir_all(checkpoint_dir).expect("Creating checkpoint directory failed!");
+
+        // Render is interrupted after two passes, which writes checkpoint on cancellation.
+        let cancellation_token = render::CancellationToken::new();
+
+        render::Renderer::new()
+            .set_parameters(&parameters)
+            .set_scene(&scene)
+            .set_cancellation_token(cancellation_token.clone())
+            .set_checkpoint(&checkpoint_path, std::time::Duration::from_secs(3600))
+            .render_progressive(2, |pass_index, _|
+            {
+                if pass_index == 2
+                {
+                    cancellation_token.cancel();
+                }
+            }).expect("Rendering failed!");
+
+        let checkpoint = render::Checkpoint::load(checkpoint_path.as_ref(), &parameters).expect("Loading checkpoint failed!");
+        assert_eq!(checkpoint.pass_count, 2);
+        assert!(checkpoint.matches(&parameters, &scene));
+
+        // Resumed render must finish with the same image as uninterrupted one.
+        let mut pass_count = 0;
+
+        let resumed_image = render::Renderer::new()
+            .set_parameters(&parameters)
+            .set_scene(&scene)
+            .set_resume_checkpoint(&checkpoint)
+            .render_progressive(2, |pass_index, _| pass_count = pass_index).expect("Rendering failed!");
+
+        let image = render::Renderer::new()
+            .set_parameters(&parameters)
+            .set_scene(&scene)
+            .render().expect("Rendering failed!");
+
+        assert_eq!(pass_count, 5);
+        assert!(resumed_image.as_pixel_slice() == image.as_pixel_slice(), "Resumed image differs from regular one!");
+
+        let changed_parameters = render::Parameters
+        {
+            seed: 1,
+            ..parameters
+        };
+
+        assert!(!checkpoint.matches(&changed_parameters, &scene));
+
+        // Checkpoint of different setup is refused instead of mixing unrelated samples into the image.
+        let changed_render = render::Renderer::new()
+            .set_parameters(&changed_parameters)
+            .set_scene(&scene)
+            .set_resume_checkpoint(&checkpoint)
+            .render();
+
+        assert!(matches!(changed_render, Err(render::renderer::Error::CheckpointMismatch)));
+    }
+
+    #[test]
+    fn roulette()
+    {
+        let parameters = |roulette_depth: u16| render::Parameters
+        {
+            image_width: 64,
+            image_height: 36,
+            antialias_samples: 6,
+            scatter_limit: 32,
+            roulette_depth,
+            ..render::Parameters::default()
+        };
+
+        let scene = render::Scene::new()
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -100.5), 100.0, materials::Diffuse::new(Vec4::new(0.9, 0.9, 0.9, 1.0)))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -0.1), 0.4, materials::Diffuse::new(Vec4::new(0.9, 0.6, 0.3, 1.0)))))
+            .set_environment(render::Environment::Color(Vec4::new(0.6, 0.7, 0.9, 1.0)));
+
+        let average_luminance = |parameters: &render::Parameters|
+        {
+            let image = render::Renderer::new()
+                .set_parameters(parameters)
+                .set_scene(&scene)
+                .render().expect("Rendering failed!");
+
+            image.as_pixel_slice().iter().map(Vec4::luminance).sum::<f32>() / image.get_pixel_count() as f32
+        };
+
+        // Terminating paths early must not change average brightness of the image.
+        let roulette_luminance = average_luminance(&parameters(1));
+        let full_luminance = average_luminance(&parameters(33));
+
+        assert!((roulette_luminance / full_luminance - 1.0).abs() < 0.01, "Roulette biases the image!");
+    }
+
+    #[test]
+    fn invalid_setup()
+    {
+        // Problems that would stop render threads are reported when setup is loaded.
+        let load = |scene: render::Scene|
+        {
+            let setup = render::Setup
+            {
+                parameters: render::Parameters::default(),
+                scene
+            };
+
+            render::Setup::from_reader(serde_json::to_vec(&setup).expect("Serializing setup failed!").as_slice(), std::path::Path::new("examples")).err()
+        };
+
+        // Scenes built in code are checked by renderer, before any rendering starts.
+        let render = |scene: render::Scene| render::Renderer::new()
+            .set_parameters(&render::Parameters { image_width: 8, image_height: 8, ..render::Parameters::default() })
+            .set_scene(&scene)
+            .render().err();
+
+        let mesh_scene = || render::Scene::new()
+            .add_object(Object::new(objects::Mesh::new(vec![Vec3::zero(), Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)], None, None, vec![0, 1, 3], materials::Diffuse::new(Vec4::white()))));
+
+        assert!(matches!(load(mesh_scene()), Some(render::setup::Error::InvalidMesh)));
+        assert!(matches!(render(mesh_scene()), Some(render::renderer::Error::InvalidSetup(render::setup::Error::InvalidMesh))));
+
+        let flat_scene = || render::Scene::new()
+            .add_object(Object::new_transformed(objects::Sphere::new(Vec3::zero(), 0.5, materials::Diffuse::new(Vec4::white())), math::Transform::new().set_scale(Vec3::new(1.0, 0.0, 1.0))));
+
+        assert!(matches!(load(flat_scene()), Some(render::setup::Error::InvalidTransform)));
+        assert!(matches!(render(flat_scene()), Some(render::renderer::Error::InvalidSetup(render::setup::Error::InvalidTransform))));
+
+        let sky_scene = || render::Scene::new()
+            .set_environment(render::Environment::Image(render::environment::Image::new("environments/sky.hdr", 0.0, 1.0)));
+
+        assert!(load(sky_scene()).is_none());
+        assert!(matches!(render(sky_scene()), Some(render::renderer::Error::InvalidSetup(render::setup::Error::UnloadedImage))));
+
+        let textured_scene = || render::Scene::new()
+            .add_object(Object::new(objects::Sphere::new(Vec3::zero(), 0.5, materials::Diffuse::new(textures::Bitmap::new("textures/grid.png", textures::Wrap::Repeat)))));
+
+        assert!(load(textured_scene()).is_none());
+        assert!(matches!(render(textured_scene()), Some(render::renderer::Error::InvalidSetup(render::setup::Error::UnloadedImage))));
+
+        let model_scene = || render::Scene::new()
+            .add_object(Object::new(objects::Model::new("models/cube.obj")));
+
+        assert!(load(model_scene()).is_none());
+        assert!(matches!(render(model_scene()), Some(render::renderer::Error::InvalidSetup(render::setup::Error::UnloadedModel))));
+
+        let occlusion_setup = render::Setup
+        {
+            parameters: render::Parameters { integrator: render::IntegratorKind::AmbientOcclusion { distance: 0.0 }, ..render::Parameters::default() },
+            scene: render::Scene::new()
+        };
+
+        let occlusion_json = serde_json::to_vec(&occlusion_setup).expect("Serializing setup failed!");
+        assert!(matches!(render::Setup::from_reader(occlusion_json.as_slice(), std::path::Path::new("examples")), Err(render::setup::Error::InvalidParameters)));
+
+        let occlusion_render = render::Renderer::new()
+            .set_parameters(&render::Parameters { image_width: 8, image_height: 8, ..occlusion_setup.parameters })
+            .set_scene(&occlusion_setup.scene)
+            .render();
+
+        assert!(matches!(occlusion_render, Err(render::renderer::Error::InvalidSetup(render::setup::Error::InvalidParameters))));
+    }
+
+    #[test]
+    fn deep_paths()
+    {
+        // Camera inside a mirror sphere, where every path keeps bouncing until it reaches scatter limit.
+        let parameters = render::Parameters
+        {
+            image_width: 8,
+            image_height: 8,
+            antialias_samples: 1,
+            scatter_limit: 20000,
+            roulette_depth: u16::MAX,
+            ..render::Parameters::default()
+        };
+
+        let scene = render::Scene::new()
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 0.0, 0.0), 2.0, materials::Metallic::new(Vec4::new(1.0, 1.0, 1.0, 1.0), 0.0))));
+
+        // Integrator does not recurse per bounce, so long paths cannot overflow the stack of render threads.
+        let image = render::Renderer::new()
+            .set_parameters(&parameters)
+            .set_scene(&scene)
+            .render().expect("Rendering failed!");
+
+        assert!(image.as_pixel_slice().iter().all(Vec4::is_finite));
+    }
+
+    #[test]
+    fn integrators()
+    {
+        let scene = render::Scene::new()
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -100.5), 100.0, materials::Diffuse::new(Vec4::new(0.8, 0.8, 0.8, 1.0)))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -0.1), 0.4, materials::Diffuse::new(Vec4::new(0.9, 0.3, 0.2, 1.0)))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.9, 1.0, -0.1), 0.4, materials::Metallic::new(Vec4::new(0.9, 0.9, 0.9, 1.0), 0.0))))
+            .add_light(lights::Point::new(Vec3::new(1.0, 0.0, 1.0), Vec4::new(1.0, 1.0, 1.0, 1.0), 2.0))
+            .set_environment(render::Environment::Color(Vec4::new(0.6, 0.7, 0.9, 1.0)));
+
+        let render = |integrator: render::IntegratorKind, thread_count: usize| render::Renderer::new()
+            .set_parameters(&render::Parameters
+            {
+                image_width: 64,
+                image_height: 36,
+                antialias_samples: 2,
+                tile_size: 8,
+                integrator,
+                ..render::Parameters::default()
+            })
+            .set_scene(&scene)
+            .set_thread_count(thread_count)
+            .render().expect("Rendering failed!");
+
+        let average_luminance = |image: &image::Surface| image.as_pixel_slice().iter().map(Vec4::luminance).sum::<f32>() / image.get_pixel_count() as f32;
+
+        let integrators = [
+            render::IntegratorKind::AmbientOcclusion { distance: 0.5 },
+            render::IntegratorKind::DirectLighting,
+            render::IntegratorKind::Whitted,
+            render::IntegratorKind::Albedo,
+            render::IntegratorKind::Normals
+        ];
+
+        // Every integrator produces the same image regardless of thread count.
+        for integrator in &integrators
+        {
+            let image = render(*integrator, 1);
+
+            assert!(image.as_pixel_slice().iter().all(Vec4::is_finite));
+            assert_eq!(image.as_pixel_slice(), render(*integrator, 0).as_pixel_slice());
+        }
+
+        // Ambient occlusion is gray and only darkens surfaces near other geometry.
+        let occlusion = render(render::IntegratorKind::AmbientOcclusion { distance: 0.5 }, 0);
+
+        assert!(occlusion.as_pixel_slice().iter().all(|pixel| pixel.get_r() == pixel.get_g() && (0.0..=1.0).contains(&pixel.get_r())));
+        assert!(average_luminance(&occlusion) < 1.0);
+        assert!(average_luminance(&render(render::IntegratorKind::AmbientOcclusion { distance: 0.01 }, 0)) > average_luminance(&occlusion));
+
+        // Albedo preview shows plain color of the sphere in the middle of the image.
+        let center = render(render::IntegratorKind::Albedo, 0).get_pixel(32, 18);
+        assert!([center.get_r() - 0.9, center.get_g() - 0.3, center.get_b() - 0.2].iter().all(|difference| difference.abs() < 1e-4));
+
+        // Surfaces lit only by the light miss indirect light of bright environment.
+        let direct = render(render::IntegratorKind::DirectLighting, 0);
+        assert!(average_luminance(&direct) < average_luminance(&render(render::IntegratorKind::PathTracer, 0)));
+
+        // Mirror is black with direct lighting alone, while Whitted integrator follows its reflections.
+        assert!(average_luminance(&render(render::IntegratorKind::Whitted, 0)) > average_luminance(&direct));
+    }
+
+    #[test]
+    fn filters()
+    {
+        let scene = render::Scene::new()
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -100.5), 100.0, materials::Diffuse::new(Vec4::new(0.8, 0.8, 0.8, 1.0)))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -0.1), 0.4, materials::Diffuse::new(Vec4::new(0.9, 0.3, 0.2, 1.0)))))
+            .add_light(lights::Point::new(Vec3::new(1.0, 0.0, 1.0), Vec4::new(1.0, 1.0, 1.0, 1.0), 2.0));
+
+        let render = |filter: render::Filter, thread_count: usize| render::Renderer::new()
+            .set_parameters(&render::Parameters
+            {
+                image_width: 64,
+                image_height: 36,
+                antialias_samples: 2,
+                tile_size: 8,
+                filter,
+                ..render::Parameters::default()
+            })
+            .set_scene(&scene)
+            .set_thread_count(thread_count)
+            .render().expect("Rendering failed!");
+
+        let box_image = render(render::Filter::default(), 0);
+        let filters = [render::Filter::Tent { radius: 1.5 }, render::Filter::Gaussian { radius: 2.0 }, render::Filter::Mitchell { radius: 2.0 }, render::Filter::Lanczos { radius: 3.0 }];
+
+        // Wider filters blend neighbouring pixels, including ones across tile edges, without depending on thread count.
+        for filter in &filters
+        {
+            let image = render(*filter, 0);
+
+            assert!(image.as_pixel_slice() == render(*filter, 1).as_pixel_slice(), "Filtered image depends on thread count!");
+            assert!(image.as_pixel_slice() != box_image.as_pixel_slice(), "Filter does not change image!");
+            assert!(image.as_pixel_slice().iter().all(|pixel| pixel.is_finite() && pixel.get_r() >= 0.0));
+        }
+    }
+
+    #[test]
+    fn cancel()
+    {
+        let parameters = render::Parameters
+        {
+            image_width: 64,
+            image_height: 36,
+            antialias_samples: 2,
+            tile_size: 8,
+            tile_order: render::TileOrder::Hilbert,
+            ..render::Parameters::default()
+        };
+
+        let scene = render::Scene::new()
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -100.5), 100.0, materials::Diffuse::new(Vec4::new(0.8, 0.8, 0.8, 1.0)))))
+            .add_light(lights::Point::new(Vec3::new(1.0, 0.0, 1.0), Vec4::new(1.0, 1.0, 1.0, 1.0), 2.0));
+
+        // Cancelling render from progress callback must return partial image with some pixels left black.
+        let cancellation_token = render::CancellationToken::new();
+        let callback_token = cancellation_token.clone();
+
+        let renderer = render::Renderer::new()
+            .set_parameters(&parameters)
+            .set_scene(&scene)
+            .set_thread_count(1)
+            .set_cancellation_token(cancellation_token.clone())
+            .set_progress_callback(move |progress|
+            {
+                assert_eq!(progress.tile_count, 8 * 5);
+
+                if progress.tiles_done == 4
+                {
+                    callback_token.cancel();
+                }
+            });
+
+        let image = renderer.render().expect("Rendering failed!");
+        let black_count = image.as_pixel_slice().iter().filter(|pixel| **pixel == Vec4::black()).count();
+
+        assert!(cancellation_token.is_cancelled());
+        assert_eq!(image.get_pixel_count(), 64 * 36);
+        assert_eq!(black_count, 64 * 36 - 4 * 8 * 8);
+    }
+
+    #[test]
+    fn aovs()
+    {
+        let plain_parameters = render::Parameters
+        {
+            image_width: 64,
+            image_height: 36,
+            antialias_samples: 4,
+            ..render::Parameters::default()
+        };
+
+        let parameters = render::Parameters
+        {
+            image_width: 64,
+            image_height: 36,
+            antialias_samples: 4,
+            aovs: vec![render::Aov::Depth, render::Aov::Normal, render::Aov::ObjectIndex, render::Aov::MotionVector, render::Aov::SampleCount],
+            ..render::Parameters::default()
+        };
+
+        let camera = render::camera::Parameters::new()
+            .set_origin(Vec3::new(0.0, -3.0, 0.0))
+            .set_look_at(Some(Vec3::new(0.0, 0.0, 0.0)));
+
+        let scene = render::Scene::new()
+            .set_camera(camera)
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 0.0, 0.0), 1.0, materials::Diffuse::new(Vec4::new(0.8, 0.8, 0.8, 1.0)))))
+            .add_object(Object::new_moving(objects::Sphere::new(Vec3::new(1.5, 0.0, 0.0), 0.4, materials::Diffuse::new(Vec4::new(0.8, 0.8, 0.8, 1.0))), Vec3::new(1.0, 0.0, 0.0)));
+
+        let renderer = render::Renderer::new()
+            .set_parameters(&parameters)
+            .set_scene(&scene);
+
+        let (image, aovs) = renderer.render_with_aovs().expect("Rendering failed!");
+        assert_eq!(aovs.len(), parameters.aovs.len());
+
+        // Output variables must not change rendered image.
+        let plain_image = render::Renderer::new()
+            .set_parameters(&plain_parameters)
+            .set_scene(&scene)
+            .render().expect("Rendering failed!");
+
+        assert!(image.as_pixel_slice() == plain_image.as_pixel_slice(), "Output variables changed rendered image!");
+
+        let (depth, normal, object_index, motion_vector, sample_count) = (&aovs[0], &aovs[1], &aovs[2], &aovs[3], &aovs[4]);
+
+        // Center of the image sees front of the sphere facing the camera.
+        assert!((depth.get_pixel(32, 18).get_r() - 2.0).abs() < 0.05);
+        assert!(normal.get_pixel(32, 18).get_g() < -0.9);
+        assert_eq!(object_index.get_pixel(32, 18).get_r(), 0.0);
+        assert_eq!(motion_vector.get_pixel(32, 18).get_r(), 0.0);
+        assert_eq!(sample_count.get_pixel(32, 18).get_r(), 16.0);
+
+        // Moving sphere is on the side of the image.
+        let moving_pixel = (0..64).find(|x| object_index.get_pixel(*x, 18).get_r() == 1.0).expect("Moving sphere is not visible!");
+        assert!(motion_vector.get_pixel(moving_pixel, 18).get_r().abs() > 1.0);
+
+        // Corner of the image only sees background.
+        assert!(depth.get_pixel(0, 0).get_r().is_infinite());
+        assert_eq!(object_index.get_pixel(0, 0).get_r(), -1.0);
+        assert_eq!(normal.get_pixel(0, 0), Vec4::zero());
+    }
+
+    #[test]
+    fn denoise()
+    {
+        let parameters = |antialias_samples: u16, denoiser: Option<render::Denoiser>| render::Parameters
+        {
+            image_width: 64,
+            image_height: 36,
+            antialias_samples,
+            aovs: vec![render::Aov::Depth],
+            denoiser,
+            ..render::Parameters::default()
+        };
+
+        let scene = render::Scene::new()
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -100.5), 100.0, materials::Diffuse::new(Vec4::new(0.8, 0.8, 0.8, 1.0)))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -0.1), 0.4, materials::Diffuse::new(Vec4::new(0.9, 0.3, 0.2, 1.0)))))
+            .set_environment(render::Environment::Color(Vec4::new(0.6, 0.7, 0.9, 1.0)));
+
+        let noisy_parameters = parameters(1, Some(render::Denoiser::new()));
+        let reference_parameters = parameters(16, None);
+
+        // Guides are rendered after requested output variables, without repeating ones that are already there.
+        assert_eq!(noisy_parameters.rendered_aovs(), vec![render::Aov::Depth, render::Aov::Albedo, render::Aov::Normal]);
+        assert_eq!(reference_parameters.rendered_aovs(), vec![render::Aov::Depth]);
+
+        let (noisy_image, aovs) = render::Renderer::new()
+            .set_parameters(&noisy_parameters)
+            .set_scene(&scene)
+            .render_with_aovs().expect("Rendering failed!");
+
+        let reference_image = render::Renderer::new()
+            .set_parameters(&reference_parameters)
+            .set_scene(&scene)
+            .render().expect("Rendering failed!");
+
+        // Denoised image must be closer to the one rendered with many samples.
+        let denoised_image = noisy_parameters.denoiser.unwrap().apply(&noisy_image, &noisy_parameters.rendered_aovs(), &aovs);
+
+        let error = |image: &image::Surface| image.as_pixel_slice().iter().zip(reference_image.as_pixel_slice())
+            .map(|(pixel, reference)| (*pixel - *reference).luminance().powi(2)).sum::<f32>();
+
+        assert_eq!(aovs.len(), 3);
+        assert!(error(&denoised_image) < error(&noisy_image) * 0.5, "Denoiser does not reduce noise!");
+    }
+
     #[test]
     fn diffuse()
     {
@@ -183,7 +1047,7 @@ mod examples
             image_height: 576,
             antialias_samples: 16,
             scatter_limit: 32,
-            debug_mode: Some(render::DebugMode::Diffuse),
+            integrator: render::IntegratorKind::Diffuse,
             ..render::Parameters::default()
         };
 
@@ -209,7 +1073,7 @@ mod examples
             image_height: 576,
             antialias_samples: 16,
             scatter_limit: 1,
-            debug_mode: Some(render::DebugMode::Normals),
+            integrator: render::IntegratorKind::Normals,
             ..render::Parameters::default()
         };
 
@@ -226,6 +1090,71 @@ mod examples
         save_and_test_example("normals", parameters, scene);
     }
 
+    #[test]
+    fn adaptive()
+    {
+        let parameters = render::Parameters
+        {
+            image_width: 1024,
+            image_height: 576,
+            scatter_limit: 16,
+            adaptive_sampling: Some(render::AdaptiveSampling
+            {
+                noise_threshold: 0.02,
+                min_samples: 16,
+                max_samples: 1024
+            }),
+            ..render::Parameters::default()
+        };
+
+        let camera = render::camera::Parameters::new()
+            .set_origin(Vec3::new(0.0, -0.6, 0.0))
+            .set_look_at(Some(Vec3::new(0.0, 1.0, -0.2)))
+            .set_field_of_view(55.0);
+
+        let scene = render::Scene::new()
+            .set_camera(camera)
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -100.5), 100.0, materials::Diffuse::new(Vec4::new(0.8, 0.8, 0.8, 1.0)))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(-0.5, 1.0, -0.1), 0.4, materials::Refractive::new(Vec4::new(1.0, 1.0, 1.0, 1.0), 1.5))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.5, 1.0, -0.1), 0.4, materials::Metallic::new(Vec4::new(0.8, 0.8, 0.8, 1.0), 0.2))))
+            .add_light(lights::Sphere::new(Vec3::new(0.0, 2.0, 1.5), 0.2, Vec4::new(1.0, 0.9, 0.8, 1.0), 20.0));
+
+        save_and_test_example("adaptive", parameters, scene);
+    }
+
+    #[test]
+    fn sample_count()
+    {
+        let parameters = render::Parameters
+        {
+            image_width: 1024,
+            image_height: 576,
+            scatter_limit: 16,
+            integrator: render::IntegratorKind::SampleCount,
+            adaptive_sampling: Some(render::AdaptiveSampling
+            {
+                noise_threshold: 0.02,
+                min_samples: 16,
+                max_samples: 1024
+            }),
+            ..render::Parameters::default()
+        };
+
+        let camera = render::camera::Parameters::new()
+            .set_origin(Vec3::new(0.0, -0.6, 0.0))
+            .set_look_at(Some(Vec3::new(0.0, 1.0, -0.2)))
+            .set_field_of_view(55.0);
+
+        let scene = render::Scene::new()
+            .set_camera(camera)
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.0, 1.0, -100.5), 100.0, materials::Diffuse::new(Vec4::new(0.8, 0.8, 0.8, 1.0)))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(-0.5, 1.0, -0.1), 0.4, materials::Refractive::new(Vec4::new(1.0, 1.0, 1.0, 1.0), 1.5))))
+            .add_object(Object::new(objects::Sphere::new(Vec3::new(0.5, 1.0, -0.1), 0.4, materials::Metallic::new(Vec4::new(0.8, 0.8, 0.8, 1.0), 0.2))))
+            .add_light(lights::Sphere::new(Vec3::new(0.0, 2.0, 1.5), 0.2, Vec4::new(1.0, 0.9, 0.8, 1.0), 20.0));
+
+        save_and_test_example("sample_count", parameters, scene);
+    }
+
     #[test]
     fn benchmark()
     {
@@ -62,4 +62,61 @@ mod image
 
         assert!(file_diff::diff(directory.join("output.png").to_str().unwrap(), "tests/references/write_image.png"));
     }
+
+    #[test]
+    fn read_pnm()
+    {
+        let image = image::FormatPNM::load(Path::new("tests/references/write_image.pnm")).expect("Failed to load image!");
+
+        assert_eq!(image.get_width(), 16);
+        assert_eq!(image.get_height(), 16);
+        assert!(image.as_pixel_slice().iter().all(|pixel| *pixel == Vec4::red()));
+    }
+
+    #[test]
+    fn read_png()
+    {
+        let image = image::FormatPNG::load(Path::new("tests/references/write_image.png")).expect("Failed to load image!");
+
+        assert_eq!(image.get_width(), 16);
+        assert_eq!(image.get_height(), 16);
+        assert!(image.as_pixel_slice().iter().all(|pixel| *pixel == Vec4::green()));
+    }
+
+    #[test]
+    fn read_detected()
+    {
+        // Format is detected from file contents, with sRGB colors converted to linear values.
+        let png = image::Reader::new().input("tests/references/write_image.png").load().expect("Failed to load image!");
+        let pnm = image::Reader::new().input("tests/references/write_image.pnm").convert_to_linear(false).load().expect("Failed to load image!");
+
+        assert!(png.as_pixel_slice().iter().all(|pixel| *pixel == Vec4::green()));
+        assert!(pnm.as_pixel_slice().iter().all(|pixel| *pixel == Vec4::red()));
+
+        let hdr = image::Reader::new().input("examples/environments/sky.hdr").load().expect("Failed to load image!");
+        let reference = image::FormatHDR::load(Path::new("examples/environments/sky.hdr")).expect("Failed to load image!");
+
+        assert!(hdr.as_pixel_slice() == reference.as_pixel_slice());
+        assert!(image::Reader::new().input("tests/image.rs").load().is_err());
+    }
+
+    #[test]
+    fn write_read_exr()
+    {
+        let directory = Path::new("target/tests/image/write_read_exr/");
+
+        let _ = std::fs::remove_dir_all(directory);
+        let _ = std::fs::create_dir_all(directory);
+
+        let pixels: Vec<Vec4> = (0..16 * 16).map(|index| Vec4::new(index as f32 / 16.0, 4.0, 0.0, 1.0)).collect();
+        let image = image::Surface::from(16, 16, pixels);
+
+        image::Writer::new(image::FormatEXR::new())
+            .input(&image).output(directory.join("output.exr"))
+            .save().expect("Failed to save image!");
+
+        let loaded = image::Reader::new().input(directory.join("output.exr")).load().expect("Failed to load image!");
+
+        assert!(loaded.as_pixel_slice() == image.as_pixel_slice());
+    }
 }
@@ -9,6 +9,7 @@ mod math
     use raytracer::math::Vec4;
     use raytracer::math::Ray;
     use raytracer::math::Sphere;
+    use raytracer::math::Triangle;
     use raytracer::math::Intersectable;
 
     #[test]
@@ -27,6 +28,45 @@ mod math
         assert!(sphere.intersect(&ray_backward, 0.0001, std::f32::MAX).is_none());
     }
 
+    #[test]
+    fn intersect_ray_triangle()
+    {
+        let triangle = Triangle::new(Vec3::new(-1.0, 10.0, -1.0), Vec3::new(1.0, 10.0, -1.0), Vec3::new(0.0, 10.0, 1.0));
+
+        let ray_forward = Ray::new(Vec3::new(0.0, 2.0, 0.0), Vec3::new(0.0, 1.0, 0.0), 0.0);
+        let intersection = triangle.intersect(&ray_forward, 0.0001, f32::MAX).unwrap();
+
+        assert_eq!(intersection.point, Vec3::new(0.0, 10.0, 0.0));
+        assert_eq!(intersection.normal, Vec3::new(0.0, -1.0, 0.0));
+        assert_eq!(intersection.length, 8.0);
+
+        let ray_backward = Ray::new(Vec3::new(0.0, 2.0, 0.0), Vec3::new(0.0, -1.0, 0.0), 0.0);
+        assert!(triangle.intersect(&ray_backward, 0.0001, f32::MAX).is_none());
+
+        let ray_outside = Ray::new(Vec3::new(2.0, 2.0, 0.0), Vec3::new(0.0, 1.0, 0.0), 0.0);
+        assert!(triangle.intersect(&ray_outside, 0.0001, f32::MAX).is_none());
+        assert!(triangle.intersect(&ray_forward, 0.0001, 5.0).is_none());
+    }
+
+    #[test]
+    fn intersect_ray_small_triangle()
+    {
+        // Triangle with edges of hundredths of millimetre, as found in detailed models measured in metres.
+        let triangle = Triangle::new(Vec3::new(-1.0e-5, 0.01, -1.0e-5), Vec3::new(1.0e-5, 0.01, -1.0e-5), Vec3::new(0.0, 0.01, 1.0e-5));
+
+        let ray_forward = Ray::new(Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), 0.0);
+        let intersection = triangle.intersect(&ray_forward, 0.0001, f32::MAX).unwrap();
+
+        assert!((intersection.length - 0.01).abs() < 1.0e-6);
+        assert_eq!(intersection.normal, Vec3::new(0.0, -1.0, 0.0));
+
+        let ray_parallel = Ray::new(Vec3::new(-1.0, 0.01, 0.0), Vec3::new(1.0, 0.0, 0.0), 0.0);
+        assert!(triangle.intersect(&ray_parallel, 0.0001, f32::MAX).is_none());
+
+        let ray_outside = Ray::new(Vec3::new(2.0e-5, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), 0.0);
+        assert!(triangle.intersect(&ray_outside, 0.0001, f32::MAX).is_none());
+    }
+
     #[test]
     fn serialize_vec2()
     {